@@ -1,8 +1,9 @@
 use crate::audio_feedback::{play_recording_start_sound, play_recording_stop_sound};
+use crate::engines::TranscriptionInterrupted;
 use crate::managers::audio::AudioRecordingManager;
-use crate::managers::transcription::TranscriptionManager;
-use crate::overlay::{show_recording_overlay, show_transcribing_overlay};
-use crate::settings::get_settings;
+use crate::managers::transcription::{JobSource, TranscriptionManager};
+use crate::overlay::{show_language_overlay, show_recording_overlay, show_transcribing_overlay};
+use crate::settings::{self, get_settings_for_binding};
 use crate::tray::{change_tray_icon, TrayIconState};
 use crate::utils;
 use log::debug;
@@ -11,6 +12,7 @@ use std::collections::HashMap;
 use std::sync::Arc;
 use std::time::Instant;
 use tauri::AppHandle;
+use tauri::Emitter;
 use tauri::Manager;
 
 // Shortcut Action Trait
@@ -34,8 +36,20 @@ impl ShortcutAction for TranscribeAction {
         let rm = app.state::<Arc<AudioRecordingManager>>();
 
         // Get the microphone mode to determine audio feedback timing
-        let settings = get_settings(app);
+        let settings = get_settings_for_binding(app, Some(&binding_id));
         let is_always_on = settings.always_on_microphone;
+
+        // Reload the model in the background if it was unloaded while idle, so
+        // it is ready by the time recording stops
+        if !settings.selected_model.is_empty() {
+            let tm = Arc::clone(&app.state::<Arc<TranscriptionManager>>());
+            let preload_binding_id = binding_id.clone();
+            std::thread::spawn(move || {
+                if let Err(e) = tm.preload_for_binding(Some(&preload_binding_id)) {
+                    debug!("Failed to preload model for {}: {}", preload_binding_id, e);
+                }
+            });
+        }
         debug!("Microphone mode - always_on: {}", is_always_on);
 
         if is_always_on {
@@ -44,6 +58,10 @@ impl ShortcutAction for TranscribeAction {
             play_recording_start_sound(app);
             let recording_started = rm.try_start_recording(&binding_id);
             debug!("Recording started: {}", recording_started);
+            if recording_started && settings.streaming_transcription {
+                app.state::<Arc<TranscriptionManager>>()
+                    .start_stream(&binding_id);
+            }
         } else {
             // On-demand mode: Start recording first, then play audio feedback
             // This allows the microphone to be activated before playing the sound
@@ -51,6 +69,10 @@ impl ShortcutAction for TranscribeAction {
             let recording_start_time = Instant::now();
             if rm.try_start_recording(&binding_id) {
                 debug!("Recording started in {:?}", recording_start_time.elapsed());
+                if settings.streaming_transcription {
+                    app.state::<Arc<TranscriptionManager>>()
+                        .start_stream(&binding_id);
+                }
                 // Small delay to ensure microphone stream is active
                 let app_clone = app.clone();
                 std::thread::spawn(move || {
@@ -77,6 +99,9 @@ impl ShortcutAction for TranscribeAction {
         let rm = Arc::clone(&app.state::<Arc<AudioRecordingManager>>());
         let tm = Arc::clone(&app.state::<Arc<TranscriptionManager>>());
 
+        // Detach the streaming session now so the next recording can start its own
+        let stream = tm.take_stream();
+
         change_tray_icon(app, TrayIconState::Transcribing);
         show_transcribing_overlay(app);
 
@@ -100,50 +125,75 @@ impl ShortcutAction for TranscribeAction {
                     samples.len()
                 );
 
-                let transcription_time = Instant::now();
-                match tm.transcribe(samples) {
-                    Ok(transcription) => {
-                        debug!(
-                            "Transcription completed in {:?}: '{}'",
-                            transcription_time.elapsed(),
-                            transcription
-                        );
-                        if !transcription.is_empty() {
-                            let transcription_clone = transcription.clone();
-                            let ah_clone = ah.clone();
-                            let paste_time = Instant::now();
-                            ah.run_on_main_thread(move || {
-                                match utils::paste(transcription_clone, ah_clone.clone()) {
-                                    Ok(()) => debug!(
-                                        "Text pasted successfully in {:?}",
-                                        paste_time.elapsed()
-                                    ),
-                                    Err(e) => eprintln!("Failed to paste transcription: {}", e),
+                let queued_time = Instant::now();
+
+                // Jobs complete in the order they were queued, so pastes keep the
+                // order the recordings were spoken in
+                tm.enqueue(
+                    &binding_id,
+                    JobSource::Dictation,
+                    samples,
+                    stream,
+                    Box::new(move |result| {
+                        match result {
+                            Ok(result) => {
+                                // Full result with segments and token probabilities for the UI
+                                let _ = ah.emit("transcription-completed", &result);
+                                let transcription = result.text;
+                                debug!(
+                                    "Transcription completed {:?} after queueing: '{}'",
+                                    queued_time.elapsed(),
+                                    transcription
+                                );
+                                if transcription.is_empty() {
+                                    // Tell the UI why nothing was pasted
+                                    if !result.filtered.is_empty() {
+                                        debug!(
+                                            "All {} segments were filtered as hallucinations",
+                                            result.filtered.len()
+                                        );
+                                        let _ = ah.emit("transcription-filtered", &result.filtered);
+                                    }
+                                    settle_after_transcription(&ah);
+                                    return;
                                 }
-                                // Hide the overlay after transcription is complete
-                                utils::hide_recording_overlay(&ah_clone);
-                                change_tray_icon(&ah_clone, TrayIconState::Idle);
-                            })
-                            .unwrap_or_else(|e| {
-                                eprintln!("Failed to run paste on main thread: {:?}", e);
-                                utils::hide_recording_overlay(&ah);
-                                change_tray_icon(&ah, TrayIconState::Idle);
-                            });
-                        } else {
-                            utils::hide_recording_overlay(&ah);
-                            change_tray_icon(&ah, TrayIconState::Idle);
+
+                                let ah_clone = ah.clone();
+                                let paste_time = Instant::now();
+                                ah.run_on_main_thread(move || {
+                                    match utils::paste(transcription, ah_clone.clone()) {
+                                        Ok(()) => debug!(
+                                            "Text pasted successfully in {:?}",
+                                            paste_time.elapsed()
+                                        ),
+                                        Err(e) => {
+                                            eprintln!("Failed to paste transcription: {}", e)
+                                        }
+                                    }
+                                    settle_after_transcription(&ah_clone);
+                                })
+                                .unwrap_or_else(|e| {
+                                    eprintln!("Failed to run paste on main thread: {:?}", e);
+                                    settle_after_transcription(&ah);
+                                });
+                            }
+                            Err(err) => {
+                                debug!("Global Shortcut Transcription error: {}", err);
+                                let cancelled = matches!(
+                                    err.downcast_ref::<TranscriptionInterrupted>(),
+                                    Some(TranscriptionInterrupted::Cancelled)
+                                );
+                                if !cancelled {
+                                    let _ = ah.emit("transcription-error", err.to_string());
+                                }
+                                settle_after_transcription(&ah);
+                            }
                         }
-                    }
-                    Err(err) => {
-                        debug!("Global Shortcut Transcription error: {}", err);
-                        utils::hide_recording_overlay(&ah);
-                        change_tray_icon(&ah, TrayIconState::Idle);
-                    }
-                }
+                    }),
+                );
             } else {
                 debug!("No samples retrieved from recording stop");
-                utils::hide_recording_overlay(&ah);
-                change_tray_icon(&ah, TrayIconState::Idle);
+                settle_after_transcription(&ah);
             }
         });
 
@@ -154,6 +204,80 @@ impl ShortcutAction for TranscribeAction {
     }
 }
 
+/// Puts the overlay and tray back in line with what is still going on once a
+/// recording has been handled: recording wins, then pending dictations,
+/// otherwise idle. API uploads and files decoding meanwhile don't show.
+fn settle_after_transcription(app: &AppHandle) {
+    if app.state::<Arc<AudioRecordingManager>>().is_recording() {
+        return;
+    }
+    if app
+        .state::<Arc<TranscriptionManager>>()
+        .pending_dictations()
+        > 0
+    {
+        change_tray_icon(app, TrayIconState::Transcribing);
+        show_transcribing_overlay(app);
+        return;
+    }
+    utils::hide_recording_overlay(app);
+    change_tray_icon(app, TrayIconState::Idle);
+}
+
+// Cancel Action
+struct CancelAction;
+
+impl ShortcutAction for CancelAction {
+    fn start(&self, app: &AppHandle, _binding_id: &str, _shortcut_str: &str) {
+        // The shortcut handler holds the toggle state while calling us, and
+        // cancelling needs it too, so do the work off this call stack
+        let app = app.clone();
+        std::thread::spawn(move || utils::cancel_current_operation(&app));
+    }
+
+    fn stop(&self, _app: &AppHandle, _binding_id: &str, _shortcut_str: &str) {}
+}
+
+// Cycle Language Action
+struct CycleLanguageAction;
+
+/// How long the overlay shows a newly selected language when nothing else is
+/// going on.
+const LANGUAGE_OVERLAY_DURATION: std::time::Duration = std::time::Duration::from_millis(1500);
+
+impl ShortcutAction for CycleLanguageAction {
+    fn start(&self, app: &AppHandle, _binding_id: &str, _shortcut_str: &str) {
+        let mut settings = settings::get_settings(app);
+        let favorites = &settings.favorite_languages;
+        if favorites.is_empty() {
+            debug!("No favorite languages configured, nothing to cycle");
+            return;
+        }
+
+        let next = match favorites
+            .iter()
+            .position(|l| *l == settings.selected_language)
+        {
+            Some(i) => favorites[(i + 1) % favorites.len()].clone(),
+            None => favorites[0].clone(),
+        };
+        debug!("Switching language to {}", next);
+        settings.selected_language = next.clone();
+        settings::write_settings(app, settings);
+
+        let _ = app.emit("selected-language-changed", &next);
+        show_language_overlay(app, &next);
+
+        let app = app.clone();
+        std::thread::spawn(move || {
+            std::thread::sleep(LANGUAGE_OVERLAY_DURATION);
+            settle_after_transcription(&app);
+        });
+    }
+
+    fn stop(&self, _app: &AppHandle, _binding_id: &str, _shortcut_str: &str) {}
+}
+
 // Test Action
 struct TestAction;
 
@@ -184,9 +308,27 @@ pub static ACTION_MAP: Lazy<HashMap<String, Arc<dyn ShortcutAction>>> = Lazy::ne
         "transcribe".to_string(),
         Arc::new(TranscribeAction) as Arc<dyn ShortcutAction>,
     );
+    map.insert(
+        "cancel".to_string(),
+        Arc::new(CancelAction) as Arc<dyn ShortcutAction>,
+    );
+    map.insert(
+        "cycle_language".to_string(),
+        Arc::new(CycleLanguageAction) as Arc<dyn ShortcutAction>,
+    );
     map.insert(
         "test".to_string(),
         Arc::new(TestAction) as Arc<dyn ShortcutAction>,
     );
     map
 });
+
+/// Looks up the action a binding triggers.
+pub fn action_for_binding(app: &AppHandle, binding_id: &str) -> Option<Arc<dyn ShortcutAction>> {
+    let bindings = crate::settings::get_bindings(app);
+    let action_id = bindings
+        .get(binding_id)
+        .map(|b| b.action_id().to_string())
+        .unwrap_or_else(|| binding_id.to_string());
+    ACTION_MAP.get(&action_id).cloned()
+}
@@ -0,0 +1,194 @@
+use anyhow::Result;
+use std::{io::Cursor, path::Path, time::Duration};
+use symphonia::core::{
+    audio::SampleBuffer,
+    codecs::{DecoderOptions, CODEC_TYPE_NULL},
+    errors::Error as SymphoniaError,
+    formats::FormatOptions,
+    io::{MediaSource, MediaSourceStream},
+    meta::MetadataOptions,
+    probe::Hint,
+};
+
+use crate::audio_toolkit::{audio::FrameResampler, constants};
+
+/// Decodes an audio file (WAV, FLAC, MP3, OGG/Vorbis) into 16 kHz mono samples
+/// ready for transcription.
+pub fn read_audio_file<P: AsRef<Path>>(path: P) -> Result<Vec<f32>> {
+    let path = path.as_ref();
+    let file = std::fs::File::open(path)
+        .map_err(|e| anyhow::anyhow!("Failed to open {:?}: {}", path, e))?;
+    let extension = path.extension().and_then(|e| e.to_str());
+    decode_audio(Box::new(file), extension, &format!("{:?}", path))
+}
+
+/// Decodes audio held in memory, e.g. an upload, like `read_audio_file`.
+/// `extension` is a hint at the format, such as "wav" or "mp3".
+pub fn read_audio_bytes(bytes: Vec<u8>, extension: Option<&str>) -> Result<Vec<f32>> {
+    decode_audio(Box::new(Cursor::new(bytes)), extension, "the audio")
+}
+
+/// `name` describes the source in error messages.
+fn decode_audio(
+    source: Box<dyn MediaSource>,
+    extension: Option<&str>,
+    name: &str,
+) -> Result<Vec<f32>> {
+    let mss = MediaSourceStream::new(source, Default::default());
+
+    let mut hint = Hint::new();
+    if let Some(ext) = extension {
+        hint.with_extension(ext);
+    }
+
+    let probed = symphonia::default::get_probe()
+        .format(
+            &hint,
+            mss,
+            &FormatOptions::default(),
+            &MetadataOptions::default(),
+        )
+        .map_err(|e| anyhow::anyhow!("Unsupported audio format: {}", e))?;
+    let mut format = probed.format;
+
+    let track = format
+        .tracks()
+        .iter()
+        .find(|t| t.codec_params.codec != CODEC_TYPE_NULL)
+        .ok_or_else(|| anyhow::anyhow!("No audio track found in {}", name))?;
+    let track_id = track.id;
+    let codec_params = track.codec_params.clone();
+
+    let in_sample_rate = codec_params
+        .sample_rate
+        .ok_or_else(|| anyhow::anyhow!("Unknown sample rate in {}", name))?;
+
+    let mut decoder = symphonia::default::get_codecs()
+        .make(&codec_params, &DecoderOptions::default())
+        .map_err(|e| anyhow::anyhow!("Unsupported codec: {}", e))?;
+
+    let mut resampler = FrameResampler::new(
+        in_sample_rate as usize,
+        constants::WHISPER_SAMPLE_RATE as usize,
+        Duration::from_millis(30),
+    );
+    let mut samples = Vec::new();
+
+    loop {
+        let packet = match format.next_packet() {
+            Ok(packet) => packet,
+            // End of stream
+            Err(SymphoniaError::IoError(e)) if e.kind() == std::io::ErrorKind::UnexpectedEof => {
+                break
+            }
+            Err(SymphoniaError::ResetRequired) => break,
+            Err(e) => return Err(anyhow::anyhow!("Failed to read audio packet: {}", e)),
+        };
+
+        if packet.track_id() != track_id {
+            continue;
+        }
+
+        let decoded = match decoder.decode(&packet) {
+            Ok(decoded) => decoded,
+            // Corrupt frames are skipped rather than failing the whole file
+            Err(SymphoniaError::DecodeError(e)) => {
+                eprintln!("Skipping undecodable audio packet: {}", e);
+                continue;
+            }
+            Err(e) => return Err(anyhow::anyhow!("Failed to decode audio: {}", e)),
+        };
+
+        let spec = *decoded.spec();
+        let channels = spec.channels.count().max(1);
+        let mut buf = SampleBuffer::<f32>::new(decoded.capacity() as u64, spec);
+        buf.copy_interleaved_ref(decoded);
+
+        let mono: Vec<f32> = buf
+            .samples()
+            .chunks_exact(channels)
+            .map(|frame| frame.iter().sum::<f32>() / channels as f32)
+            .collect();
+
+        resampler.push(&mono, |frame: &[f32]| samples.extend_from_slice(frame));
+    }
+
+    resampler.finish(|frame: &[f32]| samples.extend_from_slice(frame));
+
+    Ok(samples)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A 16-bit WAV file holding `frames`, each with a sample per channel.
+    fn wav(sample_rate: u32, frames: &[Vec<f32>]) -> Vec<u8> {
+        let spec = hound::WavSpec {
+            channels: frames[0].len() as u16,
+            sample_rate,
+            bits_per_sample: 16,
+            sample_format: hound::SampleFormat::Int,
+        };
+        let mut buffer = Cursor::new(Vec::new());
+        let mut writer = hound::WavWriter::new(&mut buffer, spec).unwrap();
+        for frame in frames {
+            for &sample in frame {
+                writer
+                    .write_sample((sample * i16::MAX as f32) as i16)
+                    .unwrap();
+            }
+        }
+        writer.finalize().unwrap();
+        buffer.into_inner()
+    }
+
+    #[test]
+    fn wav_at_the_whisper_rate_is_kept() {
+        let samples: Vec<f32> = (0..16_000).map(|i| (i % 100) as f32 / 200.0).collect();
+        let frames: Vec<Vec<f32>> = samples.iter().map(|&s| vec![s]).collect();
+
+        let decoded = read_audio_bytes(wav(16_000, &frames), Some("wav")).unwrap();
+
+        // The last 30 ms frame is padded with silence
+        assert!((samples.len()..samples.len() + 480).contains(&decoded.len()));
+        for (decoded, original) in decoded.iter().zip(&samples) {
+            assert!((decoded - original).abs() < 1e-3);
+        }
+        assert!(decoded[samples.len()..].iter().all(|&s| s == 0.0));
+    }
+
+    #[test]
+    fn stereo_is_mixed_down_and_resampled() {
+        // Two seconds at 48 kHz, the channels averaging to a constant 0.3
+        let frames = vec![vec![0.5, 0.1]; 96_000];
+
+        let decoded = read_audio_bytes(wav(48_000, &frames), Some("wav")).unwrap();
+
+        let expected = 32_000;
+        assert!(
+            decoded.len().abs_diff(expected) < 1_000,
+            "{} samples",
+            decoded.len()
+        );
+        // Away from the edges the resampler passes the level through
+        let middle = &decoded[8_000..24_000];
+        assert!(middle.iter().all(|s| (s - 0.3).abs() < 0.01));
+    }
+
+    #[test]
+    fn unknown_data_is_rejected() {
+        let err = read_audio_bytes(b"definitely not audio".to_vec(), None).unwrap_err();
+        assert!(
+            err.to_string().starts_with("Unsupported audio format"),
+            "{}",
+            err
+        );
+    }
+
+    #[test]
+    fn missing_files_are_reported() {
+        let err = read_audio_file("/nonexistent/memo.mp3").unwrap_err();
+        assert!(err.to_string().contains("memo.mp3"), "{}", err);
+    }
+}
@@ -1,10 +1,12 @@
 // Re-export all audio components
 mod device;
+mod file;
 mod recorder;
 mod resampler;
 mod visualizer;
 
 pub use device::{list_input_devices, list_output_devices, CpalDeviceInfo};
+pub use file::{read_audio_bytes, read_audio_file};
 pub use recorder::AudioRecorder;
 pub use resampler::FrameResampler;
 pub use visualizer::AudioVisualiser;
@@ -0,0 +1,260 @@
+//! Measures how fast and accurate each model is on this machine, by
+//! transcribing a reference clip whose transcript is known.
+
+use crate::audio_toolkit::audio::read_audio_file;
+use crate::audio_toolkit::constants::WHISPER_SAMPLE_RATE;
+use crate::engines::{
+    create_engine, load_with_cpu_fallback, CancellationToken, ComputeBackend, DecoderSettings,
+    EngineKind, TranscribeOptions,
+};
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+/// Bundled clip and its transcript, relative to the resource directory.
+pub const REFERENCE_CLIP: &str = "resources/benchmark/reference.wav";
+pub const REFERENCE_TRANSCRIPT: &str = "resources/benchmark/reference.txt";
+
+/// Language spoken in the reference clip.
+const REFERENCE_LANGUAGE: &str = "en";
+
+/// How often process memory is sampled while a model loads and decodes.
+const MEMORY_SAMPLE_INTERVAL: Duration = Duration::from_millis(50);
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BenchmarkResult {
+    pub model_id: String,
+    pub backend: ComputeBackend,
+    pub load_ms: u64,
+    /// Decode time divided by the clip length, below 1 is faster than real time
+    pub real_time_factor: f32,
+    /// Memory the process grew by while the model loaded and decoded
+    pub peak_memory_mb: u64,
+    /// Word error rate against the reference transcript, 0 is a perfect match
+    pub word_error_rate: f32,
+    /// Seconds since the Unix epoch
+    pub measured_at: u64,
+}
+
+/// The reference clip as 16 kHz samples, with its transcript.
+pub struct ReferenceClip {
+    pub samples: Vec<f32>,
+    pub transcript: String,
+}
+
+impl ReferenceClip {
+    pub fn load(clip: &Path, transcript: &Path) -> Result<Self> {
+        let samples = read_audio_file(clip)?;
+        let transcript = std::fs::read_to_string(transcript)
+            .map_err(|e| anyhow::anyhow!("Failed to read {:?}: {}", transcript, e))?;
+        Ok(Self {
+            samples,
+            transcript: transcript.trim().to_string(),
+        })
+    }
+
+    fn duration(&self) -> Duration {
+        Duration::from_secs_f64(self.samples.len() as f64 / WHISPER_SAMPLE_RATE as f64)
+    }
+}
+
+/// Lowercase words with punctuation removed, so only recognition errors count.
+fn normalize_words(text: &str) -> Vec<String> {
+    text.split_whitespace()
+        .map(|word| {
+            word.chars()
+                .filter(|c| c.is_alphanumeric() || *c == '\'')
+                .flat_map(|c| c.to_lowercase())
+                .collect::<String>()
+        })
+        .filter(|word| !word.is_empty())
+        .collect()
+}
+
+/// Word-level edit distance divided by the number of reference words.
+pub fn word_error_rate(reference: &str, hypothesis: &str) -> f32 {
+    let reference = normalize_words(reference);
+    let hypothesis = normalize_words(hypothesis);
+    if reference.is_empty() {
+        return if hypothesis.is_empty() { 0.0 } else { 1.0 };
+    }
+
+    let mut previous: Vec<usize> = (0..=hypothesis.len()).collect();
+    for (i, ref_word) in reference.iter().enumerate() {
+        let mut current = vec![i + 1; hypothesis.len() + 1];
+        for (j, hyp_word) in hypothesis.iter().enumerate() {
+            let substitution = previous[j] + usize::from(ref_word != hyp_word);
+            current[j + 1] = substitution.min(previous[j + 1] + 1).min(current[j] + 1);
+        }
+        previous = current;
+    }
+
+    previous[hypothesis.len()] as f32 / reference.len() as f32
+}
+
+/// Tracks the highest resident memory of this process on a background thread.
+struct MemorySampler {
+    stop: Arc<AtomicBool>,
+    handle: std::thread::JoinHandle<u64>,
+}
+
+impl MemorySampler {
+    fn start() -> Self {
+        let stop = Arc::new(AtomicBool::new(false));
+        let stop_flag = Arc::clone(&stop);
+        let handle = std::thread::spawn(move || {
+            let Ok(pid) = sysinfo::get_current_pid() else {
+                return 0;
+            };
+            let mut system = sysinfo::System::new();
+            let mut peak = 0;
+            loop {
+                if system.refresh_process(pid) {
+                    if let Some(process) = system.process(pid) {
+                        peak = peak.max(process.memory());
+                    }
+                }
+                if stop_flag.load(Ordering::SeqCst) {
+                    return peak;
+                }
+                std::thread::sleep(MEMORY_SAMPLE_INTERVAL);
+            }
+        });
+        Self { stop, handle }
+    }
+
+    /// Stops sampling and returns the peak in bytes.
+    fn finish(self) -> u64 {
+        self.stop.store(true, Ordering::SeqCst);
+        self.handle.join().unwrap_or(0)
+    }
+}
+
+fn current_memory() -> u64 {
+    let Ok(pid) = sysinfo::get_current_pid() else {
+        return 0;
+    };
+    let mut system = sysinfo::System::new();
+    system.refresh_process(pid);
+    system.process(pid).map_or(0, |p| p.memory())
+}
+
+/// Fails when the model file is larger than the free memory, loading it
+/// would push the system into swap and skew the numbers anyway.
+fn check_memory(model_path: &Path) -> Result<()> {
+    let model_mb = std::fs::metadata(model_path)
+        .map_err(|e| anyhow::anyhow!("Failed to read {:?}: {}", model_path, e))?
+        .len()
+        / (1024 * 1024);
+    let mut system = sysinfo::System::new();
+    system.refresh_memory();
+    let available_mb = system.available_memory() / (1024 * 1024);
+    if model_mb > available_mb {
+        return Err(anyhow::anyhow!(
+            "Not enough free memory to load the model ({}MB needed, {}MB available)",
+            model_mb,
+            available_mb
+        ));
+    }
+    Ok(())
+}
+
+/// Loads the model at `model_path` in a fresh engine, transcribes the clip
+/// and measures the run. The engine is dropped afterwards. A model loaded
+/// elsewhere in the process stays in memory next to it, unload it first.
+pub fn run_benchmark(
+    model_id: &str,
+    model_path: &Path,
+    engine: EngineKind,
+    clip: &ReferenceClip,
+    decoder: DecoderSettings,
+) -> Result<BenchmarkResult> {
+    check_memory(model_path)?;
+    let baseline = current_memory();
+    let sampler = MemorySampler::start();
+
+    let mut engine = create_engine(engine);
+    let load_started = Instant::now();
+    let backend = load_with_cpu_fallback(engine.as_mut(), model_path, &decoder.load_options());
+    let load_time = load_started.elapsed();
+    let backend = match backend {
+        Ok(backend) => backend,
+        Err(e) => {
+            sampler.finish();
+            return Err(e);
+        }
+    };
+
+    let options = TranscribeOptions {
+        language: REFERENCE_LANGUAGE.to_string(),
+        detect_languages: Vec::new(),
+        translate: false,
+        initial_prompt: None,
+        cancel: CancellationToken::default(),
+        timeout: None,
+        no_speech_probability: false,
+        decoder,
+    };
+    let decode_started = Instant::now();
+    let result = engine.transcribe(&clip.samples, &options);
+    let decode_time = decode_started.elapsed();
+    engine.unload();
+    let peak = sampler.finish();
+    let result = result?;
+
+    let measured_at = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |d| d.as_secs());
+
+    Ok(BenchmarkResult {
+        model_id: model_id.to_string(),
+        backend,
+        load_ms: load_time.as_millis() as u64,
+        real_time_factor: decode_time.as_secs_f32() / clip.duration().as_secs_f32().max(0.001),
+        peak_memory_mb: peak.saturating_sub(baseline) / (1024 * 1024),
+        word_error_rate: word_error_rate(&clip.transcript, &result.text),
+        measured_at,
+    })
+}
+
+/// Where results are cached between runs.
+pub fn cache_path(models_dir: &Path) -> PathBuf {
+    models_dir.join("benchmarks.json")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn words_are_compared_without_case_and_punctuation() {
+        assert_eq!(
+            normalize_words("Hello, World! It's  fine."),
+            ["hello", "world", "it's", "fine"]
+        );
+        assert!(normalize_words(" -- ... ").is_empty());
+    }
+
+    #[test]
+    fn word_error_rate_counts_edits_per_reference_word() {
+        let reference = "the quick brown fox";
+        assert_eq!(word_error_rate(reference, "The quick, brown fox."), 0.0);
+        // One substitution
+        assert_eq!(word_error_rate(reference, "the quick brown box"), 0.25);
+        // One deletion and one insertion
+        assert_eq!(word_error_rate(reference, "quick brown fox jumps"), 0.5);
+        // Everything missing
+        assert_eq!(word_error_rate(reference, ""), 1.0);
+        // More insertions than reference words go above 1
+        assert_eq!(word_error_rate("yes", "no no no"), 3.0);
+    }
+
+    #[test]
+    fn empty_reference() {
+        assert_eq!(word_error_rate("", ""), 0.0);
+        assert_eq!(word_error_rate("", "anything"), 1.0);
+    }
+}
@@ -0,0 +1,281 @@
+//! Headless entry points that run without starting the Tauri app, e.g.
+//! `handy transcribe memo.mp3 --model ggml-small.bin --format srt` or
+//! `handy benchmark ggml-small.bin ggml-large-v3-turbo.bin`.
+
+use crate::audio_toolkit::audio::read_audio_file;
+use crate::benchmark::{self, ReferenceClip};
+use crate::engines::{
+    create_engine, load_with_cpu_fallback, CancellationToken, DecoderSettings, EngineKind,
+    TranscribeOptions,
+};
+use crate::file_transcription::{format_result, transcribe_in_chunks, OutputFormat};
+use crate::hallucination_filter::HallucinationFilter;
+use anyhow::Result;
+use std::io::Write;
+use std::path::PathBuf;
+
+const TRANSCRIBE_USAGE: &str = "Usage: handy transcribe <audio-file> --model <model-file> \
+[--format text|srt|vtt|json] [--language <code>] [--translate] [--cpu] [--output <file>]";
+
+const BENCHMARK_USAGE: &str = "Usage: handy benchmark <model-file>... \
+[--clip <audio-file> --reference <transcript-file>] [--cpu]";
+
+/// Returns `Some(exit_code)` when the arguments name a CLI subcommand, `None`
+/// when the GUI should start as usual.
+pub fn try_run(args: &[String]) -> Option<i32> {
+    let command = match args.get(1).map(String::as_str) {
+        Some(command @ ("transcribe" | "benchmark")) => command,
+        _ => return None,
+    };
+    attach_parent_console();
+
+    let (result, usage) = if command == "transcribe" {
+        (run_transcribe(&args[2..]), TRANSCRIBE_USAGE)
+    } else {
+        (run_benchmark(&args[2..]), BENCHMARK_USAGE)
+    };
+    Some(match result {
+        Ok(()) => 0,
+        Err(e) => {
+            eprintln!("Error: {}", e);
+            eprintln!("{}", usage);
+            1
+        }
+    })
+}
+
+/// Release builds on Windows are GUI programs that start without a console,
+/// so anything printed would be lost. Borrow the console of the shell the
+/// command was run from.
+#[cfg(windows)]
+fn attach_parent_console() {
+    const ATTACH_PARENT_PROCESS: u32 = u32::MAX;
+
+    #[link(name = "kernel32")]
+    extern "system" {
+        fn AttachConsole(process_id: u32) -> i32;
+    }
+
+    // Fails when there is no parent console or one is attached already, e.g.
+    // in debug builds, and the output then goes where it would have anyway
+    unsafe {
+        AttachConsole(ATTACH_PARENT_PROCESS);
+    }
+}
+
+#[cfg(not(windows))]
+fn attach_parent_console() {}
+
+struct TranscribeArgs {
+    input: PathBuf,
+    model: PathBuf,
+    format: OutputFormat,
+    language: String,
+    translate: bool,
+    /// Skip the GPU even when it would work
+    cpu: bool,
+    output: Option<PathBuf>,
+}
+
+fn parse_transcribe_args(args: &[String]) -> Result<TranscribeArgs> {
+    let mut input = None;
+    let mut model = None;
+    let mut format = OutputFormat::Text;
+    let mut language = "auto".to_string();
+    let mut translate = false;
+    let mut cpu = false;
+    let mut output = None;
+
+    let mut iter = args.iter();
+    while let Some(arg) = iter.next() {
+        let mut value = |name: &str| {
+            iter.next()
+                .cloned()
+                .ok_or_else(|| anyhow::anyhow!("Missing value for {}", name))
+        };
+        match arg.as_str() {
+            "--model" | "-m" => model = Some(PathBuf::from(value("--model")?)),
+            "--format" | "-f" => format = value("--format")?.parse()?,
+            "--language" | "-l" => language = value("--language")?,
+            "--output" | "-o" => output = Some(PathBuf::from(value("--output")?)),
+            "--translate" => translate = true,
+            "--cpu" => cpu = true,
+            other if other.starts_with('-') => {
+                return Err(anyhow::anyhow!("Unknown option: {}", other))
+            }
+            other => input = Some(PathBuf::from(other)),
+        }
+    }
+
+    Ok(TranscribeArgs {
+        input: input.ok_or_else(|| anyhow::anyhow!("No input file given"))?,
+        model: model.ok_or_else(|| anyhow::anyhow!("No model file given"))?,
+        format,
+        language,
+        translate,
+        cpu,
+        output,
+    })
+}
+
+fn run_transcribe(args: &[String]) -> Result<()> {
+    let args = parse_transcribe_args(args)?;
+
+    let samples = read_audio_file(&args.input)?;
+
+    let mut engine = create_engine(EngineKind::Whisper);
+    let decoder = DecoderSettings {
+        use_gpu: !args.cpu,
+        ..DecoderSettings::default()
+    };
+    let backend = load_with_cpu_fallback(engine.as_mut(), &args.model, &decoder.load_options())?;
+    eprintln!("Running on the {}", backend);
+
+    let options = TranscribeOptions {
+        language: args.language.clone(),
+        detect_languages: Vec::new(),
+        translate: args.translate,
+        initial_prompt: None,
+        cancel: CancellationToken::default(),
+        timeout: None,
+        no_speech_probability: true,
+        decoder,
+    };
+
+    let filter = HallucinationFilter::new(&[]);
+
+    let result = transcribe_in_chunks(
+        &samples,
+        |chunk| {
+            let result = engine.transcribe(&chunk, &options)?;
+            let mut result = filter.apply(result, &chunk);
+            result.text = result.text.trim().to_string();
+            Ok(result)
+        },
+        |processed, total| {
+            eprint!(
+                "\rTranscribing... {:.0}%",
+                processed as f64 / total.max(1) as f64 * 100.0
+            );
+        },
+    )?;
+    eprintln!();
+
+    let output = format_result(&result, args.format)?;
+    match args.output {
+        Some(path) => std::fs::write(&path, output)?,
+        None => std::io::stdout().write_all(output.as_bytes())?,
+    }
+
+    Ok(())
+}
+
+struct BenchmarkArgs {
+    models: Vec<PathBuf>,
+    clip: Option<PathBuf>,
+    reference: Option<PathBuf>,
+    cpu: bool,
+}
+
+fn parse_benchmark_args(args: &[String]) -> Result<BenchmarkArgs> {
+    let mut models = Vec::new();
+    let mut clip = None;
+    let mut reference = None;
+    let mut cpu = false;
+
+    let mut iter = args.iter();
+    while let Some(arg) = iter.next() {
+        let mut value = |name: &str| {
+            iter.next()
+                .cloned()
+                .ok_or_else(|| anyhow::anyhow!("Missing value for {}", name))
+        };
+        match arg.as_str() {
+            "--clip" => clip = Some(PathBuf::from(value("--clip")?)),
+            "--reference" => reference = Some(PathBuf::from(value("--reference")?)),
+            "--cpu" => cpu = true,
+            other if other.starts_with('-') => {
+                return Err(anyhow::anyhow!("Unknown option: {}", other))
+            }
+            other => models.push(PathBuf::from(other)),
+        }
+    }
+
+    if models.is_empty() {
+        return Err(anyhow::anyhow!("No model files given"));
+    }
+    if clip.is_some() != reference.is_some() {
+        return Err(anyhow::anyhow!(
+            "--clip and --reference must be given together"
+        ));
+    }
+
+    Ok(BenchmarkArgs {
+        models,
+        clip,
+        reference,
+        cpu,
+    })
+}
+
+/// Finds a file bundled under the app's resources, next to the executable in
+/// an installed app or in the source tree in a development build.
+fn find_bundled_resource(relative: &str) -> Option<PathBuf> {
+    let mut candidates = vec![PathBuf::from(env!("CARGO_MANIFEST_DIR")).join(relative)];
+    if let Some(exe_dir) = std::env::current_exe()
+        .ok()
+        .and_then(|exe| exe.parent().map(PathBuf::from))
+    {
+        candidates.push(exe_dir.join(relative));
+        // macOS app bundles keep resources in Contents/Resources
+        candidates.push(exe_dir.join("../Resources").join(relative));
+        // Linux packages install them under /usr/lib/<app>
+        candidates.push(exe_dir.join("../lib/handy").join(relative));
+    }
+    candidates.into_iter().find(|path| path.exists())
+}
+
+fn run_benchmark(args: &[String]) -> Result<()> {
+    let args = parse_benchmark_args(args)?;
+
+    let (clip_path, reference_path) = match (args.clip, args.reference) {
+        (Some(clip), Some(reference)) => (clip, reference),
+        _ => (
+            find_bundled_resource(benchmark::REFERENCE_CLIP)
+                .ok_or_else(|| anyhow::anyhow!("Bundled reference clip not found"))?,
+            find_bundled_resource(benchmark::REFERENCE_TRANSCRIPT)
+                .ok_or_else(|| anyhow::anyhow!("Bundled reference transcript not found"))?,
+        ),
+    };
+    let clip = ReferenceClip::load(&clip_path, &reference_path)?;
+
+    let decoder = DecoderSettings {
+        use_gpu: !args.cpu,
+        ..DecoderSettings::default()
+    };
+
+    println!(
+        "{:<32} {:>7} {:>10} {:>8} {:>10} {:>7}",
+        "model", "backend", "load ms", "RTF", "memory MB", "WER"
+    );
+    for model in &args.models {
+        let name = model.file_name().map_or_else(
+            || model.display().to_string(),
+            |n| n.to_string_lossy().to_string(),
+        );
+        match benchmark::run_benchmark(&name, model, EngineKind::Whisper, &clip, decoder) {
+            Ok(result) => println!(
+                "{:<32} {:>7} {:>10} {:>8.2} {:>10} {:>6.1}%",
+                name,
+                result.backend.to_string(),
+                result.load_ms,
+                result.real_time_factor,
+                result.peak_memory_mb,
+                result.word_error_rate * 100.0
+            ),
+            Err(e) => eprintln!("{:<32} failed: {}", name, e),
+        }
+    }
+
+    Ok(())
+}
@@ -0,0 +1,87 @@
+use crate::managers::history::{ExportFormat, HistoryEntry, HistoryManager};
+use crate::utils;
+use std::sync::Arc;
+use tauri::{AppHandle, State};
+use tauri_plugin_clipboard_manager::ClipboardExt;
+
+#[tauri::command]
+pub async fn get_history_entries(
+    history_manager: State<'_, Arc<HistoryManager>>,
+    offset: Option<usize>,
+    limit: Option<usize>,
+) -> Result<Vec<HistoryEntry>, String> {
+    Ok(history_manager.list_entries(offset.unwrap_or(0), limit))
+}
+
+#[tauri::command]
+pub async fn search_history(
+    history_manager: State<'_, Arc<HistoryManager>>,
+    query: String,
+) -> Result<Vec<HistoryEntry>, String> {
+    Ok(history_manager.search_entries(&query))
+}
+
+#[tauri::command]
+pub async fn delete_history_entry(
+    history_manager: State<'_, Arc<HistoryManager>>,
+    id: u64,
+) -> Result<(), String> {
+    history_manager.delete_entry(id).map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn clear_history(history_manager: State<'_, Arc<HistoryManager>>) -> Result<(), String> {
+    history_manager.clear().map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn copy_history_entry(
+    app: AppHandle,
+    history_manager: State<'_, Arc<HistoryManager>>,
+    id: u64,
+) -> Result<(), String> {
+    let entry = history_manager
+        .get_entry(id)
+        .ok_or_else(|| format!("History entry not found: {}", id))?;
+
+    app.clipboard()
+        .write_text(&entry.text)
+        .map_err(|e| format!("Failed to write to clipboard: {}", e))
+}
+
+#[tauri::command]
+pub async fn repaste_history_entry(
+    app: AppHandle,
+    history_manager: State<'_, Arc<HistoryManager>>,
+    id: u64,
+) -> Result<(), String> {
+    let entry = history_manager
+        .get_entry(id)
+        .ok_or_else(|| format!("History entry not found: {}", id))?;
+
+    let ah = app.clone();
+    app.run_on_main_thread(move || {
+        if let Err(e) = utils::paste(entry.text, ah) {
+            eprintln!("Failed to re-paste history entry: {}", e);
+        }
+    })
+    .map_err(|e| format!("Failed to run paste on main thread: {}", e))
+}
+
+/// Exports the whole history and returns the rendered document. When `path` is
+/// given the document is also written to that file.
+#[tauri::command]
+pub async fn export_history(
+    history_manager: State<'_, Arc<HistoryManager>>,
+    format: ExportFormat,
+    path: Option<String>,
+) -> Result<String, String> {
+    let output = history_manager.export(format).map_err(|e| e.to_string())?;
+
+    if let Some(path) = path {
+        std::fs::write(&path, &output)
+            .map_err(|e| format!("Failed to write history export to {}: {}", path, e))?;
+    }
+
+    Ok(output)
+}
@@ -1,8 +1,11 @@
+use crate::benchmark::{self, BenchmarkResult, ReferenceClip};
+use crate::engines::RemoteModelConfig;
 use crate::managers::model::{ModelInfo, ModelManager};
-use crate::managers::transcription::TranscriptionManager;
-use crate::settings::{get_settings, write_settings};
+use crate::managers::transcription::{TranscriptionManager, TranscriptionModelStatus};
+use crate::settings::{decoder_settings_for_model, get_settings, write_settings};
+use serde::Serialize;
 use std::sync::Arc;
-use tauri::{AppHandle, State};
+use tauri::{AppHandle, Emitter, Manager, State};
 
 #[tauri::command]
 pub async fn get_available_models(
@@ -40,6 +43,37 @@ pub async fn delete_model(
         .map_err(|e| e.to_string())
 }
 
+#[tauri::command]
+pub async fn get_remote_model(
+    model_manager: State<'_, Arc<ModelManager>>,
+    model_id: String,
+) -> Result<Option<RemoteModelConfig>, String> {
+    Ok(model_manager.get_remote_model(&model_id))
+}
+
+/// Adds a model transcribed by a server, or updates one. A loaded remote model
+/// picks up the new configuration right away.
+#[tauri::command]
+pub async fn save_remote_model(
+    model_manager: State<'_, Arc<ModelManager>>,
+    transcription_manager: State<'_, Arc<TranscriptionManager>>,
+    model_id: String,
+    config: RemoteModelConfig,
+) -> Result<(), String> {
+    model_manager
+        .save_remote_model(&model_id, &config)
+        .map_err(|e| e.to_string())?;
+
+    if transcription_manager.is_model_loaded()
+        && transcription_manager.get_current_model().as_deref() == Some(model_id.as_str())
+    {
+        transcription_manager
+            .load_model(&model_id)
+            .map_err(|e| e.to_string())?;
+    }
+    Ok(())
+}
+
 #[tauri::command]
 pub async fn set_active_model(
     app_handle: AppHandle,
@@ -78,8 +112,8 @@ pub async fn get_current_model(app_handle: AppHandle) -> Result<String, String>
 #[tauri::command]
 pub async fn get_transcription_model_status(
     transcription_manager: State<'_, Arc<TranscriptionManager>>,
-) -> Result<Option<String>, String> {
-    Ok(transcription_manager.get_current_model())
+) -> Result<Option<TranscriptionModelStatus>, String> {
+    Ok(transcription_manager.get_model_status())
 }
 
 #[tauri::command]
@@ -123,3 +157,91 @@ pub async fn get_recommended_first_model() -> Result<String, String> {
     // Recommend small model for first-time users
     Ok("small".to_string())
 }
+
+#[derive(Clone, Debug, Serialize)]
+pub struct BenchmarkProgressEvent {
+    pub model_id: String,
+    /// Models finished so far
+    pub completed: usize,
+    pub total: usize,
+}
+
+/// Benchmarks the given models, or every downloaded one, on the bundled
+/// reference clip. Results are cached and show up on `ModelInfo`. Models that
+/// fail are skipped, progress is reported through `benchmark-progress`. The
+/// transcription model is unloaded meanwhile so two models never compete for
+/// memory, and loaded again afterwards.
+#[tauri::command]
+pub async fn run_model_benchmark(
+    app_handle: AppHandle,
+    model_ids: Option<Vec<String>>,
+) -> Result<Vec<BenchmarkResult>, String> {
+    let resolve = |path: &str| {
+        app_handle
+            .path()
+            .resolve(path, tauri::path::BaseDirectory::Resource)
+            .map_err(|e| format!("Failed to resolve {}: {}", path, e))
+    };
+    let clip_path = resolve(benchmark::REFERENCE_CLIP)?;
+    let transcript_path = resolve(benchmark::REFERENCE_TRANSCRIPT)?;
+    let model_manager = Arc::clone(&app_handle.state::<Arc<ModelManager>>());
+    let tm = Arc::clone(&app_handle.state::<Arc<TranscriptionManager>>());
+
+    // Loading and decoding are blocking, keep them off the async runtime
+    tauri::async_runtime::spawn_blocking(move || {
+        let clip = ReferenceClip::load(&clip_path, &transcript_path).map_err(|e| e.to_string())?;
+        let settings = get_settings(&app_handle);
+
+        let active_model = tm
+            .is_model_loaded()
+            .then(|| tm.get_current_model())
+            .flatten();
+        tm.unload_model("benchmarking models");
+
+        let models: Vec<ModelInfo> = model_manager
+            .get_available_models()
+            .into_iter()
+            .filter(|m| m.is_downloaded)
+            .filter(|m| model_ids.as_ref().map_or(true, |ids| ids.contains(&m.id)))
+            .collect();
+
+        let mut results = Vec::new();
+        for (index, model) in models.iter().enumerate() {
+            let _ = app_handle.emit(
+                "benchmark-progress",
+                BenchmarkProgressEvent {
+                    model_id: model.id.clone(),
+                    completed: index,
+                    total: models.len(),
+                },
+            );
+
+            let mut decoder = decoder_settings_for_model(&settings, &model.id);
+            decoder.use_gpu &= !settings.force_cpu;
+
+            let result = model_manager.get_model_path(&model.id).and_then(|path| {
+                benchmark::run_benchmark(&model.id, &path, model.engine, &clip, decoder)
+            });
+            match result {
+                Ok(result) => {
+                    println!("Benchmarked {}: {:?}", model.id, result);
+                    if let Err(e) = model_manager.record_benchmark(result.clone()) {
+                        eprintln!("Failed to cache benchmark of {}: {}", model.id, e);
+                    }
+                    results.push(result);
+                }
+                Err(e) => eprintln!("Failed to benchmark {}: {}", model.id, e),
+            }
+        }
+
+        if let Some(model_id) = active_model {
+            if let Err(e) = tm.load_model(&model_id) {
+                eprintln!("Failed to reload {} after benchmarking: {}", model_id, e);
+            }
+        }
+
+        Ok(results)
+    })
+    .await
+    .map_err(|e| format!("Benchmark task failed: {}", e))?
+}
@@ -0,0 +1,123 @@
+use crate::audio_toolkit::audio::read_audio_file;
+use crate::file_transcription::{format_result, progress_event, OutputFormat};
+use crate::llm_refinement;
+use crate::managers::transcription::TranscriptionManager;
+use crate::settings::{get_settings, get_settings_for_binding};
+use crate::text_processing::{validate_chain, ProcessedStep, ProcessingChain, TextPipeline};
+use serde::Serialize;
+use std::sync::{mpsc, Arc};
+use tauri::{AppHandle, Emitter, Manager};
+
+/// Transcribes an audio file from disk with the loaded model and returns it in
+/// the requested format. The file waits in the transcription queue behind
+/// recordings made before it. Progress is reported through
+/// `file-transcription-progress` events. When `output_path` is given the
+/// result is also written there.
+#[tauri::command]
+pub async fn transcribe_file(
+    app: AppHandle,
+    path: String,
+    format: OutputFormat,
+    output_path: Option<String>,
+    binding_id: Option<String>,
+) -> Result<String, String> {
+    let tm = Arc::clone(&app.state::<Arc<TranscriptionManager>>());
+
+    // Decoding and waiting for the queue are blocking, keep them off the async runtime
+    tauri::async_runtime::spawn_blocking(move || {
+        let samples = read_audio_file(&path).map_err(|e| e.to_string())?;
+        println!(
+            "Transcribing file {} ({} samples at 16kHz)",
+            path,
+            samples.len()
+        );
+
+        let (result_tx, result_rx) = mpsc::channel();
+        let progress_path = path.clone();
+        tm.enqueue_file(
+            binding_id.as_deref(),
+            samples,
+            Box::new(move |processed, total| {
+                let _ = app.emit(
+                    "file-transcription-progress",
+                    progress_event(&progress_path, processed, total),
+                );
+            }),
+            Box::new(move |result| {
+                let _ = result_tx.send(result);
+            }),
+        );
+        let result = result_rx
+            .recv()
+            .map_err(|_| "Transcription queue stopped".to_string())?
+            .map_err(|e| e.to_string())?;
+
+        let output = format_result(&result, format).map_err(|e| e.to_string())?;
+
+        if let Some(output_path) = output_path {
+            std::fs::write(&output_path, &output)
+                .map_err(|e| format!("Failed to write {}: {}", output_path, e))?;
+        }
+
+        Ok(output)
+    })
+    .await
+    .map_err(|e| format!("File transcription task failed: {}", e))?
+}
+
+#[derive(Serialize)]
+pub struct ProcessingPreview {
+    pub output: String,
+    /// Text after each enabled step, in order
+    pub steps: Vec<ProcessedStep>,
+}
+
+/// Runs sample text through a processing chain without transcribing anything.
+/// Previews `chain` when given, e.g. while it is being edited, otherwise the
+/// chain selected for `binding_id`.
+#[tauri::command]
+pub fn preview_text_processing(
+    app: AppHandle,
+    text: String,
+    chain: Option<ProcessingChain>,
+    binding_id: Option<String>,
+) -> Result<ProcessingPreview, String> {
+    let settings = get_settings_for_binding(&app, binding_id.as_deref());
+    let pipeline = match &chain {
+        Some(chain) => {
+            // Surface broken steps instead of silently skipping them
+            validate_chain(chain, &settings).map_err(|e| e.to_string())?;
+            TextPipeline::from_chain(chain, &settings)
+        }
+        None => TextPipeline::from_settings(&settings),
+    };
+
+    let (output, steps) = pipeline.process_with_steps(&text);
+    Ok(ProcessingPreview { output, steps })
+}
+
+/// Sends sample text through a refinement prompt, e.g. to check the endpoint
+/// settings. Unlike refinement after a transcription, errors are returned
+/// instead of falling back to the input.
+#[tauri::command]
+pub async fn test_refinement(
+    app: AppHandle,
+    text: String,
+    prompt_id: String,
+) -> Result<String, String> {
+    let settings = get_settings(&app);
+    let prompt = settings
+        .refinement_prompts
+        .get(&prompt_id)
+        .ok_or_else(|| format!("Refinement prompt with id '{}' not found", prompt_id))?;
+    settings.llm.validate().map_err(|e| e.to_string())?;
+
+    let language = if settings.selected_language == "auto" {
+        "the original language"
+    } else {
+        settings.selected_language.as_str()
+    };
+    llm_refinement::refine(&settings.llm, prompt, &text, language)
+        .await
+        .map_err(|e| e.to_string())
+}
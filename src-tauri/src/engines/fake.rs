@@ -0,0 +1,158 @@
+use super::{
+    EngineCapabilities, LoadOptions, TranscribeOptions, TranscriptionEngine, TranscriptionResult,
+    TranscriptionSegment,
+};
+use crate::audio_toolkit::constants::WHISPER_SAMPLE_RATE;
+use anyhow::Result;
+use std::collections::VecDeque;
+use std::path::Path;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+/// What the fake engine does for one `transcribe` call.
+pub enum FakeResponse {
+    /// Decodes to this text as a single segment
+    Text(String),
+    Error(String),
+    /// Keeps decoding until the call is cancelled or times out
+    Hang,
+}
+
+/// How one `load` call of the fake engine fails. Loads succeed once the
+/// scripted failures are used up.
+pub enum FakeLoadFailure {
+    Error(String),
+    /// Panics like a broken GPU driver inside the bindings
+    Panic,
+}
+
+/// A `transcribe` call the fake engine received.
+#[derive(Clone, Debug)]
+pub struct FakeCall {
+    pub options: TranscribeOptions,
+}
+
+/// Engine returning scripted responses, for tests of the code around the
+/// engines. Clones share the script and the recorded calls, so a test can keep
+/// one while the code under test owns another.
+#[derive(Clone, Default)]
+pub struct FakeEngine {
+    responses: Arc<Mutex<VecDeque<FakeResponse>>>,
+    calls: Arc<Mutex<Vec<FakeCall>>>,
+    load_failures: Arc<Mutex<VecDeque<FakeLoadFailure>>>,
+    /// Options of every `load` call, failed ones included
+    loads: Arc<Mutex<Vec<LoadOptions>>>,
+    loaded: Arc<Mutex<bool>>,
+}
+
+impl FakeEngine {
+    /// A loaded engine answering with `responses` in order.
+    pub fn new(responses: Vec<FakeResponse>) -> Self {
+        let engine = Self::default();
+        engine.responses.lock().unwrap().extend(responses);
+        *engine.loaded.lock().unwrap() = true;
+        engine
+    }
+
+    /// Makes the next loads fail, in order.
+    pub fn fail_loads(&self, failures: Vec<FakeLoadFailure>) {
+        self.load_failures.lock().unwrap().extend(failures);
+    }
+
+    pub fn loads(&self) -> Vec<LoadOptions> {
+        self.loads.lock().unwrap().clone()
+    }
+
+    pub fn calls(&self) -> Vec<FakeCall> {
+        self.calls.lock().unwrap().clone()
+    }
+
+    /// Waits until the engine received `count` calls. Panics after a few
+    /// seconds so a broken test fails instead of hanging.
+    pub fn wait_for_calls(&self, count: usize) {
+        let started = Instant::now();
+        while self.calls.lock().unwrap().len() < count {
+            assert!(
+                started.elapsed() < Duration::from_secs(5),
+                "engine received {} of {} calls",
+                self.calls.lock().unwrap().len(),
+                count
+            );
+            std::thread::sleep(Duration::from_millis(5));
+        }
+    }
+}
+
+impl TranscriptionEngine for FakeEngine {
+    fn load(&mut self, _model_path: &Path, options: &LoadOptions) -> Result<()> {
+        self.loads.lock().unwrap().push(*options);
+        let failure = self.load_failures.lock().unwrap().pop_front();
+        match failure {
+            Some(FakeLoadFailure::Error(message)) => Err(anyhow::anyhow!(message)),
+            Some(FakeLoadFailure::Panic) => panic!("GPU driver crashed"),
+            None => {
+                *self.loaded.lock().unwrap() = true;
+                Ok(())
+            }
+        }
+    }
+
+    fn unload(&mut self) {
+        *self.loaded.lock().unwrap() = false;
+    }
+
+    fn is_loaded(&self) -> bool {
+        *self.loaded.lock().unwrap()
+    }
+
+    fn transcribe(
+        &mut self,
+        audio: &[f32],
+        options: &TranscribeOptions,
+    ) -> Result<TranscriptionResult> {
+        self.calls.lock().unwrap().push(FakeCall {
+            options: options.clone(),
+        });
+        let response = self
+            .responses
+            .lock()
+            .unwrap()
+            .pop_front()
+            .ok_or_else(|| anyhow::anyhow!("No scripted response left"))?;
+
+        match response {
+            FakeResponse::Text(text) => Ok(TranscriptionResult {
+                segments: vec![TranscriptionSegment {
+                    start_ms: 0,
+                    end_ms: (audio.len() as u64 * 1000 / WHISPER_SAMPLE_RATE as u64) as i64,
+                    text: text.clone(),
+                    ..Default::default()
+                }],
+                text,
+                ..Default::default()
+            }),
+            FakeResponse::Error(message) => Err(anyhow::anyhow!(message)),
+            FakeResponse::Hang => {
+                let started = Instant::now();
+                loop {
+                    if let Some(interrupted) = options.interruption(started) {
+                        return Err(interrupted.into());
+                    }
+                    std::thread::sleep(Duration::from_millis(5));
+                }
+            }
+        }
+    }
+
+    fn capabilities(&self) -> EngineCapabilities {
+        EngineCapabilities {
+            translation: false,
+            language_detection: false,
+            initial_prompt: true,
+            segment_timestamps: true,
+            token_probabilities: false,
+            word_timestamps: false,
+            remote: false,
+        }
+    }
+}
@@ -0,0 +1,681 @@
+#[cfg(test)]
+mod fake;
+mod remote;
+mod whisper;
+
+#[cfg(test)]
+pub use fake::{FakeEngine, FakeLoadFailure, FakeResponse};
+pub use remote::{RemoteEngine, RemoteModelConfig};
+pub use whisper::WhisperEngine;
+
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use std::panic::AssertUnwindSafe;
+use std::path::Path;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+/// Which engine implementation a model runs on. Stored on each `ModelInfo`.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum EngineKind {
+    #[default]
+    Whisper,
+    /// A server speaking the OpenAI transcription API
+    Remote,
+}
+
+/// What an engine can do, so callers can skip options it would ignore.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy)]
+pub struct EngineCapabilities {
+    pub translation: bool,
+    pub language_detection: bool,
+    pub initial_prompt: bool,
+    pub segment_timestamps: bool,
+    pub token_probabilities: bool,
+    pub word_timestamps: bool,
+    /// Decodes on another machine, GPU settings don't apply
+    pub remote: bool,
+}
+
+/// Shared flag that lets another thread stop a running decode.
+#[derive(Debug, Clone, Default)]
+pub struct CancellationToken(Arc<AtomicBool>);
+
+impl CancellationToken {
+    pub fn cancel(&self) {
+        self.0.store(true, Ordering::SeqCst);
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.0.load(Ordering::SeqCst)
+    }
+}
+
+/// Returned (wrapped in `anyhow::Error`) when a decode was stopped before it
+/// finished.
+#[derive(Debug, Clone, Copy)]
+pub enum TranscriptionInterrupted {
+    Cancelled,
+    TimedOut(Duration),
+}
+
+impl std::fmt::Display for TranscriptionInterrupted {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            TranscriptionInterrupted::Cancelled => write!(f, "Transcription cancelled"),
+            TranscriptionInterrupted::TimedOut(limit) => {
+                write!(f, "Transcription timed out after {}s", limit.as_secs())
+            }
+        }
+    }
+}
+
+impl std::error::Error for TranscriptionInterrupted {}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum DecodingStrategy {
+    #[default]
+    Greedy,
+    BeamSearch,
+}
+
+/// Largest beam whisper.cpp supports.
+const MAX_BEAM_SIZE: u32 = 16;
+
+/// Speed versus accuracy trade-offs of the decoder, set per model or profile.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq)]
+pub struct DecoderSettings {
+    #[serde(default)]
+    pub strategy: DecodingStrategy,
+    /// Beams kept by beam search, ignored with greedy decoding
+    #[serde(default = "default_beam_size")]
+    pub beam_size: u32,
+    /// Sampling temperature of the first attempt, 0 is deterministic
+    #[serde(default)]
+    pub temperature: f32,
+    /// Temperature added when an attempt fails the quality checks and is
+    /// retried, 0 disables the fallback
+    #[serde(default = "default_temperature_increment")]
+    pub temperature_increment: f32,
+    /// CPU threads, 0 lets the engine choose
+    #[serde(default)]
+    pub threads: u32,
+    /// Segments more likely than this to be silence are skipped
+    #[serde(default = "default_no_speech_threshold")]
+    pub no_speech_threshold: f32,
+    /// Takes effect on the next model load
+    #[serde(default = "default_use_gpu")]
+    pub use_gpu: bool,
+    /// Takes effect on the next model load
+    #[serde(default)]
+    pub flash_attention: bool,
+}
+
+fn default_beam_size() -> u32 {
+    5
+}
+
+fn default_temperature_increment() -> f32 {
+    0.2
+}
+
+fn default_no_speech_threshold() -> f32 {
+    0.2
+}
+
+fn default_use_gpu() -> bool {
+    true
+}
+
+impl Default for DecoderSettings {
+    fn default() -> Self {
+        Self {
+            strategy: DecodingStrategy::default(),
+            beam_size: default_beam_size(),
+            temperature: 0.0,
+            temperature_increment: default_temperature_increment(),
+            threads: 0,
+            no_speech_threshold: default_no_speech_threshold(),
+            use_gpu: default_use_gpu(),
+            flash_attention: false,
+        }
+    }
+}
+
+impl DecoderSettings {
+    pub fn validate(&self) -> Result<()> {
+        if !(1..=MAX_BEAM_SIZE).contains(&self.beam_size) {
+            return Err(anyhow::anyhow!(
+                "Beam size must be between 1 and {}",
+                MAX_BEAM_SIZE
+            ));
+        }
+        if !(0.0..=1.0).contains(&self.temperature) {
+            return Err(anyhow::anyhow!("Temperature must be between 0 and 1"));
+        }
+        if !(0.0..=1.0).contains(&self.temperature_increment) {
+            return Err(anyhow::anyhow!(
+                "Temperature increment must be between 0 and 1"
+            ));
+        }
+        if !(0.0..=1.0).contains(&self.no_speech_threshold) {
+            return Err(anyhow::anyhow!(
+                "No-speech threshold must be between 0 and 1"
+            ));
+        }
+        let cores = std::thread::available_parallelism().map_or(1, |n| n.get()) as u32;
+        if self.threads > cores {
+            return Err(anyhow::anyhow!(
+                "Thread count must be at most {} on this machine",
+                cores
+            ));
+        }
+        Ok(())
+    }
+
+    pub fn load_options(&self) -> LoadOptions {
+        LoadOptions {
+            use_gpu: self.use_gpu,
+            flash_attention: self.flash_attention,
+        }
+    }
+}
+
+/// The part of `DecoderSettings` that is fixed when a model is loaded.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct LoadOptions {
+    pub use_gpu: bool,
+    pub flash_attention: bool,
+}
+
+impl Default for LoadOptions {
+    fn default() -> Self {
+        DecoderSettings::default().load_options()
+    }
+}
+
+/// Where a loaded model runs.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum ComputeBackend {
+    Gpu,
+    Cpu,
+    Remote,
+}
+
+impl std::fmt::Display for ComputeBackend {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ComputeBackend::Gpu => write!(f, "GPU"),
+            ComputeBackend::Cpu => write!(f, "CPU"),
+            ComputeBackend::Remote => write!(f, "server"),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct TranscribeOptions {
+    /// Language code, or "auto" to let the engine detect it
+    pub language: String,
+    /// Languages auto-detection may choose from, any language when empty
+    pub detect_languages: Vec<String>,
+    pub translate: bool,
+    /// Text the decoder is primed with, used to bias it towards vocabulary
+    pub initial_prompt: Option<String>,
+    pub cancel: CancellationToken,
+    /// Maximum time the decode may take before it is aborted
+    pub timeout: Option<Duration>,
+    /// Report how likely each segment is to be silence, which can take an
+    /// extra encoder pass
+    pub no_speech_probability: bool,
+    pub decoder: DecoderSettings,
+}
+
+impl TranscribeOptions {
+    /// Checks whether the decode should stop. Engines poll this while decoding.
+    pub fn interruption(&self, started: Instant) -> Option<TranscriptionInterrupted> {
+        if self.cancel.is_cancelled() {
+            return Some(TranscriptionInterrupted::Cancelled);
+        }
+        match self.timeout {
+            Some(limit) if started.elapsed() >= limit => {
+                Some(TranscriptionInterrupted::TimedOut(limit))
+            }
+            _ => None,
+        }
+    }
+}
+
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct TranscriptionToken {
+    pub text: String,
+    pub probability: f32,
+    /// Start of the token relative to the start of the audio, in milliseconds
+    #[serde(default)]
+    pub start_ms: i64,
+    /// End of the token relative to the start of the audio, in milliseconds
+    #[serde(default)]
+    pub end_ms: i64,
+}
+
+/// A word assembled from the tokens it was decoded as.
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct TranscriptionWord {
+    pub text: String,
+    /// Start of the word relative to the start of the audio, in milliseconds
+    pub start_ms: i64,
+    /// End of the word relative to the start of the audio, in milliseconds
+    pub end_ms: i64,
+    /// Average probability of the word's tokens, leaving out punctuation
+    pub probability: f32,
+}
+
+/// Probability totals of the tokens merged into a word so far. Punctuation
+/// is kept apart, whisper is mostly sure of it and it would otherwise hide an
+/// unsure word such as "Anneliese." behind a confident full stop.
+#[derive(Default)]
+struct TokenProbabilities {
+    spoken: (f32, usize),
+    punctuation: (f32, usize),
+}
+
+impl TokenProbabilities {
+    fn add(&mut self, token: &TranscriptionToken) {
+        let total = if token.text.trim().chars().all(|c| !c.is_alphanumeric()) {
+            &mut self.punctuation
+        } else {
+            &mut self.spoken
+        };
+        total.0 += token.probability;
+        total.1 += 1;
+    }
+
+    /// Average of the spoken tokens, or of the punctuation for a word that is
+    /// punctuation only.
+    fn average(&self) -> f32 {
+        let (sum, count) = if self.spoken.1 > 0 {
+            self.spoken
+        } else {
+            self.punctuation
+        };
+        sum / count.max(1) as f32
+    }
+}
+
+/// Merges tokens into words. A token starting with a space begins a new word,
+/// any other token, such as a word piece or punctuation, continues the
+/// previous one.
+pub fn merge_tokens_into_words(tokens: &[TranscriptionToken]) -> Vec<TranscriptionWord> {
+    let mut words = Vec::new();
+    let mut current: Option<(TranscriptionWord, TokenProbabilities)> = None;
+
+    for token in tokens {
+        if token.text.trim().is_empty() {
+            continue;
+        }
+        let starts_word = token.text.starts_with(char::is_whitespace);
+        match current.as_mut() {
+            Some((word, probabilities)) if !starts_word => {
+                word.text.push_str(&token.text);
+                word.end_ms = word.end_ms.max(token.end_ms);
+                probabilities.add(token);
+            }
+            _ => {
+                words.extend(current.take().map(finish_word));
+                let mut probabilities = TokenProbabilities::default();
+                probabilities.add(token);
+                current = Some((
+                    TranscriptionWord {
+                        text: token.text.trim_start().to_string(),
+                        start_ms: token.start_ms,
+                        end_ms: token.end_ms,
+                        probability: 0.0,
+                    },
+                    probabilities,
+                ));
+            }
+        }
+    }
+    words.extend(current.map(finish_word));
+    words
+}
+
+fn finish_word(
+    (mut word, probabilities): (TranscriptionWord, TokenProbabilities),
+) -> TranscriptionWord {
+    word.text = word.text.trim_end().to_string();
+    word.probability = probabilities.average();
+    word
+}
+
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct TranscriptionSegment {
+    /// Start of the segment relative to the start of the audio, in milliseconds
+    pub start_ms: i64,
+    /// End of the segment relative to the start of the audio, in milliseconds
+    pub end_ms: i64,
+    pub text: String,
+    pub tokens: Vec<TranscriptionToken>,
+    /// Probability the model gave the audio of the segment being silence,
+    /// `None` when the engine doesn't report it
+    #[serde(default)]
+    pub no_speech_probability: Option<f32>,
+}
+
+/// Why a segment was dropped as a likely hallucination.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum FilterReason {
+    /// Whisper rated the segment as silence, or the audio was near silent and
+    /// the decoder unsure
+    NoSpeech,
+    /// The text loops, e.g. the same sentence many times
+    Repetitive,
+    /// Only an annotation such as "[BLANK_AUDIO]" or "(music)"
+    Annotation,
+    /// The same text as the segments before it, more times in a row than
+    /// anyone says a sentence
+    Repeated,
+    /// A known hallucination phrase such as "Thanks for watching"
+    Blocklisted,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct FilteredSegment {
+    pub start_ms: i64,
+    pub end_ms: i64,
+    pub text: String,
+    pub reason: FilterReason,
+}
+
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct TranscriptionResult {
+    /// Final text after post-processing, ready to paste
+    pub text: String,
+    /// Raw decoder output, before post-processing
+    pub segments: Vec<TranscriptionSegment>,
+    /// Language code the model decoded in, e.g. "en"
+    pub language: Option<String>,
+    /// Probability the engine gave the language when it detected it
+    pub language_probability: Option<f32>,
+    /// Segments dropped as likely hallucinations, not part of `text`
+    #[serde(default)]
+    pub filtered: Vec<FilteredSegment>,
+    /// Words of the kept segments with their times, as decoded, before
+    /// post-processing. Empty for engines without token timestamps.
+    #[serde(default)]
+    pub words: Vec<TranscriptionWord>,
+}
+
+impl TranscriptionResult {
+    /// Appends `other`, shifting its segment and word times by `offset_ms`.
+    pub fn append(&mut self, other: TranscriptionResult, offset_ms: i64) {
+        if !other.text.is_empty() {
+            if !self.text.is_empty() {
+                self.text.push(' ');
+            }
+            self.text.push_str(&other.text);
+        }
+        self.segments
+            .extend(other.segments.into_iter().map(|mut segment| {
+                segment.start_ms += offset_ms;
+                segment.end_ms += offset_ms;
+                segment
+            }));
+        self.filtered
+            .extend(other.filtered.into_iter().map(|mut segment| {
+                segment.start_ms += offset_ms;
+                segment.end_ms += offset_ms;
+                segment
+            }));
+        self.words.extend(other.words.into_iter().map(|mut word| {
+            word.start_ms += offset_ms;
+            word.end_ms += offset_ms;
+            word
+        }));
+        if self.language.is_none() {
+            self.language = other.language;
+            self.language_probability = other.language_probability;
+        }
+    }
+}
+
+/// A speech-recognition backend. Engines own their model state; the
+/// `TranscriptionManager` takes care of locking, settings and post-processing.
+pub trait TranscriptionEngine: Send {
+    fn load(&mut self, model_path: &Path, options: &LoadOptions) -> Result<()>;
+
+    fn unload(&mut self);
+
+    fn is_loaded(&self) -> bool;
+
+    /// Transcribes 16 kHz mono samples. The returned text is the raw decoder
+    /// output without any post-processing applied.
+    fn transcribe(
+        &mut self,
+        audio: &[f32],
+        options: &TranscribeOptions,
+    ) -> Result<TranscriptionResult>;
+
+    fn capabilities(&self) -> EngineCapabilities;
+}
+
+/// Loads a model, retrying on the CPU when it fails with the GPU enabled.
+/// GPU drivers that are missing or broken, as on headless machines, make
+/// context creation fail, or panic inside the bindings. Remote engines are
+/// loaded as they are. Returns the backend the model ended up on.
+pub fn load_with_cpu_fallback(
+    engine: &mut dyn TranscriptionEngine,
+    model_path: &Path,
+    options: &LoadOptions,
+) -> Result<ComputeBackend> {
+    if engine.capabilities().remote {
+        engine.load(model_path, options)?;
+        return Ok(ComputeBackend::Remote);
+    }
+    if !options.use_gpu {
+        engine.load(model_path, options)?;
+        return Ok(ComputeBackend::Cpu);
+    }
+
+    let gpu_result =
+        std::panic::catch_unwind(AssertUnwindSafe(|| engine.load(model_path, options)))
+            .unwrap_or_else(|_| Err(anyhow::anyhow!("GPU initialization panicked")));
+
+    match gpu_result {
+        Ok(()) => Ok(ComputeBackend::Gpu),
+        Err(e) => {
+            eprintln!("Loading on the GPU failed, retrying on the CPU: {}", e);
+            engine.unload();
+            let cpu_options = LoadOptions {
+                use_gpu: false,
+                ..*options
+            };
+            engine.load(model_path, &cpu_options)?;
+            Ok(ComputeBackend::Cpu)
+        }
+    }
+}
+
+pub fn create_engine(kind: EngineKind) -> Box<dyn TranscriptionEngine> {
+    match kind {
+        EngineKind::Whisper => Box::new(WhisperEngine::default()),
+        EngineKind::Remote => Box::new(RemoteEngine::default()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn token(text: &str, probability: f32, start_ms: i64, end_ms: i64) -> TranscriptionToken {
+        TranscriptionToken {
+            text: text.to_string(),
+            probability,
+            start_ms,
+            end_ms,
+        }
+    }
+
+    const GPU: LoadOptions = LoadOptions {
+        use_gpu: true,
+        flash_attention: false,
+    };
+    const CPU: LoadOptions = LoadOptions {
+        use_gpu: false,
+        flash_attention: false,
+    };
+
+    /// An unloaded fake engine whose first loads fail with `failures`.
+    fn engine_failing(failures: Vec<FakeLoadFailure>) -> FakeEngine {
+        let engine = FakeEngine::default();
+        engine.fail_loads(failures);
+        engine
+    }
+
+    #[test]
+    fn gpu_errors_fall_back_to_the_cpu() {
+        let mut engine = engine_failing(vec![FakeLoadFailure::Error("no device".to_string())]);
+        let backend = load_with_cpu_fallback(&mut engine, Path::new("model.bin"), &GPU).unwrap();
+        assert_eq!(backend, ComputeBackend::Cpu);
+        assert_eq!(engine.loads(), [GPU, CPU]);
+        assert!(engine.is_loaded());
+    }
+
+    #[test]
+    fn gpu_panics_fall_back_to_the_cpu() {
+        let mut engine = engine_failing(vec![FakeLoadFailure::Panic]);
+        let backend = load_with_cpu_fallback(&mut engine, Path::new("model.bin"), &GPU).unwrap();
+        assert_eq!(backend, ComputeBackend::Cpu);
+        assert_eq!(engine.loads(), [GPU, CPU]);
+        assert!(engine.is_loaded());
+    }
+
+    #[test]
+    fn working_gpu_is_kept() {
+        let mut engine = engine_failing(Vec::new());
+        let backend = load_with_cpu_fallback(&mut engine, Path::new("model.bin"), &GPU).unwrap();
+        assert_eq!(backend, ComputeBackend::Gpu);
+        assert_eq!(engine.loads(), [GPU]);
+    }
+
+    #[test]
+    fn cpu_only_skips_the_gpu() {
+        let mut engine = engine_failing(Vec::new());
+        let backend = load_with_cpu_fallback(&mut engine, Path::new("model.bin"), &CPU).unwrap();
+        assert_eq!(backend, ComputeBackend::Cpu);
+        assert_eq!(engine.loads(), [CPU]);
+
+        // A CPU failure is not retried
+        let mut engine = engine_failing(vec![FakeLoadFailure::Error("corrupt".to_string())]);
+        assert!(load_with_cpu_fallback(&mut engine, Path::new("model.bin"), &CPU).is_err());
+        assert_eq!(engine.loads(), [CPU]);
+    }
+
+    #[test]
+    fn failing_on_both_backends_is_an_error() {
+        let mut engine = engine_failing(vec![
+            FakeLoadFailure::Panic,
+            FakeLoadFailure::Error("corrupt".to_string()),
+        ]);
+        let error = load_with_cpu_fallback(&mut engine, Path::new("model.bin"), &GPU).unwrap_err();
+        assert_eq!(error.to_string(), "corrupt");
+        assert_eq!(engine.loads(), [GPU, CPU]);
+        assert!(!engine.is_loaded());
+    }
+
+    #[test]
+    fn default_decoder_settings_are_valid() {
+        assert!(DecoderSettings::default().validate().is_ok());
+    }
+
+    #[test]
+    fn beam_size_must_be_in_range() {
+        for (beam_size, valid) in [(0, false), (1, true), (MAX_BEAM_SIZE, true), (17, false)] {
+            let decoder = DecoderSettings {
+                beam_size,
+                ..Default::default()
+            };
+            assert_eq!(decoder.validate().is_ok(), valid, "beam size {}", beam_size);
+        }
+    }
+
+    #[test]
+    fn temperatures_and_threshold_must_be_between_0_and_1() {
+        let with = |change: fn(&mut DecoderSettings, f32), value: f32| {
+            let mut decoder = DecoderSettings::default();
+            change(&mut decoder, value);
+            decoder.validate().is_ok()
+        };
+        let fields: [fn(&mut DecoderSettings, f32); 3] = [
+            |d, v| d.temperature = v,
+            |d, v| d.temperature_increment = v,
+            |d, v| d.no_speech_threshold = v,
+        ];
+        for change in fields {
+            assert!(with(change, 0.0));
+            assert!(with(change, 1.0));
+            assert!(!with(change, -0.1));
+            assert!(!with(change, 1.1));
+            assert!(!with(change, f32::NAN));
+        }
+    }
+
+    #[test]
+    fn threads_must_not_exceed_the_cores() {
+        let cores = std::thread::available_parallelism().map_or(1, |n| n.get()) as u32;
+        for (threads, valid) in [(0, true), (1, true), (cores, true), (cores + 1, false)] {
+            let decoder = DecoderSettings {
+                threads,
+                ..Default::default()
+            };
+            assert_eq!(decoder.validate().is_ok(), valid, "{} threads", threads);
+        }
+    }
+
+    #[test]
+    fn word_pieces_are_merged() {
+        let words = merge_tokens_into_words(&[
+            token(" Meet", 0.9, 0, 200),
+            token(" Ann", 0.4, 200, 400),
+            token("eli", 0.2, 400, 500),
+            token("ese", 0.3, 500, 700),
+            token(" ", 0.9, 700, 700),
+            token(" at", 0.8, 700, 800),
+        ]);
+
+        let texts: Vec<&str> = words.iter().map(|w| w.text.as_str()).collect();
+        assert_eq!(texts, ["Meet", "Anneliese", "at"]);
+        assert_eq!((words[1].start_ms, words[1].end_ms), (200, 700));
+        assert!((words[1].probability - 0.3).abs() < 1e-6);
+    }
+
+    #[test]
+    fn punctuation_is_left_out_of_the_probability() {
+        let words = merge_tokens_into_words(&[
+            token(" Anneliese", 0.2, 0, 500),
+            token(".", 0.99, 500, 520),
+            token(" (", 0.9, 520, 540),
+            token("noon", 0.6, 540, 800),
+            token(")", 0.95, 800, 820),
+            token(" -", 0.7, 820, 840),
+            token("-", 0.5, 840, 860),
+        ]);
+
+        let texts: Vec<&str> = words.iter().map(|w| w.text.as_str()).collect();
+        assert_eq!(texts, ["Anneliese.", "(noon)", "--"]);
+        assert!((words[0].probability - 0.2).abs() < 1e-6);
+        assert!((words[1].probability - 0.6).abs() < 1e-6);
+        // A word that is punctuation only keeps its own average
+        assert!((words[2].probability - 0.6).abs() < 1e-6);
+    }
+
+    #[test]
+    fn no_tokens_make_no_words() {
+        assert!(merge_tokens_into_words(&[]).is_empty());
+        assert!(merge_tokens_into_words(&[token(" ", 0.5, 0, 0)]).is_empty());
+    }
+}
@@ -0,0 +1,479 @@
+//! Sends recordings to a speech-to-text server that speaks the OpenAI
+//! transcription API, such as the whisper.cpp or faster-whisper servers, for
+//! machines too weak to decode locally.
+
+use super::{
+    EngineCapabilities, LoadOptions, TranscribeOptions, TranscriptionEngine,
+    TranscriptionInterrupted, TranscriptionResult, TranscriptionSegment,
+};
+use crate::audio_toolkit::constants::WHISPER_SAMPLE_RATE;
+use anyhow::Result;
+use futures_util::future::{self, Either};
+use serde::{Deserialize, Serialize};
+use std::io::Cursor;
+use std::path::Path;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+/// Describes a remote model. Stored as `<id>.remote.json` in the models
+/// directory, where local models keep their weights.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct RemoteModelConfig {
+    pub name: String,
+    /// Base URL of the API, e.g. `http://192.168.1.20:8000/v1`
+    pub endpoint: String,
+    /// Model name sent with each request, servers hosting one model ignore it
+    #[serde(default = "default_model")]
+    pub model: String,
+    /// Sent as a bearer token when set
+    #[serde(default)]
+    pub api_key: Option<String>,
+    /// Limit for a single request
+    #[serde(default = "default_timeout_seconds")]
+    pub timeout_seconds: u32,
+    /// Further attempts after a request failed with a network or server error
+    #[serde(default = "default_retries")]
+    pub retries: u32,
+    /// Local model used when the server can't be reached
+    #[serde(default)]
+    pub fallback_model: Option<String>,
+}
+
+fn default_model() -> String {
+    "whisper-1".to_string()
+}
+
+fn default_timeout_seconds() -> u32 {
+    30
+}
+
+fn default_retries() -> u32 {
+    2
+}
+
+const MAX_RETRIES: u32 = 5;
+
+/// How often a running request checks for cancellation.
+const INTERRUPT_POLL_INTERVAL: Duration = Duration::from_millis(100);
+
+/// Wait before the first retry, doubled for each further one.
+const RETRY_BACKOFF: Duration = Duration::from_millis(500);
+
+impl RemoteModelConfig {
+    pub fn validate(&self) -> Result<()> {
+        if self.name.trim().is_empty() {
+            return Err(anyhow::anyhow!("Name must not be empty"));
+        }
+        let endpoint = self.endpoint.trim();
+        if !endpoint.starts_with("http://") && !endpoint.starts_with("https://") {
+            return Err(anyhow::anyhow!(
+                "Endpoint must be an http:// or https:// URL"
+            ));
+        }
+        if self.timeout_seconds == 0 {
+            return Err(anyhow::anyhow!("Timeout must be at least 1 second"));
+        }
+        if self.retries > MAX_RETRIES {
+            return Err(anyhow::anyhow!(
+                "At most {} retries are allowed",
+                MAX_RETRIES
+            ));
+        }
+        Ok(())
+    }
+
+    pub fn read(path: &Path) -> Result<Self> {
+        let contents = std::fs::read_to_string(path)
+            .map_err(|e| anyhow::anyhow!("Failed to read {:?}: {}", path, e))?;
+        serde_json::from_str(&contents)
+            .map_err(|e| anyhow::anyhow!("Invalid remote model {:?}: {}", path, e))
+    }
+
+    /// The transcriptions URL, or the translations one. The endpoint may be
+    /// the API base or the full transcriptions URL.
+    fn url(&self, translate: bool) -> String {
+        let endpoint = self.endpoint.trim().trim_end_matches('/');
+        let base = endpoint
+            .strip_suffix("/audio/transcriptions")
+            .unwrap_or(endpoint);
+        let route = if translate {
+            "translations"
+        } else {
+            "transcriptions"
+        };
+        format!("{}/audio/{}", base, route)
+    }
+}
+
+#[derive(Deserialize)]
+struct RemoteResponse {
+    text: String,
+    #[serde(default)]
+    language: Option<String>,
+    #[serde(default)]
+    segments: Vec<RemoteSegment>,
+}
+
+#[derive(Deserialize)]
+struct RemoteSegment {
+    start: f64,
+    end: f64,
+    text: String,
+    #[serde(default)]
+    no_speech_prob: Option<f32>,
+}
+
+/// Why a request failed, and whether trying again could help.
+enum RequestError {
+    Retryable(anyhow::Error),
+    Fatal(anyhow::Error),
+}
+
+/// 16-bit mono WAV at the sample rate the samples were recorded in.
+fn encode_wav(audio: &[f32]) -> Result<Vec<u8>> {
+    let spec = hound::WavSpec {
+        channels: 1,
+        sample_rate: WHISPER_SAMPLE_RATE,
+        bits_per_sample: 16,
+        sample_format: hound::SampleFormat::Int,
+    };
+    let mut buffer = Cursor::new(Vec::new());
+    let mut writer = hound::WavWriter::new(&mut buffer, spec)?;
+    for &sample in audio {
+        writer.write_sample((sample.clamp(-1.0, 1.0) * i16::MAX as f32) as i16)?;
+    }
+    writer.finalize()?;
+    Ok(buffer.into_inner())
+}
+
+/// A multipart/form-data body with text fields and the recording as `file`.
+fn multipart_body(boundary: &str, fields: &[(&str, String)], wav: &[u8]) -> Vec<u8> {
+    let mut body = Vec::with_capacity(wav.len() + 1024);
+    for (name, value) in fields {
+        body.extend_from_slice(
+            format!(
+                "--{}\r\nContent-Disposition: form-data; name=\"{}\"\r\n\r\n{}\r\n",
+                boundary, name, value
+            )
+            .as_bytes(),
+        );
+    }
+    body.extend_from_slice(
+        format!(
+            "--{}\r\nContent-Disposition: form-data; name=\"file\"; filename=\"audio.wav\"\r\n\
+             Content-Type: audio/wav\r\n\r\n",
+            boundary
+        )
+        .as_bytes(),
+    );
+    body.extend_from_slice(wav);
+    body.extend_from_slice(format!("\r\n--{}--\r\n", boundary).as_bytes());
+    body
+}
+
+/// Whisper language code for what a server reports, which is either a code
+/// or, in OpenAI's verbose format, the full name such as "english".
+fn language_code(language: &str) -> Option<String> {
+    let language = language.trim().to_lowercase();
+    if whisper_rs::get_lang_id(&language).is_some() {
+        return Some(language);
+    }
+    (0..=whisper_rs::get_lang_max_id())
+        .find(|&id| whisper_rs::get_lang_str_full(id) == Some(language.as_str()))
+        .and_then(whisper_rs::get_lang_str)
+        .map(str::to_string)
+}
+
+#[derive(Default)]
+pub struct RemoteEngine {
+    config: Option<RemoteModelConfig>,
+    client: reqwest::Client,
+}
+
+impl RemoteEngine {
+    /// An engine loaded with `config` without reading it from a file.
+    #[cfg(test)]
+    pub fn with_config(config: RemoteModelConfig) -> Self {
+        Self {
+            config: Some(config),
+            client: reqwest::Client::new(),
+        }
+    }
+
+    async fn send(
+        &self,
+        config: &RemoteModelConfig,
+        url: &str,
+        boundary: &str,
+        body: Vec<u8>,
+    ) -> std::result::Result<TranscriptionResult, RequestError> {
+        let mut request = self
+            .client
+            .post(url)
+            .timeout(Duration::from_secs(config.timeout_seconds as u64))
+            .header(
+                "Content-Type",
+                format!("multipart/form-data; boundary={}", boundary),
+            )
+            .body(body);
+        if let Some(api_key) = config.api_key.as_deref().filter(|k| !k.is_empty()) {
+            request = request.bearer_auth(api_key);
+        }
+
+        let response = request.send().await.map_err(|e| {
+            RequestError::Retryable(anyhow::anyhow!("Request to {} failed: {}", url, e))
+        })?;
+
+        let status = response.status();
+        if !status.is_success() {
+            let detail = response.text().await.unwrap_or_default();
+            let error = anyhow::anyhow!("Server returned {}: {}", status, detail.trim());
+            let retryable = status.is_server_error()
+                || status == reqwest::StatusCode::REQUEST_TIMEOUT
+                || status == reqwest::StatusCode::TOO_MANY_REQUESTS;
+            return Err(if retryable {
+                RequestError::Retryable(error)
+            } else {
+                RequestError::Fatal(error)
+            });
+        }
+
+        let response: RemoteResponse = response.json().await.map_err(|e| {
+            RequestError::Fatal(anyhow::anyhow!("Unexpected response from server: {}", e))
+        })?;
+
+        let segments = response
+            .segments
+            .into_iter()
+            .map(|segment| TranscriptionSegment {
+                start_ms: (segment.start * 1000.0) as i64,
+                end_ms: (segment.end * 1000.0) as i64,
+                text: segment.text.trim().to_string(),
+                tokens: Vec::new(),
+                no_speech_probability: segment.no_speech_prob,
+            })
+            .collect();
+
+        Ok(TranscriptionResult {
+            text: response.text.trim().to_string(),
+            segments,
+            language: response.language.as_deref().and_then(language_code),
+            ..Default::default()
+        })
+    }
+
+    /// Resolves once the transcription is cancelled or over its time limit.
+    async fn interrupted(
+        options: &TranscribeOptions,
+        started: Instant,
+    ) -> TranscriptionInterrupted {
+        loop {
+            if let Some(interruption) = options.interruption(started) {
+                return interruption;
+            }
+            tokio::time::sleep(INTERRUPT_POLL_INTERVAL).await;
+        }
+    }
+}
+
+impl TranscriptionEngine for RemoteEngine {
+    fn load(&mut self, model_path: &Path, _options: &LoadOptions) -> Result<()> {
+        let config = RemoteModelConfig::read(model_path)?;
+        config.validate()?;
+        self.config = Some(config);
+        Ok(())
+    }
+
+    fn unload(&mut self) {
+        self.config = None;
+    }
+
+    fn is_loaded(&self) -> bool {
+        self.config.is_some()
+    }
+
+    fn transcribe(
+        &mut self,
+        audio: &[f32],
+        options: &TranscribeOptions,
+    ) -> Result<TranscriptionResult> {
+        let config = self
+            .config
+            .as_ref()
+            .ok_or_else(|| anyhow::anyhow!("Remote model not loaded"))?;
+        let started = Instant::now();
+
+        let mut fields = vec![
+            ("model", config.model.clone()),
+            ("response_format", "verbose_json".to_string()),
+            ("temperature", options.decoder.temperature.to_string()),
+        ];
+        if options.language != "auto" && !options.translate {
+            fields.push(("language", options.language.clone()));
+        }
+        if let Some(prompt) = &options.initial_prompt {
+            fields.push(("prompt", prompt.clone()));
+        }
+
+        let wav = encode_wav(audio)?;
+        let nanos = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_or(0, |d| d.as_nanos());
+        let boundary = format!("----handy{:032x}", nanos);
+        let url = config.url(options.translate);
+
+        let mut last_error = anyhow::anyhow!("No request was made");
+        for attempt in 0..=config.retries {
+            let backoff = if attempt > 0 {
+                let backoff = RETRY_BACKOFF * 2u32.pow(attempt - 1);
+                eprintln!(
+                    "Remote transcription failed, retrying in {}ms: {}",
+                    backoff.as_millis(),
+                    last_error
+                );
+                backoff
+            } else {
+                Duration::ZERO
+            };
+            if let Some(interruption) = options.interruption(started) {
+                return Err(interruption.into());
+            }
+
+            let body = multipart_body(&boundary, &fields, &wav);
+            let outcome = tauri::async_runtime::block_on(async {
+                // Wait out the backoff within the select, so a cancel or the
+                // time limit ends it right away
+                let send = Box::pin(async {
+                    tokio::time::sleep(backoff).await;
+                    self.send(config, &url, &boundary, body).await
+                });
+                let interrupted = Box::pin(Self::interrupted(options, started));
+                match future::select(send, interrupted).await {
+                    Either::Left((result, _)) => Ok(result),
+                    Either::Right((interruption, _)) => Err(interruption),
+                }
+            });
+
+            match outcome {
+                Err(interruption) => return Err(interruption.into()),
+                Ok(Ok(result)) => return Ok(result),
+                Ok(Err(RequestError::Fatal(e))) => return Err(e),
+                Ok(Err(RequestError::Retryable(e))) => last_error = e,
+            }
+        }
+
+        Err(last_error)
+    }
+
+    fn capabilities(&self) -> EngineCapabilities {
+        EngineCapabilities {
+            translation: true,
+            language_detection: true,
+            initial_prompt: true,
+            segment_timestamps: true,
+            token_probabilities: false,
+            word_timestamps: false,
+            remote: true,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::engines::CancellationToken;
+    use crate::test_server::{StubResponse, StubServer};
+    use serde_json::json;
+
+    fn config(server: &StubServer, retries: u32) -> RemoteModelConfig {
+        RemoteModelConfig {
+            name: "Server".to_string(),
+            endpoint: format!("{}/v1", server.url),
+            model: default_model(),
+            api_key: None,
+            timeout_seconds: 5,
+            retries,
+            fallback_model: None,
+        }
+    }
+
+    fn options() -> TranscribeOptions {
+        TranscribeOptions {
+            language: "en".to_string(),
+            detect_languages: Vec::new(),
+            translate: false,
+            initial_prompt: None,
+            cancel: CancellationToken::default(),
+            timeout: None,
+            no_speech_probability: false,
+            decoder: Default::default(),
+        }
+    }
+
+    fn second_of_silence() -> Vec<f32> {
+        vec![0.0; WHISPER_SAMPLE_RATE as usize]
+    }
+
+    #[test]
+    fn server_errors_are_retried() {
+        let server = StubServer::start(vec![
+            StubResponse::json(502, json!({"error": "bad gateway"})),
+            StubResponse::json(
+                200,
+                json!({
+                    "text": " Hello there. ",
+                    "language": "english",
+                    "segments": [{"start": 0.0, "end": 1.0, "text": " Hello there."}],
+                }),
+            ),
+        ]);
+        let mut engine = RemoteEngine::with_config(config(&server, 2));
+
+        let result = engine.transcribe(&second_of_silence(), &options()).unwrap();
+
+        assert_eq!(result.text, "Hello there.");
+        assert_eq!(result.segments[0].end_ms, 1000);
+        let requests = server.requests();
+        assert_eq!(requests.len(), 2);
+        assert_eq!(requests[1].path, "/v1/audio/transcriptions");
+    }
+
+    #[test]
+    fn client_errors_are_not_retried() {
+        let server = StubServer::start(vec![StubResponse::json(
+            400,
+            json!({"error": "unsupported audio"}),
+        )]);
+        let mut engine = RemoteEngine::with_config(config(&server, 2));
+
+        let err = engine
+            .transcribe(&second_of_silence(), &options())
+            .unwrap_err();
+
+        assert!(err.to_string().contains("400"), "{}", err);
+        assert_eq!(server.requests().len(), 1);
+    }
+
+    #[test]
+    fn cancel_ends_the_retry_backoff() {
+        let server = StubServer::start(vec![StubResponse::json(503, json!({"error": "busy"}))]);
+        let mut engine = RemoteEngine::with_config(config(&server, 1));
+        let options = options();
+        let cancel = options.cancel.clone();
+        std::thread::spawn(move || {
+            std::thread::sleep(Duration::from_millis(200));
+            cancel.cancel();
+        });
+
+        let started = Instant::now();
+        let err = engine
+            .transcribe(&second_of_silence(), &options)
+            .unwrap_err();
+
+        assert!(matches!(
+            err.downcast_ref::<TranscriptionInterrupted>(),
+            Some(TranscriptionInterrupted::Cancelled)
+        ));
+        assert!(started.elapsed() < RETRY_BACKOFF, "{:?}", started.elapsed());
+        assert_eq!(server.requests().len(), 1);
+    }
+}
@@ -0,0 +1,377 @@
+use super::{
+    DecodingStrategy, EngineCapabilities, LoadOptions, TranscribeOptions, TranscriptionEngine,
+    TranscriptionResult, TranscriptionSegment, TranscriptionToken,
+};
+use anyhow::Result;
+use std::cmp::Ordering;
+use std::ffi::c_void;
+use std::path::Path;
+use std::time::Instant;
+use whisper_rs::{
+    FullParams, SamplingStrategy, WhisperContext, WhisperContextParameters, WhisperState,
+};
+
+/// Threads to run on, `requested` unless it is 0. Then as many as there are
+/// cores, but at most 4, mirroring the default of the whisper.cpp CLI.
+fn thread_count(requested: u32) -> usize {
+    if requested > 0 {
+        return requested as usize;
+    }
+    std::thread::available_parallelism()
+        .map(|n| n.get().min(4))
+        .unwrap_or(1)
+}
+
+/// Length of the window whisper encodes at once.
+const WINDOW_MS: i64 = 30_000;
+
+/// Probability of the no-speech token in the logits whisper produced for the
+/// token after start-of-transcript, the same estimate whisper uses to skip
+/// silent windows. Expects the first window to be decoded up to that token.
+fn no_speech_probability(state: &WhisperState, context: &WhisperContext) -> Result<f32> {
+    let logits = state
+        .get_logits()
+        .map_err(|e| anyhow::anyhow!("Failed to get logits: {}", e))?;
+    let no_speech = logits
+        .get(context.token_nosp() as usize)
+        .copied()
+        .ok_or_else(|| anyhow::anyhow!("Model has no no-speech token"))?;
+    let max = logits.iter().copied().fold(f32::NEG_INFINITY, f32::max);
+    let sum: f32 = logits.iter().map(|logit| (logit - max).exp()).sum();
+    Ok((no_speech - max).exp() / sum)
+}
+
+/// Encodes the first window of `audio` and decodes start-of-transcript, for
+/// `no_speech_probability` when language detection didn't do so already.
+fn decode_first_window(
+    state: &mut WhisperState,
+    context: &WhisperContext,
+    audio: &[f32],
+    threads: usize,
+) -> Result<()> {
+    state
+        .pcm_to_mel(audio, threads)
+        .map_err(|e| anyhow::anyhow!("Failed to compute mel spectrogram: {}", e))?;
+    state
+        .encode(0, threads)
+        .map_err(|e| anyhow::anyhow!("Failed to encode audio: {}", e))?;
+    state
+        .decode(&[context.token_sot()], 0, threads)
+        .map_err(|e| anyhow::anyhow!("Failed to decode: {}", e))
+}
+
+/// Detects the spoken language, choosing only from `allowed` when it is not
+/// empty. Returns the language code and the probability whisper gave it.
+fn detect_language(
+    state: &mut WhisperState,
+    audio: &[f32],
+    allowed: &[String],
+    threads: usize,
+) -> Result<(String, f32)> {
+    state
+        .pcm_to_mel(audio, threads)
+        .map_err(|e| anyhow::anyhow!("Failed to compute mel spectrogram: {}", e))?;
+    let (_, probabilities) = state
+        .lang_detect(0, threads)
+        .map_err(|e| anyhow::anyhow!("Failed to detect language: {}", e))?;
+
+    let candidates: Vec<i32> = if allowed.is_empty() {
+        (0..probabilities.len() as i32).collect()
+    } else {
+        allowed
+            .iter()
+            .filter_map(|code| whisper_rs::get_lang_id(code))
+            .collect()
+    };
+
+    candidates
+        .into_iter()
+        .filter_map(|id| probabilities.get(id as usize).map(|p| (id, *p)))
+        .max_by(|a, b| a.1.partial_cmp(&b.1).unwrap_or(Ordering::Equal))
+        .and_then(|(id, p)| whisper_rs::get_lang_str(id).map(|code| (code.to_string(), p)))
+        .ok_or_else(|| anyhow::anyhow!("None of the allowed languages is known to whisper"))
+}
+
+/// What the abort callback checks, kept on the stack of `transcribe` until
+/// the decode it belongs to has returned.
+struct AbortCheck<'a> {
+    options: &'a TranscribeOptions,
+    started: Instant,
+}
+
+/// Called by whisper between decoder steps, returning true aborts the decode.
+///
+/// # Safety
+///
+/// `user_data` must point at an `AbortCheck` that outlives the decode.
+unsafe extern "C" fn should_abort(user_data: *mut c_void) -> bool {
+    let check = &*(user_data as *const AbortCheck);
+    check.options.interruption(check.started).is_some()
+}
+
+#[derive(Default)]
+pub struct WhisperEngine {
+    context: Option<WhisperContext>,
+    state: Option<WhisperState>,
+}
+
+impl TranscriptionEngine for WhisperEngine {
+    fn load(&mut self, model_path: &Path, options: &LoadOptions) -> Result<()> {
+        let path_str = model_path
+            .to_str()
+            .ok_or_else(|| anyhow::anyhow!("Invalid model path: {:?}", model_path))?;
+
+        let mut context_params = WhisperContextParameters::default();
+        context_params.use_gpu = options.use_gpu;
+        context_params.flash_attn = options.flash_attention;
+
+        let context = WhisperContext::new_with_params(path_str, context_params)
+            .map_err(|e| anyhow::anyhow!("Failed to load whisper model: {}", e))?;
+
+        let state = context
+            .create_state()
+            .map_err(|e| anyhow::anyhow!("Failed to create whisper state: {}", e))?;
+
+        self.context = Some(context);
+        self.state = Some(state);
+        Ok(())
+    }
+
+    fn unload(&mut self) {
+        // The state keeps a reference to the context, drop it first
+        self.state = None;
+        self.context = None;
+    }
+
+    fn is_loaded(&self) -> bool {
+        self.state.is_some()
+    }
+
+    fn transcribe(
+        &mut self,
+        audio: &[f32],
+        options: &TranscribeOptions,
+    ) -> Result<TranscriptionResult> {
+        let (Some(context), Some(state)) = (self.context.as_ref(), self.state.as_mut()) else {
+            return Err(anyhow::anyhow!("Whisper model is not loaded"));
+        };
+        let threads = thread_count(options.decoder.threads);
+
+        // Detect the language up front, so it can be limited to the user's
+        // languages and its probability reported
+        let detected = if options.language == "auto" {
+            match detect_language(state, audio, &options.detect_languages, threads) {
+                Ok(detected) => Some(detected),
+                Err(e) => {
+                    eprintln!("Language detection failed, leaving it to whisper: {}", e);
+                    None
+                }
+            }
+        } else {
+            None
+        };
+
+        // Language detection leaves the first window decoded up to the token
+        // the estimate needs, otherwise that takes an extra encoder pass
+        let no_speech = if options.no_speech_probability {
+            let decoded = if detected.is_some() {
+                Ok(())
+            } else {
+                decode_first_window(state, context, audio, threads)
+            };
+            match decoded.and_then(|()| no_speech_probability(state, context)) {
+                Ok(probability) => Some(probability),
+                Err(e) => {
+                    eprintln!("Failed to estimate the no-speech probability: {}", e);
+                    None
+                }
+            }
+        } else {
+            None
+        };
+        let language = detected
+            .as_ref()
+            .map_or(options.language.as_str(), |(code, _)| code.as_str());
+
+        // Initialize parameters
+        let decoder = &options.decoder;
+        let strategy = match decoder.strategy {
+            DecodingStrategy::Greedy => SamplingStrategy::Greedy { best_of: 1 },
+            DecodingStrategy::BeamSearch => SamplingStrategy::BeamSearch {
+                beam_size: decoder.beam_size as i32,
+                patience: -1.0,
+            },
+        };
+        let mut params = FullParams::new(strategy);
+        params.set_language(Some(language));
+        params.set_n_threads(threads as i32);
+        params.set_temperature(decoder.temperature);
+        params.set_temperature_inc(decoder.temperature_increment);
+        params.set_print_special(false);
+        params.set_print_progress(false);
+        params.set_print_realtime(false);
+        params.set_print_timestamps(false);
+        params.set_suppress_blank(true);
+        params.set_suppress_non_speech_tokens(true);
+        params.set_no_speech_thold(decoder.no_speech_threshold);
+        // Per-token times, merged into words once decoding is done
+        params.set_token_timestamps(true);
+
+        // Enable translation to English if requested
+        if options.translate {
+            params.set_translate(true);
+        }
+
+        if let Some(prompt) = &options.initial_prompt {
+            params.set_initial_prompt(prompt);
+        }
+
+        let abort = AbortCheck {
+            options,
+            started: Instant::now(),
+        };
+        // SAFETY: `abort` lives until the end of this function, past the
+        // decode that calls back with it
+        unsafe {
+            params.set_abort_callback(Some(should_abort));
+            params.set_abort_callback_user_data(&abort as *const AbortCheck as *mut c_void);
+        }
+        let started = abort.started;
+
+        if let Err(e) = state.full(params, audio) {
+            // An aborted decode surfaces as a generic whisper error, report why
+            if let Some(interrupted) = options.interruption(started) {
+                return Err(interrupted.into());
+            }
+            return Err(anyhow::anyhow!("Whisper decoding failed: {}", e));
+        }
+
+        let num_segments = state
+            .full_n_segments()
+            .map_err(|e| anyhow::anyhow!("Failed to get number of segments: {}", e))?;
+
+        let mut text = String::new();
+        let mut segments = Vec::with_capacity(num_segments.max(0) as usize);
+
+        for i in 0..num_segments {
+            let segment_text = state
+                .full_get_segment_text(i)
+                .map_err(|e| anyhow::anyhow!("Failed to get segment text: {}", e))?;
+            // whisper reports segment times in 10ms units
+            let start_ms = state.full_get_segment_t0(i).unwrap_or(0) * 10;
+            let end_ms = state.full_get_segment_t1(i).unwrap_or(0) * 10;
+
+            let num_tokens = state.full_n_tokens(i).unwrap_or(0);
+            let mut tokens = Vec::with_capacity(num_tokens.max(0) as usize);
+            for j in 0..num_tokens {
+                let token_text = match state.full_get_token_text(i, j) {
+                    Ok(t) => t,
+                    Err(_) => continue,
+                };
+                // Skip special tokens such as [_BEG_] and [_TT_150]
+                if token_text.starts_with("[_") || token_text.starts_with("<|") {
+                    continue;
+                }
+                let Ok(data) = state.full_get_token_data(i, j) else {
+                    continue;
+                };
+                // Token times are in 10ms units as well, and can stray slightly
+                // outside their segment
+                tokens.push(TranscriptionToken {
+                    text: token_text,
+                    probability: data.p,
+                    start_ms: (data.t0 * 10).max(start_ms).min(end_ms),
+                    end_ms: (data.t1 * 10).max(start_ms).min(end_ms),
+                });
+            }
+
+            text.push_str(&segment_text);
+            segments.push(TranscriptionSegment {
+                start_ms,
+                end_ms,
+                text: segment_text.trim().to_string(),
+                tokens,
+                // The estimate only covers the first window
+                no_speech_probability: no_speech.filter(|_| start_ms < WINDOW_MS),
+            });
+        }
+
+        let (language, language_probability) = match detected {
+            Some((code, probability)) => (Some(code), Some(probability)),
+            None if options.language == "auto" => (
+                state
+                    .full_lang_id_from_state()
+                    .ok()
+                    .and_then(whisper_rs::get_lang_str)
+                    .map(|lang| lang.to_string()),
+                None,
+            ),
+            None => (Some(options.language.clone()), None),
+        };
+
+        Ok(TranscriptionResult {
+            text,
+            segments,
+            language,
+            language_probability,
+            filtered: Vec::new(),
+            words: Vec::new(),
+        })
+    }
+
+    fn capabilities(&self) -> EngineCapabilities {
+        EngineCapabilities {
+            translation: true,
+            language_detection: true,
+            initial_prompt: true,
+            segment_timestamps: true,
+            token_probabilities: true,
+            word_timestamps: true,
+            remote: false,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::engines::CancellationToken;
+    use std::time::Duration;
+
+    fn options(timeout: Option<Duration>) -> TranscribeOptions {
+        TranscribeOptions {
+            language: "en".to_string(),
+            detect_languages: Vec::new(),
+            translate: false,
+            initial_prompt: None,
+            cancel: CancellationToken::default(),
+            timeout,
+            no_speech_probability: false,
+            decoder: Default::default(),
+        }
+    }
+
+    fn call(check: &AbortCheck) -> bool {
+        unsafe { should_abort(check as *const AbortCheck as *mut c_void) }
+    }
+
+    #[test]
+    fn abort_callback_follows_cancel_and_timeout() {
+        let running = options(Some(Duration::from_secs(60)));
+        let check = AbortCheck {
+            options: &running,
+            started: Instant::now(),
+        };
+        assert!(!call(&check));
+
+        running.cancel.cancel();
+        assert!(call(&check));
+
+        let timed_out = options(Some(Duration::from_millis(10)));
+        let check = AbortCheck {
+            options: &timed_out,
+            started: Instant::now() - Duration::from_millis(20),
+        };
+        assert!(call(&check));
+    }
+}
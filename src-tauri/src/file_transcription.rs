@@ -0,0 +1,288 @@
+use crate::audio_toolkit::constants::WHISPER_SAMPLE_RATE;
+use crate::engines::TranscriptionResult;
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+
+/// Long files are cut into chunks of roughly this length so progress can be
+/// reported and memory stays bounded.
+const CHUNK_SECONDS: usize = 30;
+/// How far back from the nominal chunk end to look for a quiet cut point.
+const CUT_SEARCH_SECONDS: usize = 5;
+/// Window used when measuring loudness around a cut point.
+const CUT_WINDOW_MS: usize = 100;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum OutputFormat {
+    Text,
+    Srt,
+    Vtt,
+    Json,
+}
+
+impl std::str::FromStr for OutputFormat {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s.to_lowercase().as_str() {
+            "text" | "txt" => Ok(OutputFormat::Text),
+            "srt" => Ok(OutputFormat::Srt),
+            "vtt" | "webvtt" => Ok(OutputFormat::Vtt),
+            "json" => Ok(OutputFormat::Json),
+            other => Err(anyhow::anyhow!("Unknown output format: {}", other)),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FileTranscriptionProgress {
+    pub path: String,
+    pub processed_ms: u64,
+    pub total_ms: u64,
+    pub percentage: f64,
+}
+
+fn samples_to_ms(samples: usize) -> u64 {
+    samples as u64 * 1000 / WHISPER_SAMPLE_RATE as u64
+}
+
+/// Picks the end of the next chunk, preferring the quietest window near the
+/// nominal boundary so words are not cut in half.
+fn find_chunk_end(samples: &[f32], start: usize) -> usize {
+    let rate = WHISPER_SAMPLE_RATE as usize;
+    let nominal_end = start + CHUNK_SECONDS * rate;
+    if nominal_end >= samples.len() {
+        return samples.len();
+    }
+
+    let window = CUT_WINDOW_MS * rate / 1000;
+    let search_start = nominal_end
+        .saturating_sub(CUT_SEARCH_SECONDS * rate)
+        .max(start + window);
+
+    let mut best_end = nominal_end;
+    let mut best_energy = f32::MAX;
+    let mut pos = search_start;
+    while pos + window <= nominal_end {
+        let energy: f32 = samples[pos..pos + window].iter().map(|s| s * s).sum();
+        if energy < best_energy {
+            best_energy = energy;
+            best_end = pos + window / 2;
+        }
+        pos += window;
+    }
+
+    best_end
+}
+
+/// Runs `transcribe` over `samples` chunk by chunk and stitches the results
+/// together with corrected segment times. `on_progress` receives the number of
+/// processed and total samples after each chunk.
+pub fn transcribe_in_chunks<T, P>(
+    samples: &[f32],
+    mut transcribe: T,
+    mut on_progress: P,
+) -> Result<TranscriptionResult>
+where
+    T: FnMut(Vec<f32>) -> Result<TranscriptionResult>,
+    P: FnMut(usize, usize),
+{
+    let mut result = TranscriptionResult::default();
+    let mut start = 0;
+
+    on_progress(0, samples.len());
+
+    while start < samples.len() {
+        let end = find_chunk_end(samples, start);
+        let mut chunk = samples[start..end].to_vec();
+
+        // whisper needs at least a second of audio to produce anything useful
+        if chunk.len() < WHISPER_SAMPLE_RATE as usize {
+            chunk.resize(WHISPER_SAMPLE_RATE as usize * 5 / 4, 0.0);
+        }
+
+        let chunk_result = transcribe(chunk)?;
+        result.append(chunk_result, samples_to_ms(start) as i64);
+
+        start = end;
+        on_progress(start, samples.len());
+    }
+
+    Ok(result)
+}
+
+fn format_timestamp(ms: i64, decimal_separator: char) -> String {
+    let ms = ms.max(0);
+    format!(
+        "{:02}:{:02}:{:02}{}{:03}",
+        ms / 3_600_000,
+        (ms / 60_000) % 60,
+        (ms / 1000) % 60,
+        decimal_separator,
+        ms % 1000
+    )
+}
+
+pub fn format_result(result: &TranscriptionResult, format: OutputFormat) -> Result<String> {
+    let output = match format {
+        OutputFormat::Text => format!("{}\n", result.text),
+        OutputFormat::Json => serde_json::to_string_pretty(result)?,
+        OutputFormat::Srt => {
+            let mut out = String::new();
+            for (i, segment) in result
+                .segments
+                .iter()
+                .filter(|s| !s.text.is_empty())
+                .enumerate()
+            {
+                out.push_str(&format!(
+                    "{}\n{} --> {}\n{}\n\n",
+                    i + 1,
+                    format_timestamp(segment.start_ms, ','),
+                    format_timestamp(segment.end_ms, ','),
+                    segment.text
+                ));
+            }
+            out
+        }
+        OutputFormat::Vtt => {
+            let mut out = String::from("WEBVTT\n\n");
+            for segment in result.segments.iter().filter(|s| !s.text.is_empty()) {
+                out.push_str(&format!(
+                    "{} --> {}\n{}\n\n",
+                    format_timestamp(segment.start_ms, '.'),
+                    format_timestamp(segment.end_ms, '.'),
+                    segment.text
+                ));
+            }
+            out
+        }
+    };
+
+    Ok(output)
+}
+
+pub fn progress_event(path: &str, processed: usize, total: usize) -> FileTranscriptionProgress {
+    FileTranscriptionProgress {
+        path: path.to_string(),
+        processed_ms: samples_to_ms(processed),
+        total_ms: samples_to_ms(total),
+        percentage: if total > 0 {
+            (processed as f64 / total as f64) * 100.0
+        } else {
+            100.0
+        },
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::engines::TranscriptionSegment;
+
+    fn segment(start_ms: i64, end_ms: i64, text: &str) -> TranscriptionSegment {
+        TranscriptionSegment {
+            start_ms,
+            end_ms,
+            text: text.to_string(),
+            ..Default::default()
+        }
+    }
+
+    fn result() -> TranscriptionResult {
+        TranscriptionResult {
+            text: "Hello there. General Kenobi.".to_string(),
+            segments: vec![
+                segment(0, 1_250, "Hello there."),
+                segment(1_250, 2_000, ""),
+                segment(3_599_500, 3_725_042, "General Kenobi."),
+            ],
+            language: Some("en".to_string()),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn srt_numbers_cues_and_uses_commas() {
+        assert_eq!(
+            format_result(&result(), OutputFormat::Srt).unwrap(),
+            "1\n00:00:00,000 --> 00:00:01,250\nHello there.\n\n\
+             2\n00:59:59,500 --> 01:02:05,042\nGeneral Kenobi.\n\n"
+        );
+    }
+
+    #[test]
+    fn vtt_has_a_header_and_uses_dots() {
+        assert_eq!(
+            format_result(&result(), OutputFormat::Vtt).unwrap(),
+            "WEBVTT\n\n\
+             00:00:00.000 --> 00:00:01.250\nHello there.\n\n\
+             00:59:59.500 --> 01:02:05.042\nGeneral Kenobi.\n\n"
+        );
+    }
+
+    #[test]
+    fn text_and_json_output() {
+        assert_eq!(
+            format_result(&result(), OutputFormat::Text).unwrap(),
+            "Hello there. General Kenobi.\n"
+        );
+
+        let json = format_result(&result(), OutputFormat::Json).unwrap();
+        let parsed: TranscriptionResult = serde_json::from_str(&json).unwrap();
+        assert_eq!(parsed.text, "Hello there. General Kenobi.");
+        assert_eq!(parsed.segments.len(), 3);
+        assert_eq!(parsed.segments[2].end_ms, 3_725_042);
+        assert_eq!(parsed.language.as_deref(), Some("en"));
+    }
+
+    #[test]
+    fn timestamps_clamp_negative_times() {
+        assert_eq!(format_timestamp(-20, ','), "00:00:00,000");
+        assert_eq!(format_timestamp(100 * 3_600_000 + 1, '.'), "100:00:00.001");
+    }
+
+    #[test]
+    fn output_formats_parse() {
+        assert_eq!("SRT".parse::<OutputFormat>().unwrap(), OutputFormat::Srt);
+        assert_eq!("webvtt".parse::<OutputFormat>().unwrap(), OutputFormat::Vtt);
+        assert_eq!("txt".parse::<OutputFormat>().unwrap(), OutputFormat::Text);
+        assert!("docx".parse::<OutputFormat>().is_err());
+    }
+
+    #[test]
+    fn chunks_are_stitched_with_their_offsets() {
+        let rate = WHISPER_SAMPLE_RATE as usize;
+        // 70 seconds with a quiet stretch just before the 30 second mark
+        let mut samples = vec![0.5; 70 * rate];
+        samples[28 * rate..29 * rate].fill(0.0);
+
+        let mut chunk_lengths = Vec::new();
+        let mut progress = Vec::new();
+        let result = transcribe_in_chunks(
+            &samples,
+            |chunk| {
+                chunk_lengths.push(chunk.len());
+                Ok(TranscriptionResult {
+                    text: format!("chunk{}", chunk_lengths.len()),
+                    segments: vec![segment(0, 1_000, &format!("chunk{}", chunk_lengths.len()))],
+                    ..Default::default()
+                })
+            },
+            |processed, total| progress.push((processed, total)),
+        )
+        .unwrap();
+
+        // The first cut lands in the quiet stretch rather than at 30 seconds
+        let first_cut = chunk_lengths[0];
+        assert!((28 * rate..29 * rate).contains(&first_cut), "{}", first_cut);
+        assert_eq!(chunk_lengths.iter().sum::<usize>(), samples.len());
+        assert_eq!(progress.first(), Some(&(0, samples.len())));
+        assert_eq!(progress.last(), Some(&(samples.len(), samples.len())));
+
+        let starts: Vec<i64> = result.segments.iter().map(|s| s.start_ms).collect();
+        assert_eq!(starts[0], 0);
+        assert_eq!(starts[1], samples_to_ms(first_cut) as i64);
+        assert_eq!(result.segments.len(), chunk_lengths.len());
+    }
+}
@@ -0,0 +1,376 @@
+//! Drops text whisper tends to invent on silence and noise, such as "Thank
+//! you for watching." or "[BLANK_AUDIO]", before it reaches the clipboard.
+
+use crate::audio_toolkit::constants::WHISPER_SAMPLE_RATE;
+use crate::engines::{FilterReason, FilteredSegment, TranscriptionResult, TranscriptionSegment};
+use flate2::write::ZlibEncoder;
+use flate2::Compression;
+use once_cell::sync::Lazy;
+use regex::Regex;
+use std::io::Write;
+
+/// Segments whisper rates at least this likely to be silence are dropped when
+/// their text is unlikely too. The thresholds whisper itself uses to skip
+/// silent windows.
+const NO_SPEECH_PROBABILITY: f32 = 0.6;
+const MAX_MEAN_LOG_PROBABILITY: f32 = -1.0;
+
+/// Without a no-speech probability, a segment only counts as silence when its
+/// audio is quieter than this RMS and the decoder was unsure of the text.
+const SILENCE_RMS: f32 = 0.002;
+const MIN_MEAN_TOKEN_PROBABILITY: f32 = 0.25;
+
+/// A segment repeating the one before it is only dropped from this copy on.
+/// People do say a sentence twice, whisper stuck in a loop goes on longer.
+const MAX_REPEATS: usize = 2;
+
+/// Text that compresses better than this is a decoder loop, such as the same
+/// sentence over and over. The same limit OpenAI's reference decoder uses.
+const MAX_COMPRESSION_RATIO: f32 = 2.4;
+
+/// Annotations such as "[BLANK_AUDIO]", "(music)" or "*laughs*". Round
+/// brackets and asterisks only count with up to three words inside, so
+/// dictated asides are kept.
+static ANNOTATION: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(
+        r"\[[^\]]*\]|\(\s*[^()\s]+(?:\s+[^()\s]+){0,2}\s*\)|\*\s*[^*\s]+(?:\s+[^*\s]+){0,2}\s*\*|[♪♫]+",
+    )
+    .unwrap()
+});
+
+/// Phrases whisper is known to produce from silence, mostly subtitle credits
+/// from its training data.
+pub fn builtin_hallucinations() -> Vec<String> {
+    [
+        "thank you for watching",
+        "thanks for watching",
+        "thank you so much for watching",
+        "thank you for watching and see you next time",
+        "please subscribe",
+        "like and subscribe",
+        "please like and subscribe",
+        "subscribe to my channel",
+        "subtitles by the amara.org community",
+        "transcription by castingwords",
+        "untertitel im auftrag des zdf für funk 2017",
+        "untertitel der amara.org-community",
+        "sous-titres réalisés para la communauté d'amara.org",
+        "sous-titrage st' 501",
+        "subtítulos realizados por la comunidad de amara.org",
+        "sottotitoli creati dalla comunità amara.org",
+    ]
+    .iter()
+    .map(|p| p.to_string())
+    .collect()
+}
+
+/// Lowercases and keeps only letters, digits and single spaces.
+fn normalize(text: &str) -> String {
+    text.split_whitespace()
+        .map(|word| {
+            word.chars()
+                .filter(|c| c.is_alphanumeric())
+                .flat_map(|c| c.to_lowercase())
+                .collect::<String>()
+        })
+        .filter(|word| !word.is_empty())
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+fn compression_ratio(text: &str) -> f32 {
+    let bytes = text.as_bytes();
+    if bytes.is_empty() {
+        return 0.0;
+    }
+    let mut encoder = ZlibEncoder::new(Vec::new(), Compression::default());
+    if encoder.write_all(bytes).is_err() {
+        return 0.0;
+    }
+    match encoder.finish() {
+        Ok(compressed) if !compressed.is_empty() => bytes.len() as f32 / compressed.len() as f32,
+        _ => 0.0,
+    }
+}
+
+/// RMS of the audio a segment covers, `None` when the times fall outside it.
+fn segment_rms(audio: &[f32], segment: &TranscriptionSegment) -> Option<f32> {
+    let to_sample = |ms: i64| ms.max(0) as usize * WHISPER_SAMPLE_RATE as usize / 1000;
+    let start = to_sample(segment.start_ms).min(audio.len());
+    let end = to_sample(segment.end_ms).min(audio.len());
+    if end <= start {
+        return None;
+    }
+    let samples = &audio[start..end];
+    let sum: f32 = samples.iter().map(|s| s * s).sum();
+    Some((sum / samples.len() as f32).sqrt())
+}
+
+fn mean_token_probability(segment: &TranscriptionSegment) -> Option<f32> {
+    if segment.tokens.is_empty() {
+        return None;
+    }
+    let sum: f32 = segment.tokens.iter().map(|t| t.probability).sum();
+    Some(sum / segment.tokens.len() as f32)
+}
+
+fn mean_log_probability(segment: &TranscriptionSegment) -> Option<f32> {
+    if segment.tokens.is_empty() {
+        return None;
+    }
+    let sum: f32 = segment
+        .tokens
+        .iter()
+        .map(|t| t.probability.max(f32::MIN_POSITIVE).ln())
+        .sum();
+    Some(sum / segment.tokens.len() as f32)
+}
+
+/// Whether a segment was decoded from silence or noise. Uses whisper's own
+/// no-speech probability when the engine reported one, otherwise requires the
+/// audio to be near silent and the decoder to be unsure, so speech is never
+/// dropped on one weak signal.
+fn is_no_speech(segment: &TranscriptionSegment, audio: &[f32]) -> bool {
+    match segment.no_speech_probability {
+        Some(probability) => {
+            probability >= NO_SPEECH_PROBABILITY
+                && mean_log_probability(segment).map_or(true, |p| p < MAX_MEAN_LOG_PROBABILITY)
+        }
+        None => {
+            let silent = segment_rms(audio, segment).map_or(false, |rms| rms < SILENCE_RMS);
+            let unsure =
+                mean_token_probability(segment).map_or(true, |p| p < MIN_MEAN_TOKEN_PROBABILITY);
+            silent && unsure
+        }
+    }
+}
+
+pub struct HallucinationFilter {
+    /// Normalized built-in and user phrases
+    blocklist: Vec<String>,
+}
+
+impl HallucinationFilter {
+    /// `user_phrases` are blocked on top of the built-in ones.
+    pub fn new(user_phrases: &[String]) -> Self {
+        let blocklist = builtin_hallucinations()
+            .iter()
+            .chain(user_phrases)
+            .map(|p| normalize(p))
+            .filter(|p| !p.is_empty())
+            .collect();
+        Self { blocklist }
+    }
+
+    /// Why a segment should be dropped, if it should. `text` is the segment
+    /// text with annotations removed, `previous` the normalized text of the
+    /// kept segment before it with how many times in a row it was kept.
+    fn check(
+        &self,
+        segment: &TranscriptionSegment,
+        text: &str,
+        audio: &[f32],
+        previous: Option<(&str, usize)>,
+    ) -> Option<FilterReason> {
+        let normalized = normalize(text);
+        if normalized.is_empty() {
+            return Some(FilterReason::Annotation);
+        }
+        if self.blocklist.contains(&normalized) {
+            return Some(FilterReason::Blocklisted);
+        }
+        if is_no_speech(segment, audio) {
+            return Some(FilterReason::NoSpeech);
+        }
+        if compression_ratio(text) > MAX_COMPRESSION_RATIO {
+            return Some(FilterReason::Repetitive);
+        }
+        if matches!(previous, Some((text, count)) if text == normalized && count >= MAX_REPEATS) {
+            return Some(FilterReason::Repeated);
+        }
+        None
+    }
+
+    /// Removes hallucinated segments from a raw result and rebuilds its text
+    /// from what is left. `audio` is what the result was decoded from.
+    pub fn apply(&self, result: TranscriptionResult, audio: &[f32]) -> TranscriptionResult {
+        // Without segments there are no times to check the audio against
+        if result.segments.is_empty() {
+            return result;
+        }
+
+        let TranscriptionResult {
+            segments,
+            mut filtered,
+            ..
+        } = result;
+
+        let mut kept: Vec<TranscriptionSegment> = Vec::with_capacity(segments.len());
+        let mut previous: Option<(String, usize)> = None;
+
+        for mut segment in segments {
+            let text = ANNOTATION.replace_all(&segment.text, "");
+            let text = text.split_whitespace().collect::<Vec<_>>().join(" ");
+
+            let last = previous
+                .as_ref()
+                .map(|(text, count)| (text.as_str(), *count));
+            match self.check(&segment, &text, audio, last) {
+                Some(reason) => {
+                    println!("Filtered segment ({:?}): {}", reason, segment.text);
+                    filtered.push(FilteredSegment {
+                        start_ms: segment.start_ms,
+                        end_ms: segment.end_ms,
+                        text: segment.text,
+                        reason,
+                    });
+                }
+                None => {
+                    let normalized = normalize(&text);
+                    previous = match previous {
+                        Some((last, count)) if last == normalized => Some((last, count + 1)),
+                        _ => Some((normalized, 1)),
+                    };
+                    segment.text = text;
+                    kept.push(segment);
+                }
+            }
+        }
+
+        let text = kept
+            .iter()
+            .map(|s| s.text.as_str())
+            .collect::<Vec<_>>()
+            .join(" ");
+
+        TranscriptionResult {
+            text,
+            segments: kept,
+            filtered,
+            ..result
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::engines::TranscriptionToken;
+
+    /// One second of a tone loud enough to count as speech.
+    fn speech() -> Vec<f32> {
+        (0..WHISPER_SAMPLE_RATE)
+            .map(|i| (i as f32 * 0.05).sin() * 0.2)
+            .collect()
+    }
+
+    fn segment(text: &str, token_probability: f32) -> TranscriptionSegment {
+        TranscriptionSegment {
+            start_ms: 0,
+            end_ms: 1000,
+            text: text.to_string(),
+            tokens: text
+                .split_whitespace()
+                .map(|word| TranscriptionToken {
+                    text: format!(" {}", word),
+                    probability: token_probability,
+                    ..Default::default()
+                })
+                .collect(),
+            no_speech_probability: None,
+        }
+    }
+
+    fn filter(segments: Vec<TranscriptionSegment>, audio: &[f32]) -> TranscriptionResult {
+        let result = TranscriptionResult {
+            segments,
+            ..Default::default()
+        };
+        HallucinationFilter::new(&["Custom outro".to_string()]).apply(result, audio)
+    }
+
+    fn reasons(result: &TranscriptionResult) -> Vec<FilterReason> {
+        result.filtered.iter().map(|f| f.reason).collect()
+    }
+
+    #[test]
+    fn annotations_are_dropped() {
+        let result = filter(vec![segment("[BLANK_AUDIO]", 0.9)], &speech());
+        assert_eq!(reasons(&result), [FilterReason::Annotation]);
+
+        // Annotations inside speech are removed, the speech is kept
+        let result = filter(vec![segment("(coughs) Let's begin.", 0.9)], &speech());
+        assert_eq!(result.text, "Let's begin.");
+    }
+
+    #[test]
+    fn blocklisted_phrases_are_dropped() {
+        let result = filter(
+            vec![
+                segment("Thanks for watching!", 0.9),
+                segment("Custom outro.", 0.9),
+            ],
+            &speech(),
+        );
+        assert_eq!(
+            reasons(&result),
+            [FilterReason::Blocklisted, FilterReason::Blocklisted]
+        );
+        assert_eq!(result.text, "");
+    }
+
+    #[test]
+    fn no_speech_uses_whisper_probability() {
+        let mut silent = segment("I'm here.", 0.2);
+        silent.no_speech_probability = Some(0.9);
+        // Confident text is kept even when whisper suspects silence
+        let mut confident = segment("Still here.", 0.9);
+        confident.no_speech_probability = Some(0.9);
+
+        let result = filter(vec![silent, confident], &speech());
+        assert_eq!(reasons(&result), [FilterReason::NoSpeech]);
+        assert_eq!(result.text, "Still here.");
+    }
+
+    #[test]
+    fn no_speech_without_probability_needs_silence_and_doubt() {
+        let silence = vec![0.0; WHISPER_SAMPLE_RATE as usize];
+
+        let result = filter(vec![segment("You", 0.1)], &silence);
+        assert_eq!(reasons(&result), [FilterReason::NoSpeech]);
+
+        // Either signal alone is not enough
+        let result = filter(vec![segment("Quietly said.", 0.9)], &silence);
+        assert!(result.filtered.is_empty());
+        let result = filter(vec![segment("Mumbled words.", 0.1)], &speech());
+        assert!(result.filtered.is_empty());
+    }
+
+    #[test]
+    fn decoder_loops_are_dropped() {
+        let looped = "the end ".repeat(20);
+        let result = filter(vec![segment(&looped, 0.9)], &speech());
+        assert_eq!(reasons(&result), [FilterReason::Repetitive]);
+    }
+
+    #[test]
+    fn sentences_said_twice_are_kept() {
+        let result = filter(
+            vec![segment("Say it again.", 0.9), segment("Say it again.", 0.9)],
+            &speech(),
+        );
+        assert!(result.filtered.is_empty());
+        assert_eq!(result.text, "Say it again. Say it again.");
+    }
+
+    #[test]
+    fn longer_runs_of_the_same_segment_are_dropped() {
+        let segments = (0..4).map(|_| segment("Thank you.", 0.9)).collect();
+        let result = filter(segments, &speech());
+        assert_eq!(
+            reasons(&result),
+            [FilterReason::Repeated, FilterReason::Repeated]
+        );
+        assert_eq!(result.text, "Thank you. Thank you.");
+    }
+}
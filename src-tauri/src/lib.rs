@@ -1,16 +1,27 @@
 mod actions;
 mod audio_feedback;
 pub mod audio_toolkit;
+mod benchmark;
+pub mod cli;
 mod clipboard;
 mod commands;
+mod engines;
+mod file_transcription;
+mod hallucination_filter;
+mod llm_refinement;
 mod managers;
 mod overlay;
 mod settings;
 mod shortcut;
+#[cfg(test)]
+mod test_server;
+mod text_processing;
 mod tray;
 mod utils;
 
+use managers::api_server::ApiServerManager;
 use managers::audio::AudioRecordingManager;
+use managers::history::HistoryManager;
 use managers::model::ModelManager;
 use managers::transcription::TranscriptionManager;
 use std::collections::HashMap;
@@ -144,11 +155,20 @@ pub fn run() {
                 TranscriptionManager::new(&app, model_manager.clone())
                     .expect("Failed to initialize transcription manager"),
             );
+            let history_manager =
+                Arc::new(HistoryManager::new(&app).expect("Failed to initialize history manager"));
 
             // Add managers to Tauri's managed state
             app.manage(recording_manager.clone());
             app.manage(model_manager.clone());
             app.manage(transcription_manager.clone());
+            transcription_manager.start_idle_watcher();
+            transcription_manager.start_job_worker();
+            app.manage(history_manager.clone());
+
+            // Started after the transcription manager it hands requests to
+            let api_server_manager = Arc::new(ApiServerManager::new(&app.handle()));
+            app.manage(api_server_manager);
 
             // Create the recording overlay window (hidden by default)
             utils::create_recording_overlay(&app.handle());
@@ -185,10 +205,50 @@ pub fn run() {
             shortcut::change_audio_feedback_setting,
             shortcut::change_translate_to_english_setting,
             shortcut::change_selected_language_setting,
+            shortcut::change_auto_detect_languages_setting,
+            shortcut::change_favorite_languages_setting,
             shortcut::change_overlay_position_setting,
             shortcut::change_debug_mode_setting,
             shortcut::change_word_correction_threshold_setting,
             shortcut::update_custom_words,
+            shortcut::update_custom_word_aliases,
+            shortcut::update_language_custom_words,
+            shortcut::update_spoken_commands,
+            shortcut::get_builtin_spoken_commands,
+            shortcut::change_literal_mode_setting,
+            shortcut::update_filler_words,
+            shortcut::get_builtin_filler_words,
+            shortcut::change_hallucination_filter_setting,
+            shortcut::update_hallucination_blocklist,
+            shortcut::get_builtin_hallucination_phrases,
+            shortcut::get_decoder_settings,
+            shortcut::save_decoder_settings,
+            shortcut::change_force_cpu_setting,
+            shortcut::change_llm_settings,
+            shortcut::save_refinement_prompt,
+            shortcut::delete_refinement_prompt,
+            shortcut::change_selected_refinement_prompt,
+            shortcut::change_api_server_setting,
+            shortcut::regenerate_api_server_token,
+            shortcut::get_api_server_status,
+            shortcut::change_streaming_transcription_setting,
+            shortcut::change_initial_prompt_template_setting,
+            shortcut::change_prompt_context_length_setting,
+            shortcut::change_model_unload_timeout_setting,
+            shortcut::change_low_memory_unload_threshold_setting,
+            shortcut::change_max_decode_seconds_setting,
+            shortcut::add_cancel_binding,
+            shortcut::add_cycle_language_binding,
+            shortcut::get_processing_chains,
+            shortcut::save_processing_chain,
+            shortcut::delete_processing_chain,
+            shortcut::change_processing_chain_setting,
+            shortcut::get_transcription_profiles,
+            shortcut::save_transcription_profile,
+            shortcut::delete_transcription_profile,
+            shortcut::set_binding_profile,
+            shortcut::add_transcribe_binding,
+            shortcut::remove_binding,
             shortcut::suspend_binding,
             shortcut::resume_binding,
             trigger_update_check,
@@ -197,6 +257,9 @@ pub fn run() {
             commands::models::get_available_models,
             commands::models::get_model_info,
             commands::models::download_model,
+            commands::models::run_model_benchmark,
+            commands::models::get_remote_model,
+            commands::models::save_remote_model,
             commands::models::delete_model,
             commands::models::cancel_download,
             commands::models::set_active_model,
@@ -213,7 +276,17 @@ pub fn run() {
             commands::audio::get_selected_microphone,
             commands::audio::get_available_output_devices,
             commands::audio::set_selected_output_device,
-            commands::audio::get_selected_output_device
+            commands::audio::get_selected_output_device,
+            commands::history::get_history_entries,
+            commands::history::search_history,
+            commands::history::delete_history_entry,
+            commands::history::clear_history,
+            commands::history::copy_history_entry,
+            commands::history::repaste_history_entry,
+            commands::history::export_history,
+            commands::transcription::transcribe_file,
+            commands::transcription::preview_text_processing,
+            commands::transcription::test_refinement
         ])
         .run(tauri::generate_context!())
         .expect("error while running tauri application");
@@ -0,0 +1,308 @@
+//! Optional rewrite of a finished transcript by a language model, e.g. to
+//! clean up a dictation or turn it into a commit message. Talks to any
+//! OpenAI-compatible chat completions endpoint, such as llama.cpp's server or
+//! Ollama running locally.
+
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::time::Duration;
+
+/// Where and how to reach the model.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct LlmSettings {
+    /// Base URL of the API, e.g. `http://localhost:11434/v1`
+    #[serde(default = "default_endpoint")]
+    pub endpoint: String,
+    /// Model name sent with each request, servers hosting one model ignore it
+    #[serde(default)]
+    pub model: String,
+    /// Sent as a bearer token when set
+    #[serde(default)]
+    pub api_key: Option<String>,
+    /// The raw transcript is used when the model takes longer than this
+    #[serde(default = "default_timeout_seconds")]
+    pub timeout_seconds: u32,
+}
+
+fn default_endpoint() -> String {
+    "http://localhost:11434/v1".to_string()
+}
+
+fn default_timeout_seconds() -> u32 {
+    10
+}
+
+impl Default for LlmSettings {
+    fn default() -> Self {
+        Self {
+            endpoint: default_endpoint(),
+            model: String::new(),
+            api_key: None,
+            timeout_seconds: default_timeout_seconds(),
+        }
+    }
+}
+
+/// Longest timeout accepted, a slower model would hold up every paste.
+const MAX_TIMEOUT_SECONDS: u32 = 120;
+
+impl LlmSettings {
+    pub fn validate(&self) -> Result<()> {
+        let endpoint = self.endpoint.trim();
+        if !endpoint.starts_with("http://") && !endpoint.starts_with("https://") {
+            return Err(anyhow::anyhow!(
+                "Endpoint must be an http:// or https:// URL"
+            ));
+        }
+        if !(1..=MAX_TIMEOUT_SECONDS).contains(&self.timeout_seconds) {
+            return Err(anyhow::anyhow!(
+                "Timeout must be between 1 and {} seconds",
+                MAX_TIMEOUT_SECONDS
+            ));
+        }
+        Ok(())
+    }
+
+    /// The chat completions URL, the endpoint may be given with or without it.
+    fn completions_url(&self) -> String {
+        let endpoint = self.endpoint.trim().trim_end_matches('/');
+        if endpoint.ends_with("/chat/completions") {
+            endpoint.to_string()
+        } else {
+            format!("{}/chat/completions", endpoint)
+        }
+    }
+}
+
+/// Instructions for the model. `{language}` expands to the transcript's
+/// language code.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct RefinementPrompt {
+    pub id: String,
+    pub name: String,
+    pub template: String,
+}
+
+pub fn default_refinement_prompts() -> HashMap<String, RefinementPrompt> {
+    [
+        (
+            "clean_up",
+            "Clean up",
+            "You clean up dictated text. Fix punctuation, capitalization and \
+             obvious recognition errors, and remove false starts and repeated \
+             words. Keep the wording and the language ({language}) otherwise. \
+             Reply with the cleaned text only.",
+        ),
+        (
+            "bullet_points",
+            "Bullet points",
+            "You turn dictated notes into a concise bulleted list, one idea per \
+             bullet, written in {language}. Reply with the list only, using \"- \" \
+             for bullets.",
+        ),
+        (
+            "commit_message",
+            "Commit message",
+            "You turn a spoken description of a code change into a git commit \
+             message: an imperative subject line of at most 72 characters, a \
+             blank line, then a short body if there is more to say. Reply with \
+             the commit message only.",
+        ),
+    ]
+    .into_iter()
+    .map(|(id, name, template)| {
+        (
+            id.to_string(),
+            RefinementPrompt {
+                id: id.to_string(),
+                name: name.to_string(),
+                template: template.to_string(),
+            },
+        )
+    })
+    .collect()
+}
+
+#[derive(Serialize)]
+struct ChatMessage<'a> {
+    role: &'a str,
+    content: &'a str,
+}
+
+#[derive(Serialize)]
+struct ChatRequest<'a> {
+    model: &'a str,
+    messages: Vec<ChatMessage<'a>>,
+    temperature: f32,
+    stream: bool,
+}
+
+#[derive(Deserialize)]
+struct ChatResponse {
+    choices: Vec<ChatChoice>,
+}
+
+#[derive(Deserialize)]
+struct ChatChoice {
+    message: ChatResponseMessage,
+}
+
+#[derive(Deserialize)]
+struct ChatResponseMessage {
+    content: String,
+}
+
+/// Sends `text` to the model with the prompt's instructions and returns its
+/// reply. Fails on network errors, timeouts, error statuses and empty replies,
+/// callers fall back to the unrefined text.
+pub async fn refine(
+    settings: &LlmSettings,
+    prompt: &RefinementPrompt,
+    text: &str,
+    language: &str,
+) -> Result<String> {
+    let instructions = prompt.template.replace("{language}", language);
+    let body = ChatRequest {
+        model: &settings.model,
+        messages: vec![
+            ChatMessage {
+                role: "system",
+                content: &instructions,
+            },
+            ChatMessage {
+                role: "user",
+                content: text,
+            },
+        ],
+        temperature: 0.2,
+        stream: false,
+    };
+
+    let client = reqwest::Client::new();
+    let mut request = client
+        .post(settings.completions_url())
+        .timeout(Duration::from_secs(settings.timeout_seconds as u64))
+        .json(&body);
+    if let Some(api_key) = settings.api_key.as_deref().filter(|k| !k.is_empty()) {
+        request = request.bearer_auth(api_key);
+    }
+
+    let response = request.send().await.map_err(|e| {
+        if e.is_timeout() {
+            anyhow::anyhow!("Model did not answer within {}s", settings.timeout_seconds)
+        } else {
+            anyhow::anyhow!("Request to {} failed: {}", settings.endpoint, e)
+        }
+    })?;
+
+    let status = response.status();
+    if !status.is_success() {
+        let detail = response.text().await.unwrap_or_default();
+        return Err(anyhow::anyhow!(
+            "Model endpoint returned {}: {}",
+            status,
+            detail.trim()
+        ));
+    }
+
+    let response: ChatResponse = response
+        .json()
+        .await
+        .map_err(|e| anyhow::anyhow!("Unexpected response from model endpoint: {}", e))?;
+    let reply = response
+        .choices
+        .into_iter()
+        .next()
+        .map(|choice| choice.message.content.trim().to_string())
+        .unwrap_or_default();
+
+    if reply.is_empty() {
+        return Err(anyhow::anyhow!("Model returned an empty reply"));
+    }
+    Ok(reply)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_server::{StubResponse, StubServer};
+    use serde_json::json;
+
+    fn settings(server: &StubServer) -> LlmSettings {
+        LlmSettings {
+            endpoint: format!("{}/v1", server.url),
+            model: "test-model".to_string(),
+            api_key: Some("secret".to_string()),
+            timeout_seconds: 1,
+        }
+    }
+
+    fn clean_up() -> RefinementPrompt {
+        default_refinement_prompts().remove("clean_up").unwrap()
+    }
+
+    fn reply(content: &str) -> serde_json::Value {
+        json!({"choices": [{"message": {"role": "assistant", "content": content}}]})
+    }
+
+    #[test]
+    fn returns_the_model_reply() {
+        let server = StubServer::start(vec![StubResponse::json(200, reply(" Hello, world. \n"))]);
+
+        let refined = tauri::async_runtime::block_on(refine(
+            &settings(&server),
+            &clean_up(),
+            "hello world",
+            "de",
+        ))
+        .unwrap();
+        assert_eq!(refined, "Hello, world.");
+
+        let requests = server.requests();
+        assert_eq!(requests.len(), 1);
+        assert_eq!(requests[0].path, "/v1/chat/completions");
+        assert_eq!(requests[0].header("authorization"), Some("Bearer secret"));
+        let body: serde_json::Value = serde_json::from_slice(&requests[0].body).unwrap();
+        assert_eq!(body["model"], "test-model");
+        assert!(body["messages"][0]["content"]
+            .as_str()
+            .unwrap()
+            .contains("(de)"));
+        assert_eq!(body["messages"][1]["content"], "hello world");
+    }
+
+    #[test]
+    fn error_statuses_fail() {
+        let server = StubServer::start(vec![StubResponse::json(
+            503,
+            json!({"error": "model is loading"}),
+        )]);
+
+        let err = tauri::async_runtime::block_on(refine(
+            &settings(&server),
+            &clean_up(),
+            "hello world",
+            "en",
+        ))
+        .unwrap_err();
+        assert!(err.to_string().contains("503"), "{}", err);
+        assert!(err.to_string().contains("model is loading"), "{}", err);
+    }
+
+    #[test]
+    fn slow_models_time_out() {
+        let server = StubServer::start(vec![
+            StubResponse::json(200, reply("too late")).delayed(Duration::from_secs(3))
+        ]);
+
+        let err = tauri::async_runtime::block_on(refine(
+            &settings(&server),
+            &clean_up(),
+            "hello world",
+            "en",
+        ))
+        .unwrap_err();
+        assert_eq!(err.to_string(), "Model did not answer within 1s");
+    }
+}
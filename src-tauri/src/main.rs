@@ -2,5 +2,10 @@
 #![cfg_attr(not(debug_assertions), windows_subsystem = "windows")]
 
 fn main() {
+    let args: Vec<String> = std::env::args().collect();
+    if let Some(exit_code) = handy_app_lib::cli::try_run(&args) {
+        std::process::exit(exit_code);
+    }
+
     handy_app_lib::run()
 }
@@ -0,0 +1,741 @@
+//! Optional HTTP API on 127.0.0.1 that lets scripts and other apps transcribe
+//! with the model Handy already has loaded. Speaks the OpenAI
+//! `/v1/audio/transcriptions` format, and runs uploads through the same queue
+//! and post-processing as dictation.
+
+use crate::audio_toolkit::audio::read_audio_bytes;
+use crate::audio_toolkit::constants::WHISPER_SAMPLE_RATE;
+use crate::file_transcription::{format_result, OutputFormat};
+use crate::managers::transcription::{JobOverrides, TranscriptionManager, TranscriptionResult};
+use crate::settings::{get_settings, write_settings};
+use anyhow::Result;
+use rand::distributions::{Alphanumeric, DistString};
+use serde::Serialize;
+use serde_json::json;
+use std::collections::HashMap;
+use std::io::{BufRead, BufReader, Read, Write};
+use std::net::{Ipv4Addr, TcpListener, TcpStream};
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::{mpsc, Arc, Mutex};
+use std::thread::JoinHandle;
+use std::time::{Duration, Instant};
+use tauri::{AppHandle, Manager};
+
+/// Uploads larger than this are rejected, the same limit OpenAI's API has.
+const MAX_BODY_BYTES: usize = 25 * 1024 * 1024;
+
+/// Request line and headers larger than this are rejected.
+const MAX_HEADER_BYTES: usize = 16 * 1024;
+
+/// How long a client may take to send its whole request, so a client that
+/// trickles bytes can't hold a connection open.
+const READ_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// How long writing a response may stall on a client that doesn't read it.
+const WRITE_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Connections served at once. Uploads are held in memory while they wait
+/// for the queue, further clients get a 503 until one finishes.
+const MAX_CONNECTIONS: usize = 8;
+
+/// How often the accept loop checks whether the server should stop.
+const ACCEPT_POLL_INTERVAL: Duration = Duration::from_millis(100);
+
+const TOKEN_LENGTH: usize = 32;
+
+/// Binding whose settings API requests use when the request's `model` field
+/// doesn't name one.
+const API_BINDING_ID: &str = "api";
+
+#[derive(Clone, Debug, Serialize)]
+pub struct ApiServerStatus {
+    pub running: bool,
+    pub port: Option<u16>,
+}
+
+struct RunningServer {
+    port: u16,
+    stop: Arc<AtomicBool>,
+    handle: JoinHandle<()>,
+}
+
+pub struct ApiServerManager {
+    app_handle: AppHandle,
+    running: Mutex<Option<RunningServer>>,
+}
+
+pub fn generate_token() -> String {
+    Alphanumeric.sample_string(&mut rand::thread_rng(), TOKEN_LENGTH)
+}
+
+impl ApiServerManager {
+    pub fn new(app_handle: &AppHandle) -> Self {
+        let manager = Self {
+            app_handle: app_handle.clone(),
+            running: Mutex::new(None),
+        };
+        if let Err(e) = manager.apply_settings() {
+            eprintln!("Failed to start API server: {}", e);
+        }
+        manager
+    }
+
+    /// Starts, stops or restarts the server to match the settings.
+    pub fn apply_settings(&self) -> Result<()> {
+        let mut settings = get_settings(&self.app_handle);
+        let mut running = self.running.lock().unwrap();
+
+        let wanted_port = settings
+            .api_server_enabled
+            .then_some(settings.api_server_port);
+        if running.as_ref().map(|server| server.port) == wanted_port {
+            return Ok(());
+        }
+
+        if let Some(server) = running.take() {
+            server.stop.store(true, Ordering::SeqCst);
+            let _ = server.handle.join();
+            println!("API server on port {} stopped", server.port);
+        }
+
+        let Some(port) = wanted_port else {
+            return Ok(());
+        };
+
+        // Never serve without a token
+        if settings.api_server_token.is_empty() {
+            settings.api_server_token = generate_token();
+            write_settings(&self.app_handle, settings);
+        }
+
+        let listener = TcpListener::bind((Ipv4Addr::LOCALHOST, port))
+            .map_err(|e| anyhow::anyhow!("Failed to listen on 127.0.0.1:{}: {}", port, e))?;
+        listener.set_nonblocking(true)?;
+
+        let stop = Arc::new(AtomicBool::new(false));
+        let handle = {
+            let stop = Arc::clone(&stop);
+            let app_handle = self.app_handle.clone();
+            std::thread::spawn(move || accept_loop(listener, stop, app_handle))
+        };
+        println!("API server listening on http://127.0.0.1:{}", port);
+
+        *running = Some(RunningServer { port, stop, handle });
+        Ok(())
+    }
+
+    pub fn status(&self) -> ApiServerStatus {
+        let port = self.running.lock().unwrap().as_ref().map(|s| s.port);
+        ApiServerStatus {
+            running: port.is_some(),
+            port,
+        }
+    }
+}
+
+/// Counts a connection as open until dropped.
+struct ConnectionSlot(Arc<AtomicUsize>);
+
+impl ConnectionSlot {
+    fn acquire(open: &Arc<AtomicUsize>) -> Option<Self> {
+        open.fetch_update(Ordering::SeqCst, Ordering::SeqCst, |count| {
+            (count < MAX_CONNECTIONS).then_some(count + 1)
+        })
+        .ok()
+        .map(|_| Self(Arc::clone(open)))
+    }
+}
+
+impl Drop for ConnectionSlot {
+    fn drop(&mut self) {
+        self.0.fetch_sub(1, Ordering::SeqCst);
+    }
+}
+
+fn accept_loop(listener: TcpListener, stop: Arc<AtomicBool>, app_handle: AppHandle) {
+    let open = Arc::new(AtomicUsize::new(0));
+    while !stop.load(Ordering::SeqCst) {
+        match listener.accept() {
+            Ok((stream, _)) => {
+                let _ = stream.set_nonblocking(false);
+                let _ = stream.set_write_timeout(Some(WRITE_TIMEOUT));
+                let Some(slot) = ConnectionSlot::acquire(&open) else {
+                    write_response(
+                        stream,
+                        Response::error(503, "Too many connections, try again later"),
+                    );
+                    continue;
+                };
+                let app_handle = app_handle.clone();
+                std::thread::spawn(move || {
+                    handle_connection(stream, &app_handle);
+                    drop(slot);
+                });
+            }
+            Err(e) if e.kind() == std::io::ErrorKind::WouldBlock => {
+                std::thread::sleep(ACCEPT_POLL_INTERVAL);
+            }
+            Err(e) => eprintln!("API server failed to accept a connection: {}", e),
+        }
+    }
+}
+
+/// Request line and headers, read before deciding whether to take the body.
+#[derive(Debug)]
+struct RequestHead {
+    method: String,
+    path: String,
+    /// Lowercase names
+    headers: HashMap<String, String>,
+}
+
+#[derive(Debug)]
+struct Request {
+    method: String,
+    path: String,
+    /// Lowercase names
+    headers: HashMap<String, String>,
+    body: Vec<u8>,
+}
+
+#[derive(Debug)]
+struct Response {
+    status: u16,
+    content_type: &'static str,
+    body: String,
+}
+
+impl Response {
+    fn json(status: u16, body: serde_json::Value) -> Self {
+        Self {
+            status,
+            content_type: "application/json",
+            body: body.to_string(),
+        }
+    }
+
+    /// An error in the shape OpenAI's API uses.
+    fn error(status: u16, message: impl Into<String>) -> Self {
+        let kind = if status >= 500 {
+            "server_error"
+        } else {
+            "invalid_request_error"
+        };
+        Self::json(
+            status,
+            json!({ "error": { "message": message.into(), "type": kind } }),
+        )
+    }
+}
+
+fn reason_phrase(status: u16) -> &'static str {
+    match status {
+        200 => "OK",
+        400 => "Bad Request",
+        401 => "Unauthorized",
+        404 => "Not Found",
+        405 => "Method Not Allowed",
+        411 => "Length Required",
+        413 => "Payload Too Large",
+        503 => "Service Unavailable",
+        _ => "Internal Server Error",
+    }
+}
+
+fn handle_connection(stream: TcpStream, app_handle: &AppHandle) {
+    let mut reader = BufReader::new(DeadlineReader {
+        stream: &stream,
+        deadline: Instant::now() + READ_TIMEOUT,
+    });
+
+    // Turn away unauthorized clients before reading, or making room for, an
+    // upload
+    let response = match read_head(&mut reader) {
+        Ok(head) if !authorized(&head.headers, app_handle) => {
+            Response::error(401, "Missing or invalid API token")
+        }
+        Ok(head) => match read_body(&mut reader, head) {
+            Ok(request) => route(request, app_handle),
+            Err(response) => response,
+        },
+        Err(response) => response,
+    };
+
+    write_response(stream, response);
+}
+
+/// Reads from a stream until a deadline for the whole request, rather than
+/// only limiting how long each read may stall.
+struct DeadlineReader<'a> {
+    stream: &'a TcpStream,
+    deadline: Instant,
+}
+
+impl Read for DeadlineReader<'_> {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        let remaining = self.deadline.saturating_duration_since(Instant::now());
+        if remaining.is_zero() {
+            return Err(std::io::ErrorKind::TimedOut.into());
+        }
+        self.stream.set_read_timeout(Some(remaining))?;
+        let mut stream = self.stream;
+        stream.read(buf)
+    }
+}
+
+fn write_response(mut stream: TcpStream, response: Response) {
+    let head = format!(
+        "HTTP/1.1 {} {}\r\nContent-Type: {}\r\nContent-Length: {}\r\nConnection: close\r\n\r\n",
+        response.status,
+        reason_phrase(response.status),
+        response.content_type,
+        response.body.len()
+    );
+    let _ = stream
+        .write_all(head.as_bytes())
+        .and_then(|_| stream.write_all(response.body.as_bytes()))
+        .and_then(|_| stream.flush());
+}
+
+fn bad_request(message: &str) -> Response {
+    Response::error(400, message)
+}
+
+fn read_head<R: BufRead>(reader: &mut R) -> std::result::Result<RequestHead, Response> {
+    let mut header_bytes = 0;
+    let mut read_line = |reader: &mut R| {
+        let mut line = String::new();
+        let read = reader
+            .read_line(&mut line)
+            .map_err(|_| bad_request("Failed to read request"))?;
+        header_bytes += read;
+        if read == 0 || header_bytes > MAX_HEADER_BYTES {
+            return Err(bad_request("Malformed request"));
+        }
+        Ok(line.trim_end().to_string())
+    };
+
+    let request_line = read_line(reader)?;
+    let mut parts = request_line.split_whitespace();
+    let (Some(method), Some(target)) = (parts.next(), parts.next()) else {
+        return Err(bad_request("Malformed request line"));
+    };
+    let method = method.to_string();
+    let path = target.split('?').next().unwrap_or(target).to_string();
+
+    let mut headers = HashMap::new();
+    loop {
+        let line = read_line(reader)?;
+        if line.is_empty() {
+            break;
+        }
+        if let Some((name, value)) = line.split_once(':') {
+            headers.insert(name.trim().to_lowercase(), value.trim().to_string());
+        }
+    }
+
+    Ok(RequestHead {
+        method,
+        path,
+        headers,
+    })
+}
+
+fn read_body(reader: &mut impl Read, head: RequestHead) -> std::result::Result<Request, Response> {
+    let RequestHead {
+        method,
+        path,
+        headers,
+    } = head;
+    if headers
+        .get("transfer-encoding")
+        .map_or(false, |v| v.to_lowercase().contains("chunked"))
+    {
+        return Err(Response::error(411, "Chunked uploads are not supported"));
+    }
+    let length = match headers.get("content-length") {
+        Some(value) => value
+            .parse::<usize>()
+            .map_err(|_| bad_request("Invalid Content-Length"))?,
+        None => 0,
+    };
+    if length > MAX_BODY_BYTES {
+        return Err(Response::error(413, "Audio file is too large"));
+    }
+
+    let mut body = vec![0; length];
+    reader
+        .read_exact(&mut body)
+        .map_err(|_| bad_request("Request body ended early"))?;
+
+    Ok(Request {
+        method,
+        path,
+        headers,
+        body,
+    })
+}
+
+/// Compares without bailing out at the first difference, so response times
+/// don't reveal how much of a guessed token was right.
+fn tokens_match(given: &str, expected: &str) -> bool {
+    given.len() == expected.len()
+        && given
+            .bytes()
+            .zip(expected.bytes())
+            .fold(0u8, |diff, (a, b)| diff | (a ^ b))
+            == 0
+}
+
+fn authorized(headers: &HashMap<String, String>, app_handle: &AppHandle) -> bool {
+    let token = get_settings(app_handle).api_server_token;
+    headers
+        .get("authorization")
+        .and_then(|value| value.strip_prefix("Bearer "))
+        .map_or(false, |given| {
+            !token.is_empty() && tokens_match(given.trim(), &token)
+        })
+}
+
+fn route(request: Request, app_handle: &AppHandle) -> Response {
+    match (request.method.as_str(), request.path.as_str()) {
+        ("GET", "/v1/models") => Response::json(
+            200,
+            json!({
+                "object": "list",
+                "data": [{ "id": "handy", "object": "model", "owned_by": "handy" }],
+            }),
+        ),
+        ("POST", "/v1/audio/transcriptions") => transcribe(request, app_handle),
+        (_, "/v1/models") | (_, "/v1/audio/transcriptions") => {
+            Response::error(405, "Method not allowed")
+        }
+        _ => Response::error(404, "Not found"),
+    }
+}
+
+/// A part of a multipart/form-data body.
+struct FormPart {
+    name: String,
+    filename: Option<String>,
+    data: Vec<u8>,
+}
+
+fn find(haystack: &[u8], needle: &[u8]) -> Option<usize> {
+    haystack
+        .windows(needle.len())
+        .position(|window| window == needle)
+}
+
+/// Position of the `\r\n--boundary` that ends a part. Binary data may contain
+/// the same bytes as the start of a longer line, a delimiter has to be
+/// followed by "--", whitespace or the end of the body.
+fn find_delimiter(content: &[u8], delimiter: &[u8]) -> Option<usize> {
+    let mut from = 0;
+    loop {
+        let at = from + find(&content[from..], delimiter)?;
+        match content.get(at + delimiter.len()) {
+            None | Some(b'-' | b'\r' | b' ' | b'\t') => return Some(at),
+            Some(_) => from = at + 1,
+        }
+    }
+}
+
+fn parse_multipart(content_type: &str, body: &[u8]) -> Option<Vec<FormPart>> {
+    let boundary = content_type
+        .split(';')
+        .filter_map(|param| param.trim().strip_prefix("boundary="))
+        .next()?
+        .trim_matches('"');
+    let delimiter = format!("--{}", boundary).into_bytes();
+
+    let mut parts = Vec::new();
+    let mut rest = &body[find(body, &delimiter)? + delimiter.len()..];
+    // The last delimiter is followed by "--", some clients leave it out
+    while !rest.starts_with(b"--") {
+        if rest.iter().all(u8::is_ascii_whitespace) {
+            break;
+        }
+        let rest_after_crlf = rest.strip_prefix(b"\r\n")?;
+        let header_end = find(rest_after_crlf, b"\r\n\r\n")?;
+        let header = String::from_utf8_lossy(&rest_after_crlf[..header_end]);
+        let content = &rest_after_crlf[header_end + 4..];
+        let end = find_delimiter(
+            content,
+            &[b"\r\n".as_slice(), delimiter.as_slice()].concat(),
+        )?;
+
+        let disposition = header
+            .lines()
+            .find(|line| line.to_lowercase().starts_with("content-disposition:"))?;
+        let param = |key: &str| {
+            disposition.split(';').find_map(|p| {
+                p.trim()
+                    .strip_prefix(key)
+                    .map(|v| v.trim_matches('"').to_string())
+            })
+        };
+        parts.push(FormPart {
+            name: param("name=")?,
+            filename: param("filename="),
+            data: content[..end].to_vec(),
+        });
+
+        rest = &content[end + 2 + delimiter.len()..];
+    }
+    Some(parts)
+}
+
+fn transcribe(request: Request, app_handle: &AppHandle) -> Response {
+    let content_type = request
+        .headers
+        .get("content-type")
+        .cloned()
+        .unwrap_or_default();
+    if !content_type.starts_with("multipart/form-data") {
+        return Response::error(400, "Expected a multipart/form-data upload");
+    }
+    let Some(parts) = parse_multipart(&content_type, &request.body) else {
+        return Response::error(400, "Malformed multipart body");
+    };
+
+    let field = |name: &str| {
+        parts
+            .iter()
+            .find(|p| p.name == name)
+            .map(|p| String::from_utf8_lossy(&p.data).trim().to_string())
+    };
+    let Some(file) = parts.iter().find(|p| p.name == "file") else {
+        return Response::error(400, "Missing 'file' field");
+    };
+
+    let response_format = field("response_format").unwrap_or_else(|| "json".to_string());
+    let output_format = match response_format.as_str() {
+        "json" | "verbose_json" | "text" => None,
+        "srt" => Some(OutputFormat::Srt),
+        "vtt" => Some(OutputFormat::Vtt),
+        other => return Response::error(400, format!("Unsupported response_format: {}", other)),
+    };
+
+    // Optional in OpenAI's API as well, an empty field counts as missing
+    let language = field("language")
+        .filter(|language| !language.is_empty())
+        .map(|language| language.to_lowercase());
+    if let Some(language) = &language {
+        if whisper_rs::get_lang_id(language).is_none() {
+            return Response::error(400, format!("Unsupported language: {}", language));
+        }
+    }
+    let overrides = JobOverrides {
+        language,
+        prompt: field("prompt").filter(|prompt| !prompt.is_empty()),
+    };
+
+    // OpenAI only lists words when asked to, as `timestamp_granularities[]=word`
+    let wants_words = parts.iter().any(|p| {
+        p.name.starts_with("timestamp_granularities")
+            && String::from_utf8_lossy(&p.data).trim() == "word"
+    });
+
+    // A `model` naming a binding applies that binding's profile, anything
+    // else, such as "whisper-1", uses the global settings
+    let settings = get_settings(app_handle);
+    let binding_id = field("model")
+        .filter(|model| settings.bindings.contains_key(model))
+        .unwrap_or_else(|| API_BINDING_ID.to_string());
+
+    let extension = file
+        .filename
+        .as_deref()
+        .and_then(|name| name.rsplit_once('.'))
+        .map(|(_, ext)| ext.to_lowercase());
+    let samples = match read_audio_bytes(file.data.clone(), extension.as_deref()) {
+        Ok(samples) => samples,
+        Err(e) => return Response::error(400, format!("Could not decode audio: {}", e)),
+    };
+    let duration = samples.len() as f64 / WHISPER_SAMPLE_RATE as f64;
+
+    let (result_tx, result_rx) = mpsc::channel();
+    let tm = app_handle.state::<Arc<TranscriptionManager>>();
+    tm.enqueue_upload(
+        &binding_id,
+        samples,
+        overrides,
+        Box::new(move |result| {
+            let _ = result_tx.send(result);
+        }),
+    );
+    let result: TranscriptionResult = match result_rx.recv() {
+        Ok(Ok(result)) => result,
+        Ok(Err(e)) => return Response::error(500, e.to_string()),
+        Err(_) => return Response::error(503, "Transcription queue stopped"),
+    };
+
+    if let Some(format) = output_format {
+        return match format_result(&result, format) {
+            Ok(body) => Response {
+                status: 200,
+                content_type: "text/plain; charset=utf-8",
+                body,
+            },
+            Err(e) => Response::error(500, e.to_string()),
+        };
+    }
+
+    match response_format.as_str() {
+        "text" => Response {
+            status: 200,
+            content_type: "text/plain; charset=utf-8",
+            body: result.text,
+        },
+        "verbose_json" => {
+            let segments: Vec<_> = result
+                .segments
+                .iter()
+                .enumerate()
+                .map(|(id, segment)| {
+                    json!({
+                        "id": id,
+                        "start": segment.start_ms as f64 / 1000.0,
+                        "end": segment.end_ms as f64 / 1000.0,
+                        "text": segment.text,
+                    })
+                })
+                .collect();
+            let mut body = json!({
+                "task": "transcribe",
+                "language": result.language,
+                "duration": duration,
+                "text": result.text,
+                "segments": segments,
+            });
+            if wants_words {
+                body["words"] = result
+                    .words
+                    .iter()
+                    .map(|word| {
+                        json!({
+                            "word": word.text,
+                            "start": word.start_ms as f64 / 1000.0,
+                            "end": word.end_ms as f64 / 1000.0,
+                            "probability": word.probability,
+                        })
+                    })
+                    .collect();
+            }
+            Response::json(200, body)
+        }
+        _ => Response::json(200, json!({ "text": result.text })),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn connections_beyond_the_limit_are_refused() {
+        let open = Arc::new(AtomicUsize::new(0));
+        let slots: Vec<_> = (0..MAX_CONNECTIONS)
+            .map(|_| ConnectionSlot::acquire(&open).unwrap())
+            .collect();
+        assert!(ConnectionSlot::acquire(&open).is_none());
+
+        drop(slots);
+        assert_eq!(open.load(Ordering::SeqCst), 0);
+        assert!(ConnectionSlot::acquire(&open).is_some());
+    }
+
+    #[test]
+    fn head_is_read_without_the_body() {
+        let request = b"POST /v1/audio/transcriptions?x=1 HTTP/1.1\r\n\
+            Authorization: Bearer abc\r\nContent-Length: 4\r\n\r\nRIFF";
+        let mut reader = BufReader::new(&request[..]);
+
+        let head = read_head(&mut reader).unwrap();
+        assert_eq!(head.method, "POST");
+        assert_eq!(head.path, "/v1/audio/transcriptions");
+        assert_eq!(head.headers["authorization"], "Bearer abc");
+        assert_eq!(reader.buffer(), b"RIFF");
+
+        let request = read_body(&mut reader, head).unwrap();
+        assert_eq!(request.body, b"RIFF");
+    }
+
+    fn field<'a>(parts: &'a [FormPart], name: &str) -> &'a [u8] {
+        &parts.iter().find(|p| p.name == name).unwrap().data
+    }
+
+    #[test]
+    fn multipart_fields_and_files_are_parsed() {
+        let body = b"preamble\r\n--b1\r\n\
+            Content-Disposition: form-data; name=\"model\"\r\n\r\nwhisper-1\r\n\
+            --b1\r\n\
+            Content-Disposition: form-data; name=\"file\"; filename=\"a.wav\"\r\n\
+            Content-Type: audio/wav\r\n\r\nRIFF\r\n--b1--\r\n";
+        let parts = parse_multipart("multipart/form-data; boundary=b1", body).unwrap();
+        assert_eq!(parts.len(), 2);
+        assert_eq!(field(&parts, "model"), b"whisper-1");
+        assert_eq!(field(&parts, "file"), b"RIFF");
+        assert_eq!(parts[1].filename.as_deref(), Some("a.wav"));
+    }
+
+    #[test]
+    fn quoted_boundaries_are_unquoted() {
+        let body = b"--a b:c\r\nContent-Disposition: form-data; name=\"language\"\r\n\r\n\
+            de\r\n--a b:c--";
+        let parts = parse_multipart(
+            "multipart/form-data; charset=utf-8; boundary=\"a b:c\"",
+            body,
+        )
+        .unwrap();
+        assert_eq!(field(&parts, "language"), b"de");
+    }
+
+    #[test]
+    fn final_dashes_may_be_missing() {
+        for end in ["--xyz\r\n", "--xyz"] {
+            let body = format!(
+                "--xyz\r\nContent-Disposition: form-data; name=\"prompt\"\r\n\r\nHandy\r\n{}",
+                end
+            );
+            let parts = parse_multipart("multipart/form-data; boundary=xyz", body.as_bytes())
+                .unwrap_or_else(|| panic!("{:?} was rejected", end));
+            assert_eq!(field(&parts, "prompt"), b"Handy");
+        }
+    }
+
+    #[test]
+    fn binary_data_may_look_like_a_delimiter() {
+        let data = b"RIFF\r\n--\r\n--xy\x00\xff\r\n--xyzz";
+        let mut body = b"--xyz\r\nContent-Disposition: form-data; name=\"file\"\r\n\r\n".to_vec();
+        body.extend_from_slice(data);
+        body.extend_from_slice(
+            b"\r\n--xyz\r\nContent-Disposition: form-data; name=\"model\"\r\n\r\nbase\r\n--xyz--\r\n",
+        );
+        let parts = parse_multipart("multipart/form-data; boundary=xyz", &body).unwrap();
+        assert_eq!(field(&parts, "file"), data);
+        assert_eq!(field(&parts, "model"), b"base");
+    }
+
+    #[test]
+    fn malformed_multipart_is_rejected() {
+        let body = b"--xyz\r\nContent-Disposition: form-data; name=\"file\"\r\n\r\nRIFF";
+        assert!(parse_multipart("multipart/form-data; boundary=xyz", body).is_none());
+        assert!(parse_multipart("multipart/form-data", b"--xyz--").is_none());
+    }
+
+    #[test]
+    fn oversized_uploads_are_refused_before_reading() {
+        let head = RequestHead {
+            method: "POST".to_string(),
+            path: "/v1/audio/transcriptions".to_string(),
+            headers: HashMap::from([(
+                "content-length".to_string(),
+                (MAX_BODY_BYTES + 1).to_string(),
+            )]),
+        };
+        let response = read_body(&mut std::io::empty(), head).unwrap_err();
+        assert_eq!(response.status, 413);
+    }
+}
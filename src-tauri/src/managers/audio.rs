@@ -1,4 +1,5 @@
 use crate::audio_toolkit::{list_input_devices, vad::SmoothedVad, AudioRecorder, SileroVad};
+use crate::managers::transcription::TranscriptionManager;
 use crate::settings::get_settings;
 use crate::utils;
 use log::{debug, info};
@@ -33,7 +34,8 @@ fn create_audio_recorder(
     let smoothed_vad = SmoothedVad::new(Box::new(silero), 15, 15, 2);
 
     // Recorder with VAD plus a spectrum-level callback that forwards updates to
-    // the frontend.
+    // the frontend, and a chunk callback that feeds finalized speech chunks to
+    // an active streaming transcription.
     let recorder = AudioRecorder::new()
         .map_err(|e| anyhow::anyhow!("Failed to create AudioRecorder: {}", e))?
         .with_vad(Box::new(smoothed_vad))
@@ -42,6 +44,14 @@ fn create_audio_recorder(
             move |levels| {
                 utils::emit_levels(&app_handle, &levels);
             }
+        })
+        .with_chunk_callback({
+            let app_handle = app_handle.clone();
+            move |chunk| {
+                if let Some(tm) = app_handle.try_state::<Arc<TranscriptionManager>>() {
+                    tm.push_stream_chunk(chunk);
+                }
+            }
         });
 
     Ok(recorder)
@@ -222,6 +232,10 @@ impl AudioRecordingManager {
         }
     }
 
+    pub fn is_recording(&self) -> bool {
+        *self.is_recording.lock().unwrap()
+    }
+
     pub fn update_selected_device(&self) -> Result<(), anyhow::Error> {
         // If currently open, restart the microphone stream to use the new device
         if *self.is_open.lock().unwrap() {
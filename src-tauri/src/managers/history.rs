@@ -0,0 +1,463 @@
+use crate::engines::TranscriptionWord;
+use anyhow::Result;
+use chrono::{DateTime, Local};
+use serde::{Deserialize, Serialize};
+use std::fs::{self, File, OpenOptions};
+use std::io::{BufRead, BufReader, Write};
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use tauri::{App, Emitter, Manager};
+
+const HISTORY_FILE_NAME: &str = "history.jsonl";
+/// Holds the id the next entry gets, so ids of deleted entries are never
+/// handed out again.
+const NEXT_ID_FILE_NAME: &str = "history_next_id";
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HistoryEntry {
+    pub id: u64,
+    pub text: String,
+    /// Unix timestamp in milliseconds of when the transcription finished
+    pub timestamp: i64,
+    /// Length of the transcribed audio in milliseconds
+    pub duration_ms: u64,
+    pub model_id: Option<String>,
+    pub language: String,
+    pub binding_id: String,
+    /// Decoded words with their times in the recording and confidence
+    #[serde(default)]
+    pub words: Vec<TranscriptionWord>,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ExportFormat {
+    Csv,
+    Markdown,
+    Json,
+}
+
+/// Append-only store of every transcription, kept as one JSON object per line
+/// in `history.jsonl` next to the `models/` directory.
+pub struct HistoryManager {
+    /// Tells the frontend the entries changed
+    on_change: Box<dyn Fn() + Send + Sync>,
+    history_path: PathBuf,
+    next_id_path: PathBuf,
+    entries: Mutex<Vec<HistoryEntry>>,
+    next_id: Mutex<u64>,
+}
+
+impl HistoryManager {
+    pub fn new(app: &App) -> Result<Self> {
+        let app_handle = app.app_handle().clone();
+
+        let app_data_dir = app
+            .path()
+            .app_data_dir()
+            .map_err(|e| anyhow::anyhow!("Failed to get app data dir: {}", e))?;
+
+        Self::open(
+            &app_data_dir,
+            Box::new(move || {
+                let _ = app_handle.emit("history-updated", ());
+            }),
+        )
+    }
+
+    /// Loads the history kept in `dir`, creating the directory if needed.
+    fn open(dir: &Path, on_change: Box<dyn Fn() + Send + Sync>) -> Result<Self> {
+        if !dir.exists() {
+            fs::create_dir_all(dir)?;
+        }
+
+        let history_path = dir.join(HISTORY_FILE_NAME);
+        let next_id_path = dir.join(NEXT_ID_FILE_NAME);
+        let entries = Self::load_entries(&history_path)?;
+        // The stored counter wins, unless the entries were written by a
+        // version without one
+        let next_id = Self::load_next_id(&next_id_path)
+            .max(entries.iter().map(|e| e.id + 1).max().unwrap_or(1));
+
+        println!(
+            "Loaded {} history entries from {:?}",
+            entries.len(),
+            history_path
+        );
+
+        Ok(Self {
+            on_change,
+            history_path,
+            next_id_path,
+            entries: Mutex::new(entries),
+            next_id: Mutex::new(next_id),
+        })
+    }
+
+    fn load_next_id(path: &PathBuf) -> u64 {
+        fs::read_to_string(path)
+            .ok()
+            .and_then(|content| content.trim().parse().ok())
+            .unwrap_or(1)
+    }
+
+    /// Hands out the next id and stores the counter before the id is used.
+    fn take_next_id(&self) -> Result<u64> {
+        let mut next_id = self.next_id.lock().unwrap();
+        let id = *next_id;
+        let tmp_path = self.next_id_path.with_extension("tmp");
+        fs::write(&tmp_path, (id + 1).to_string())?;
+        fs::rename(&tmp_path, &self.next_id_path)?;
+        *next_id = id + 1;
+        Ok(id)
+    }
+
+    fn load_entries(path: &PathBuf) -> Result<Vec<HistoryEntry>> {
+        if !path.exists() {
+            return Ok(Vec::new());
+        }
+
+        let reader = BufReader::new(File::open(path)?);
+        let mut entries = Vec::new();
+
+        for (line_no, line) in reader.lines().enumerate() {
+            let line = line?;
+            if line.trim().is_empty() {
+                continue;
+            }
+            // Skip malformed lines instead of losing the whole history
+            match serde_json::from_str::<HistoryEntry>(&line) {
+                Ok(entry) => entries.push(entry),
+                Err(e) => eprintln!("Skipping malformed history line {}: {}", line_no + 1, e),
+            }
+        }
+
+        Ok(entries)
+    }
+
+    fn rewrite_file(&self, entries: &[HistoryEntry]) -> Result<()> {
+        let tmp_path = self.history_path.with_extension("jsonl.tmp");
+        {
+            let mut file = File::create(&tmp_path)?;
+            for entry in entries {
+                writeln!(file, "{}", serde_json::to_string(entry)?)?;
+            }
+            file.flush()?;
+        }
+        fs::rename(&tmp_path, &self.history_path)?;
+        Ok(())
+    }
+
+    pub fn add_entry(
+        &self,
+        text: String,
+        duration_ms: u64,
+        model_id: Option<String>,
+        language: String,
+        binding_id: String,
+        words: Vec<TranscriptionWord>,
+    ) -> Result<HistoryEntry> {
+        let mut entries = self.entries.lock().unwrap();
+
+        let entry = HistoryEntry {
+            id: self.take_next_id()?,
+            text,
+            timestamp: Local::now().timestamp_millis(),
+            duration_ms,
+            model_id,
+            language,
+            binding_id,
+            words,
+        };
+
+        let mut file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&self.history_path)?;
+        writeln!(file, "{}", serde_json::to_string(&entry)?)?;
+
+        entries.push(entry.clone());
+        drop(entries);
+
+        (self.on_change)();
+        Ok(entry)
+    }
+
+    /// Returns entries newest first.
+    pub fn list_entries(&self, offset: usize, limit: Option<usize>) -> Vec<HistoryEntry> {
+        let entries = self.entries.lock().unwrap();
+        let iter = entries.iter().rev().skip(offset).cloned();
+        match limit {
+            Some(limit) => iter.take(limit).collect(),
+            None => iter.collect(),
+        }
+    }
+
+    /// Case-insensitive substring search over the entry text, newest first.
+    pub fn search_entries(&self, query: &str) -> Vec<HistoryEntry> {
+        let query = query.trim().to_lowercase();
+        if query.is_empty() {
+            return self.list_entries(0, None);
+        }
+
+        let entries = self.entries.lock().unwrap();
+        entries
+            .iter()
+            .rev()
+            .filter(|e| e.text.to_lowercase().contains(&query))
+            .cloned()
+            .collect()
+    }
+
+    pub fn get_entry(&self, id: u64) -> Option<HistoryEntry> {
+        let entries = self.entries.lock().unwrap();
+        entries.iter().find(|e| e.id == id).cloned()
+    }
+
+    pub fn delete_entry(&self, id: u64) -> Result<()> {
+        let mut entries = self.entries.lock().unwrap();
+
+        let index = entries
+            .iter()
+            .position(|e| e.id == id)
+            .ok_or_else(|| anyhow::anyhow!("History entry not found: {}", id))?;
+        entries.remove(index);
+        self.rewrite_file(&entries)?;
+        drop(entries);
+
+        (self.on_change)();
+        Ok(())
+    }
+
+    pub fn clear(&self) -> Result<()> {
+        let mut entries = self.entries.lock().unwrap();
+        entries.clear();
+        self.rewrite_file(&entries)?;
+        drop(entries);
+
+        (self.on_change)();
+        Ok(())
+    }
+
+    pub fn export(&self, format: ExportFormat) -> Result<String> {
+        let entries = self.list_entries(0, None);
+
+        let output = match format {
+            ExportFormat::Json => serde_json::to_string_pretty(&entries)?,
+            ExportFormat::Csv => {
+                let mut out =
+                    String::from("id,timestamp,duration_ms,model_id,language,binding_id,text\n");
+                for e in &entries {
+                    out.push_str(&format!(
+                        "{},{},{},{},{},{},{}\n",
+                        e.id,
+                        csv_escape(&format_timestamp(e.timestamp)),
+                        e.duration_ms,
+                        csv_escape(e.model_id.as_deref().unwrap_or("")),
+                        csv_escape(&e.language),
+                        csv_escape(&e.binding_id),
+                        csv_escape(&e.text),
+                    ));
+                }
+                out
+            }
+            ExportFormat::Markdown => {
+                let mut out = String::from("# Handy Transcription History\n");
+                for e in &entries {
+                    out.push_str(&format!(
+                        "\n## {}\n\n*{:.1}s · {} · {}*\n\n{}\n",
+                        format_timestamp(e.timestamp),
+                        e.duration_ms as f64 / 1000.0,
+                        e.model_id.as_deref().unwrap_or("unknown model"),
+                        e.language,
+                        e.text
+                    ));
+                }
+                out
+            }
+        };
+
+        Ok(output)
+    }
+}
+
+fn format_timestamp(timestamp_ms: i64) -> String {
+    DateTime::from_timestamp_millis(timestamp_ms)
+        .map(|dt| {
+            dt.with_timezone(&Local)
+                .format("%Y-%m-%d %H:%M:%S")
+                .to_string()
+        })
+        .unwrap_or_default()
+}
+
+fn csv_escape(field: &str) -> String {
+    if field.contains(|c| matches!(c, ',' | '"' | '\n' | '\r')) {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Arc;
+
+    /// An empty directory of its own for each test.
+    fn temp_dir(name: &str) -> PathBuf {
+        let dir =
+            std::env::temp_dir().join(format!("handy-history-{}-{}", name, std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        dir
+    }
+
+    fn open(dir: &Path) -> HistoryManager {
+        HistoryManager::open(dir, Box::new(|| {})).unwrap()
+    }
+
+    fn add(history: &HistoryManager, text: &str) -> u64 {
+        history
+            .add_entry(
+                text.to_string(),
+                1500,
+                Some("small".to_string()),
+                "en".to_string(),
+                "transcribe".to_string(),
+                Vec::new(),
+            )
+            .unwrap()
+            .id
+    }
+
+    fn texts(entries: &[HistoryEntry]) -> Vec<&str> {
+        entries.iter().map(|e| e.text.as_str()).collect()
+    }
+
+    #[test]
+    fn entries_survive_a_restart_newest_first() {
+        let dir = temp_dir("restart");
+        let history = open(&dir);
+        add(&history, "first");
+        add(&history, "second");
+        drop(history);
+
+        let history = open(&dir);
+        assert_eq!(texts(&history.list_entries(0, None)), ["second", "first"]);
+        assert_eq!(texts(&history.list_entries(1, Some(1))), ["first"]);
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn ids_are_never_reused() {
+        let dir = temp_dir("ids");
+        let history = open(&dir);
+        assert_eq!(add(&history, "one"), 1);
+        assert_eq!(add(&history, "two"), 2);
+        let three = add(&history, "three");
+        history.delete_entry(three).unwrap();
+        assert_eq!(add(&history, "four"), 4);
+        history.clear().unwrap();
+        drop(history);
+
+        let history = open(&dir);
+        assert!(history.list_entries(0, None).is_empty());
+        assert_eq!(add(&history, "five"), 5);
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn ids_continue_after_entries_written_without_a_counter() {
+        let dir = temp_dir("legacy");
+        let history = open(&dir);
+        add(&history, "one");
+        add(&history, "two");
+        drop(history);
+        fs::remove_file(dir.join(NEXT_ID_FILE_NAME)).unwrap();
+
+        let history = open(&dir);
+        assert_eq!(add(&history, "three"), 3);
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn search_delete_and_clear() {
+        let dir = temp_dir("search");
+        let changes = Arc::new(AtomicUsize::new(0));
+        let counter = Arc::clone(&changes);
+        let history = HistoryManager::open(
+            &dir,
+            Box::new(move || {
+                counter.fetch_add(1, Ordering::SeqCst);
+            }),
+        )
+        .unwrap();
+        add(&history, "Meet Anneliese at noon");
+        let lunch = add(&history, "lunch with anneliese");
+        add(&history, "call the bank");
+
+        assert_eq!(
+            texts(&history.search_entries("ANNELIESE")),
+            ["lunch with anneliese", "Meet Anneliese at noon"]
+        );
+        assert_eq!(history.search_entries("  ").len(), 3);
+        assert!(history.search_entries("dentist").is_empty());
+
+        history.delete_entry(lunch).unwrap();
+        assert!(history.get_entry(lunch).is_none());
+        assert!(history.delete_entry(lunch).is_err());
+        assert_eq!(
+            texts(&open(&dir).search_entries("anneliese")),
+            ["Meet Anneliese at noon"]
+        );
+
+        history.clear().unwrap();
+        assert!(history.list_entries(0, None).is_empty());
+        assert!(open(&dir).list_entries(0, None).is_empty());
+        // Three adds, a delete and a clear
+        assert_eq!(changes.load(Ordering::SeqCst), 5);
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn csv_export_escapes_fields() {
+        let dir = temp_dir("csv");
+        let history = open(&dir);
+        add(&history, "plain");
+        add(&history, "one, two");
+        add(&history, "say \"hi\"\nthen leave");
+
+        let csv = history.export(ExportFormat::Csv).unwrap();
+        let lines: Vec<&str> = csv.lines().collect();
+        assert_eq!(
+            lines[0],
+            "id,timestamp,duration_ms,model_id,language,binding_id,text"
+        );
+        assert!(lines[1].starts_with("3,"));
+        assert!(lines[1].ends_with(",1500,small,en,transcribe,\"say \"\"hi\"\""));
+        assert_eq!(lines[2], "then leave\"");
+        assert!(lines[3].ends_with(",transcribe,\"one, two\""));
+        assert!(lines[4].ends_with(",transcribe,plain"));
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn markdown_and_json_exports() {
+        let dir = temp_dir("export");
+        let history = open(&dir);
+        add(&history, "first note");
+        add(&history, "second note");
+
+        let markdown = history.export(ExportFormat::Markdown).unwrap();
+        assert!(markdown.starts_with("# Handy Transcription History\n"));
+        assert!(markdown.contains("*1.5s · small · en*\n\nsecond note\n"));
+        assert!(markdown.find("second note") < markdown.find("first note"));
+
+        let json = history.export(ExportFormat::Json).unwrap();
+        let entries: Vec<HistoryEntry> = serde_json::from_str(&json).unwrap();
+        assert_eq!(texts(&entries), ["second note", "first note"]);
+        assert_eq!(entries[0].id, 2);
+        let _ = fs::remove_dir_all(&dir);
+    }
+}
@@ -1,3 +1,5 @@
+pub mod api_server;
 pub mod audio;
+pub mod history;
 pub mod model;
 pub mod transcription;
@@ -1,3 +1,5 @@
+use crate::benchmark::{self, BenchmarkResult};
+use crate::engines::{EngineKind, RemoteModelConfig};
 use crate::settings::{get_settings, write_settings};
 use anyhow::Result;
 use futures_util::StreamExt;
@@ -20,6 +22,11 @@ pub struct ModelInfo {
     pub is_downloaded: bool,
     pub is_downloading: bool,
     pub partial_size: u64,
+    #[serde(default)]
+    pub engine: EngineKind,
+    /// Latest benchmark of the model on this machine
+    #[serde(default)]
+    pub benchmark: Option<BenchmarkResult>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -30,6 +37,9 @@ pub struct DownloadProgress {
     pub percentage: f64,
 }
 
+/// Suffix of the files describing remote models in the models directory.
+const REMOTE_MODEL_SUFFIX: &str = ".remote.json";
+
 pub struct ModelManager {
     app_handle: AppHandle,
     models_dir: PathBuf,
@@ -65,6 +75,8 @@ impl ModelManager {
                 is_downloaded: false,
                 is_downloading: false,
                 partial_size: 0,
+                engine: EngineKind::Whisper,
+                benchmark: None,
             },
         );
 
@@ -81,6 +93,8 @@ impl ModelManager {
                 is_downloaded: false,
                 is_downloading: false,
                 partial_size: 0,
+                engine: EngineKind::Whisper,
+                benchmark: None,
             },
         );
 
@@ -96,6 +110,8 @@ impl ModelManager {
                 is_downloaded: false,
                 is_downloading: false,
                 partial_size: 0,
+                engine: EngineKind::Whisper,
+                benchmark: None,
             },
         );
 
@@ -111,6 +127,8 @@ impl ModelManager {
                 is_downloaded: false,
                 is_downloading: false,
                 partial_size: 0,
+                engine: EngineKind::Whisper,
+                benchmark: None,
             },
         );
 
@@ -126,6 +144,9 @@ impl ModelManager {
         // Check which models are already downloaded
         manager.update_download_status()?;
 
+        manager.load_remote_models();
+        manager.load_benchmarks();
+
         // Auto-select a model if none is currently selected
         manager.auto_select_model_if_needed()?;
 
@@ -142,6 +163,119 @@ impl ModelManager {
         models.get(model_id).cloned()
     }
 
+    /// Registers the remote models configured in the models directory.
+    fn load_remote_models(&self) {
+        let Ok(entries) = fs::read_dir(&self.models_dir) else {
+            return;
+        };
+        for entry in entries.flatten() {
+            let filename = entry.file_name().to_string_lossy().to_string();
+            let Some(id) = filename.strip_suffix(REMOTE_MODEL_SUFFIX) else {
+                continue;
+            };
+            match RemoteModelConfig::read(&entry.path()) {
+                Ok(config) => self.register_remote_model(id, &config),
+                Err(e) => eprintln!("Skipping remote model {}: {}", id, e),
+            }
+        }
+    }
+
+    fn register_remote_model(&self, id: &str, config: &RemoteModelConfig) {
+        let mut models = self.available_models.lock().unwrap();
+        let benchmark = models.get(id).and_then(|m| m.benchmark.clone());
+        models.insert(
+            id.to_string(),
+            ModelInfo {
+                id: id.to_string(),
+                name: config.name.clone(),
+                description: format!("Transcribed by the server at {}", config.endpoint),
+                filename: format!("{}{}", id, REMOTE_MODEL_SUFFIX),
+                url: None,
+                size_mb: 0,
+                is_downloaded: true,
+                is_downloading: false,
+                partial_size: 0,
+                engine: EngineKind::Remote,
+                benchmark,
+            },
+        );
+    }
+
+    /// Adds a remote model or updates the one with the same id.
+    pub fn save_remote_model(&self, id: &str, config: &RemoteModelConfig) -> Result<()> {
+        config.validate()?;
+        let valid_id = !id.is_empty()
+            && id
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_');
+        if !valid_id {
+            return Err(anyhow::anyhow!(
+                "Model id may only contain letters, digits, '-' and '_'"
+            ));
+        }
+        if let Some(existing) = self.get_model_info(id) {
+            if existing.engine != EngineKind::Remote {
+                return Err(anyhow::anyhow!("A local model with id '{}' exists", id));
+            }
+        }
+
+        let path = self
+            .models_dir
+            .join(format!("{}{}", id, REMOTE_MODEL_SUFFIX));
+        fs::write(&path, serde_json::to_string_pretty(config)?)?;
+        self.register_remote_model(id, config);
+        Ok(())
+    }
+
+    /// Configuration of a remote model, `None` for local models.
+    pub fn get_remote_model(&self, model_id: &str) -> Option<RemoteModelConfig> {
+        let model = self.get_model_info(model_id)?;
+        if model.engine != EngineKind::Remote {
+            return None;
+        }
+        RemoteModelConfig::read(&self.models_dir.join(&model.filename)).ok()
+    }
+
+    /// Annotates the models with cached benchmark results.
+    fn load_benchmarks(&self) {
+        let path = benchmark::cache_path(&self.models_dir);
+        let Ok(contents) = fs::read_to_string(&path) else {
+            return;
+        };
+        let results: HashMap<String, BenchmarkResult> = match serde_json::from_str(&contents) {
+            Ok(results) => results,
+            Err(e) => {
+                eprintln!("Ignoring unreadable benchmark cache {:?}: {}", path, e);
+                return;
+            }
+        };
+
+        let mut models = self.available_models.lock().unwrap();
+        for (model_id, result) in results {
+            if let Some(model) = models.get_mut(&model_id) {
+                model.benchmark = Some(result);
+            }
+        }
+    }
+
+    /// Stores a benchmark result on its model and in the cache.
+    pub fn record_benchmark(&self, result: BenchmarkResult) -> Result<()> {
+        let mut models = self.available_models.lock().unwrap();
+        if let Some(model) = models.get_mut(&result.model_id) {
+            model.benchmark = Some(result);
+        }
+
+        let results: HashMap<&String, &BenchmarkResult> = models
+            .values()
+            .filter_map(|model| model.benchmark.as_ref().map(|b| (&model.id, b)))
+            .collect();
+        fs::write(
+            benchmark::cache_path(&self.models_dir),
+            serde_json::to_string_pretty(&results)?,
+        )?;
+        Ok(())
+    }
+
     fn migrate_bundled_models(&self) -> Result<()> {
         // Check for bundled models and copy them to user directory
         let bundled_models = ["ggml-small.bin"]; // Add other bundled models here if any
@@ -425,6 +559,11 @@ impl ModelManager {
             return Err(anyhow::anyhow!("No model files found to delete"));
         }
 
+        // Remote models only exist as long as their configuration does
+        if model_info.engine == EngineKind::Remote {
+            self.available_models.lock().unwrap().remove(model_id);
+        }
+
         // Update download status
         self.update_download_status()?;
         println!("ModelManager: Download status updated");
@@ -1,14 +1,31 @@
-use crate::managers::model::ModelManager;
-use crate::settings::get_settings;
+use crate::audio_toolkit::constants::WHISPER_SAMPLE_RATE;
+use crate::engines::{
+    create_engine, load_with_cpu_fallback, merge_tokens_into_words, CancellationToken,
+    ComputeBackend, EngineKind, LoadOptions, RemoteModelConfig, TranscribeOptions,
+    TranscriptionEngine, TranscriptionInterrupted,
+};
+use crate::file_transcription::transcribe_in_chunks;
+use crate::hallucination_filter::HallucinationFilter;
+use crate::llm_refinement;
+use crate::managers::audio::AudioRecordingManager;
+use crate::managers::history::HistoryManager;
+use crate::managers::model::{ModelInfo, ModelManager};
+use crate::settings::{
+    decoder_settings_for_model, get_settings, resolve_binding_settings, AppSettings,
+};
+use crate::text_processing::{custom_words_for_language, TextPipeline};
+use crate::utils;
 use anyhow::Result;
-use natural::phonetics::soundex;
 use serde::Serialize;
-use std::sync::{Arc, Mutex};
-use strsim::levenshtein;
+use std::collections::VecDeque;
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::{mpsc, Arc, Mutex, MutexGuard, Weak};
+use std::thread::JoinHandle;
+use std::time::{Duration, Instant};
 use tauri::{App, AppHandle, Emitter, Manager};
-use whisper_rs::{
-    FullParams, SamplingStrategy, WhisperContext, WhisperContextParameters, WhisperState,
-};
+
+pub use crate::engines::TranscriptionResult;
 
 #[derive(Clone, Debug, Serialize)]
 pub struct ModelStateEvent {
@@ -18,144 +35,365 @@ pub struct ModelStateEvent {
     pub error: Option<String>,
 }
 
-pub struct TranscriptionManager {
-    state: Mutex<Option<WhisperState>>,
-    context: Mutex<Option<WhisperContext>>,
-    model_manager: Arc<ModelManager>,
+#[derive(Clone, Debug, Serialize)]
+pub struct TranscriptionModelStatus {
+    pub model_id: String,
+    /// `None` while the model is unloaded for being idle
+    pub backend: Option<ComputeBackend>,
+}
+
+#[derive(Clone, Debug, Serialize)]
+pub struct TranscriptionPartialEvent {
+    pub binding_id: String,
+    pub text: String,
+}
+
+#[derive(Clone, Debug, Serialize)]
+pub struct TranscriptionQueueEvent {
+    /// Dictations waiting or being decoded
+    pub depth: usize,
+}
+
+fn samples_to_ms(samples: usize) -> i64 {
+    (samples as u64 * 1000 / WHISPER_SAMPLE_RATE as u64) as i64
+}
+
+/// Text of the chunks transcribed so far, shown while the user keeps speaking.
+fn partial_text(chunks: &[(usize, StreamChunk)]) -> String {
+    let texts: Vec<&str> = chunks
+        .iter()
+        .filter_map(|(_, chunk)| match chunk {
+            StreamChunk::Transcribed(result) if !result.text.is_empty() => {
+                Some(result.text.as_str())
+            }
+            _ => None,
+        })
+        .collect();
+    texts.join(" ")
+}
+
+/// A streaming transcription running alongside an active recording. Chunks
+/// finalized by the VAD are transcribed on a worker thread while the user keeps
+/// speaking, so only the tail is left to decode when recording stops.
+pub struct StreamSession {
+    binding_id: String,
+    chunk_tx: mpsc::Sender<(usize, Vec<f32>)>,
+    worker: JoinHandle<()>,
+    /// Outcome of each chunk with its offset in samples, in recording order
+    chunks: Arc<Mutex<Vec<(usize, StreamChunk)>>>,
+    consumed_samples: usize,
+}
+
+/// What became of a chunk sent to the streaming worker.
+enum StreamChunk {
+    Transcribed(TranscriptionResult),
+    /// Decoding failed, the samples are decoded again when the session ends
+    Failed(Vec<f32>),
+}
+
+/// Receives the outcome of a queued job. Callbacks run on the queue worker in
+/// the order the jobs were queued.
+pub type JobCallback = Box<dyn FnOnce(Result<TranscriptionResult>) + Send>;
+
+/// Receives the number of processed and total samples of a file job.
+pub type ProgressCallback = Box<dyn FnMut(usize, usize) + Send>;
+
+/// Where the audio of a queued job comes from.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum JobSource {
+    /// A recording made with a shortcut
+    Dictation,
+    /// An audio file, decoded in chunks with its segments post-processed too
+    File,
+    /// An upload to the local transcription API
+    Api,
+}
+
+/// Settings a single job asks for on top of its binding's, such as the
+/// `language` and `prompt` fields of an API upload.
+#[derive(Clone, Debug, Default)]
+pub struct JobOverrides {
+    /// Language code to decode in instead of the selected language
+    pub language: Option<String>,
+    /// Initial prompt used as is, instead of the one built from the template
+    pub prompt: Option<String>,
+}
+
+/// A finished recording or an audio file waiting to be decoded.
+struct TranscriptionJob {
+    binding_id: String,
+    source: JobSource,
+    samples: Vec<f32>,
+    overrides: JobOverrides,
+    stream: Option<StreamSession>,
+    cancel: CancellationToken,
+    on_progress: Option<ProgressCallback>,
+    on_complete: JobCallback,
+}
+
+/// What the manager needs from the app around it: the settings, the model
+/// catalogue and the UI. Tests run the manager against a fake host.
+pub trait TranscriptionHost: Send + Sync {
+    fn settings(&self) -> AppSettings;
+    fn model_info(&self, model_id: &str) -> Option<ModelInfo>;
+    fn model_path(&self, model_id: &str) -> Result<PathBuf>;
+    fn remote_model(&self, model_id: &str) -> Option<RemoteModelConfig>;
+    fn create_engine(&self, kind: EngineKind) -> Box<dyn TranscriptionEngine>;
+    /// Sends an event to the frontend
+    fn emit(&self, event: &str, payload: serde_json::Value);
+    /// Number of dictations waiting or being decoded changed
+    fn queue_depth_changed(&self, depth: usize);
+    fn partial_transcription(&self, event: TranscriptionPartialEvent);
+    /// Switches the overlay to refining, unless a recording is showing in it
+    fn show_refining_overlay(&self);
+    /// Saves a finished, non-empty transcription to the history
+    fn add_history_entry(
+        &self,
+        binding_id: &str,
+        duration_ms: u64,
+        model_id: Option<String>,
+        language: String,
+        result: &TranscriptionResult,
+    );
+}
+
+/// The host of the running app.
+struct AppHost {
     app_handle: AppHandle,
-    current_model_id: Mutex<Option<String>>,
+    model_manager: Arc<ModelManager>,
 }
 
-fn apply_custom_words(text: &str, custom_words: &[String], threshold: f64) -> String {
-    if custom_words.is_empty() {
-        return text.to_string();
+impl TranscriptionHost for AppHost {
+    fn settings(&self) -> AppSettings {
+        get_settings(&self.app_handle)
     }
 
-    // Pre-compute lowercase versions to avoid repeated allocations
-    let custom_words_lower: Vec<String> = custom_words.iter().map(|w| w.to_lowercase()).collect();
+    fn model_info(&self, model_id: &str) -> Option<ModelInfo> {
+        self.model_manager.get_model_info(model_id)
+    }
 
-    let words: Vec<&str> = text.split_whitespace().collect();
-    let mut corrected_words = Vec::new();
+    fn model_path(&self, model_id: &str) -> Result<PathBuf> {
+        self.model_manager.get_model_path(model_id)
+    }
 
-    for word in words {
-        let cleaned_word = word
-            .trim_matches(|c: char| !c.is_alphabetic())
-            .to_lowercase();
+    fn remote_model(&self, model_id: &str) -> Option<RemoteModelConfig> {
+        self.model_manager.get_remote_model(model_id)
+    }
 
-        if cleaned_word.is_empty() {
-            corrected_words.push(word.to_string());
-            continue;
-        }
+    fn create_engine(&self, kind: EngineKind) -> Box<dyn TranscriptionEngine> {
+        create_engine(kind)
+    }
 
-        // Skip extremely long words to avoid performance issues
-        if cleaned_word.len() > 50 {
-            corrected_words.push(word.to_string());
-            continue;
-        }
+    fn emit(&self, event: &str, payload: serde_json::Value) {
+        let _ = self.app_handle.emit(event, payload);
+    }
 
-        let mut best_match: Option<&String> = None;
-        let mut best_score = f64::MAX;
+    fn queue_depth_changed(&self, depth: usize) {
+        utils::emit_queue_depth(&self.app_handle, TranscriptionQueueEvent { depth });
+        utils::update_tray_queue_depth(&self.app_handle, depth);
+    }
 
-        for (i, custom_word_lower) in custom_words_lower.iter().enumerate() {
-            // Skip if lengths are too different (optimization)
-            let len_diff = (cleaned_word.len() as i32 - custom_word_lower.len() as i32).abs();
-            if len_diff > 5 {
-                continue;
-            }
+    fn partial_transcription(&self, event: TranscriptionPartialEvent) {
+        utils::emit_partial_transcription(&self.app_handle, event);
+    }
 
-            // Calculate Levenshtein distance (normalized by length)
-            let levenshtein_dist = levenshtein(&cleaned_word, custom_word_lower);
-            let max_len = cleaned_word.len().max(custom_word_lower.len()) as f64;
-            let levenshtein_score = if max_len > 0.0 {
-                levenshtein_dist as f64 / max_len
-            } else {
-                1.0
-            };
+    fn show_refining_overlay(&self) {
+        // Don't take the overlay away from a recording that started meanwhile
+        let recording = self
+            .app_handle
+            .state::<Arc<AudioRecordingManager>>()
+            .is_recording();
+        if !recording {
+            utils::show_refining_overlay(&self.app_handle);
+        }
+    }
 
-            // Calculate phonetic similarity using Soundex
-            let phonetic_match = soundex(&cleaned_word, custom_word_lower);
+    fn add_history_entry(
+        &self,
+        binding_id: &str,
+        duration_ms: u64,
+        model_id: Option<String>,
+        language: String,
+        result: &TranscriptionResult,
+    ) {
+        let Some(hm) = self.app_handle.try_state::<Arc<HistoryManager>>() else {
+            return;
+        };
+        if let Err(e) = hm.add_entry(
+            result.text.clone(),
+            duration_ms,
+            model_id,
+            language,
+            binding_id.to_string(),
+            result.words.clone(),
+        ) {
+            eprintln!("Failed to save transcription to history: {}", e);
+        }
+    }
+}
 
-            // Combine scores: favor phonetic matches, but also consider string similarity
-            let combined_score = if phonetic_match {
-                levenshtein_score * 0.3 // Give significant boost to phonetic matches
-            } else {
-                levenshtein_score
-            };
+/// A loaded profile model with its id and the options it was loaded with.
+type ProfileEngine = (String, LoadOptions, Box<dyn TranscriptionEngine>);
 
-            // Accept if the score is good enough (configurable threshold)
-            if combined_score < threshold && combined_score < best_score {
-                best_match = Some(&custom_words[i]);
-                best_score = combined_score;
-            }
-        }
+pub struct TranscriptionManager {
+    engine: Mutex<Option<Box<dyn TranscriptionEngine>>>,
+    host: Arc<dyn TranscriptionHost>,
+    current_model_id: Mutex<Option<String>>,
+    /// What the current model was loaded with, a change requires a reload
+    current_load_options: Mutex<LoadOptions>,
+    /// Where the current model runs, `None` while no model is loaded
+    current_backend: Mutex<Option<ComputeBackend>>,
+    /// Set once loading on the GPU failed, later loads go straight to the CPU
+    gpu_failed: AtomicBool,
+    /// Local model standing in for an unreachable remote one, with its id
+    fallback_engine: Mutex<Option<(String, Box<dyn TranscriptionEngine>)>>,
+    /// Model a profile selects instead of the global one, with its id and
+    /// load options. Kept next to the global model so alternating between
+    /// bindings doesn't reload a model on every recording, at the cost of
+    /// holding both in memory.
+    profile_engine: Mutex<Option<ProfileEngine>>,
+    stream: Mutex<Option<StreamSession>>,
+    recent_transcriptions: Mutex<VecDeque<String>>,
+    /// Serializes model loads so a lazy reload and an explicit model switch
+    /// don't both load a copy
+    load_lock: Mutex<()>,
+    last_activity: Mutex<Instant>,
+    /// Token of the decode currently running, cancelled by `cancel_transcription`
+    cancel_token: Mutex<CancellationToken>,
+    job_tx: Mutex<mpsc::Sender<TranscriptionJob>>,
+    /// Taken by the queue worker when it starts
+    job_rx: Mutex<Option<mpsc::Receiver<TranscriptionJob>>>,
+    queue_depth: AtomicUsize,
+    /// The dictations among the queued jobs, the only ones the overlay and
+    /// tray show
+    pending_dictations: AtomicUsize,
+}
 
-        if let Some(replacement) = best_match {
-            // Preserve the original case pattern as much as possible
-            let corrected = if word.chars().all(|c| c.is_uppercase()) {
-                replacement.to_uppercase()
-            } else if word.chars().next().map_or(false, |c| c.is_uppercase()) {
-                let mut chars: Vec<char> = replacement.chars().collect();
-                if let Some(first_char) = chars.get_mut(0) {
-                    *first_char = first_char.to_uppercase().next().unwrap_or(*first_char);
-                }
-                chars.into_iter().collect()
-            } else {
-                replacement.clone()
-            };
+/// How often the idle watcher checks whether the model should be unloaded.
+const IDLE_CHECK_INTERVAL: Duration = Duration::from_secs(30);
 
-            // Preserve punctuation from original word - optimized version
-            let prefix_end = word.chars().take_while(|c| !c.is_alphabetic()).count();
-            let suffix_start = word
-                .char_indices()
-                .rev()
-                .take_while(|(_, c)| !c.is_alphabetic())
-                .count();
+/// Whisper only looks at roughly the last 224 prompt tokens, so anything longer
+/// would be cut anyway.
+const MAX_INITIAL_PROMPT_CHARS: usize = 800;
 
-            let original_prefix = if prefix_end > 0 {
-                &word[..prefix_end]
-            } else {
-                ""
-            };
-            let original_suffix = if suffix_start > 0 {
-                &word[word.len() - suffix_start..]
-            } else {
-                ""
-            };
+/// Fills the prompt template with the custom words and recent transcriptions.
+/// Returns `None` when there is nothing to prime the decoder with.
+fn build_initial_prompt(
+    template: &str,
+    custom_words: &[String],
+    context: &[String],
+) -> Option<String> {
+    let words = if custom_words.is_empty() {
+        String::new()
+    } else {
+        format!("{}.", custom_words.join(", "))
+    };
+    let context = context.join(" ");
 
-            corrected_words.push(format!(
-                "{}{}{}",
-                original_prefix, corrected, original_suffix
-            ));
-        } else {
-            corrected_words.push(word.to_string());
-        }
+    let prompt = template
+        .replace("{words}", &words)
+        .replace("{context}", &context);
+    let prompt = prompt.trim();
+
+    if prompt.is_empty() {
+        return None;
     }
 
-    corrected_words.join(" ")
+    // Keep the end of the prompt, it sits closest to the audio being decoded
+    let char_count = prompt.chars().count();
+    if char_count > MAX_INITIAL_PROMPT_CHARS {
+        let tail: String = prompt
+            .chars()
+            .skip(char_count - MAX_INITIAL_PROMPT_CHARS)
+            .collect();
+        let tail = match tail.find(' ') {
+            Some(idx) => tail[idx..].trim_start().to_string(),
+            None => tail,
+        };
+        return Some(tail);
+    }
+
+    Some(prompt.to_string())
 }
 
 impl TranscriptionManager {
     pub fn new(app: &App, model_manager: Arc<ModelManager>) -> Result<Self> {
-        let app_handle = app.app_handle().clone();
+        let host = AppHost {
+            app_handle: app.app_handle().clone(),
+            model_manager,
+        };
+        Self::with_host(Arc::new(host))
+    }
+
+    pub fn with_host(host: Arc<dyn TranscriptionHost>) -> Result<Self> {
+        let (job_tx, job_rx) = mpsc::channel();
 
         let manager = Self {
-            state: Mutex::new(None),
-            context: Mutex::new(None),
-            model_manager,
-            app_handle: app_handle.clone(),
+            engine: Mutex::new(None),
+            host,
             current_model_id: Mutex::new(None),
+            current_load_options: Mutex::new(LoadOptions::default()),
+            current_backend: Mutex::new(None),
+            gpu_failed: AtomicBool::new(false),
+            fallback_engine: Mutex::new(None),
+            profile_engine: Mutex::new(None),
+            stream: Mutex::new(None),
+            recent_transcriptions: Mutex::new(VecDeque::new()),
+            load_lock: Mutex::new(()),
+            last_activity: Mutex::new(Instant::now()),
+            cancel_token: Mutex::new(CancellationToken::default()),
+            job_tx: Mutex::new(job_tx),
+            job_rx: Mutex::new(Some(job_rx)),
+            queue_depth: AtomicUsize::new(0),
+            pending_dictations: AtomicUsize::new(0),
         };
 
         // Try to load the default model from settings, but don't fail if no models are available
-        let settings = get_settings(&app_handle);
+        let settings = manager.host.settings();
         let _ = manager.load_model(&settings.selected_model);
 
         Ok(manager)
     }
 
+    /// Loads `model_id` with the decoder settings saved for it.
     pub fn load_model(&self, model_id: &str) -> Result<()> {
+        let settings = self.host.settings();
+        let options = decoder_settings_for_model(&settings, model_id).load_options();
+        let options = self.effective_load_options(&options);
+        let _load_guard = self.load_lock.lock().unwrap();
+        self.load_model_locked(model_id, &options)
+    }
+
+    /// Turns the GPU off when CPU-only mode is on, or when the GPU already
+    /// failed to load a model in this session.
+    fn effective_load_options(&self, options: &LoadOptions) -> LoadOptions {
+        let force_cpu = self.host.settings().force_cpu;
+        LoadOptions {
+            use_gpu: options.use_gpu && !force_cpu && !self.gpu_failed.load(Ordering::SeqCst),
+            ..*options
+        }
+    }
+
+    /// Loads `model_id` unless it is already the loaded model and was loaded
+    /// with the same options. Used to reload a model transparently after it
+    /// was unloaded for being idle, or after its GPU settings changed.
+    pub fn ensure_model_loaded(&self, model_id: &str, options: &LoadOptions) -> Result<()> {
+        let options = &self.effective_load_options(options);
+        let _load_guard = self.load_lock.lock().unwrap();
+        // A job is about to use the model, keep the idle watcher away from it
+        *self.last_activity.lock().unwrap() = Instant::now();
+        if self.is_model_loaded()
+            && self.get_current_model().as_deref() == Some(model_id)
+            && *self.current_load_options.lock().unwrap() == *options
+        {
+            return Ok(());
+        }
+        self.load_model_locked(model_id, options)
+    }
+
+    fn load_model_locked(&self, model_id: &str, options: &LoadOptions) -> Result<()> {
         // Emit loading started event
-        let _ = self.app_handle.emit(
+        self.emit(
             "model-state-changed",
             ModelStateEvent {
                 event_type: "loading_started".to_string(),
@@ -166,13 +404,13 @@ impl TranscriptionManager {
         );
 
         let model_info = self
-            .model_manager
-            .get_model_info(model_id)
+            .host
+            .model_info(model_id)
             .ok_or_else(|| anyhow::anyhow!("Model not found: {}", model_id))?;
 
         if !model_info.is_downloaded {
             let error_msg = "Model not downloaded";
-            let _ = self.app_handle.emit(
+            self.emit(
                 "model-state-changed",
                 ModelStateEvent {
                     event_type: "loading_failed".to_string(),
@@ -184,65 +422,51 @@ impl TranscriptionManager {
             return Err(anyhow::anyhow!(error_msg));
         }
 
-        let model_path = self.model_manager.get_model_path(model_id)?;
-
-        let path_str = model_path
-            .to_str()
-            .ok_or_else(|| anyhow::anyhow!("Invalid path for model: {}", model_id))?;
+        let model_path = self.host.model_path(model_id)?;
 
         println!(
-            "Loading transcription model {} from: {}",
-            model_id, path_str
+            "Loading transcription model {} ({:?} engine) from: {:?}",
+            model_id, model_info.engine, model_path
         );
 
-        // Create new context
-        let context =
-            WhisperContext::new_with_params(path_str, WhisperContextParameters::default())
-                .map_err(|e| {
-                    let error_msg = format!("Failed to load whisper model {}: {}", model_id, e);
-                    let _ = self.app_handle.emit(
-                        "model-state-changed",
-                        ModelStateEvent {
-                            event_type: "loading_failed".to_string(),
-                            model_id: Some(model_id.to_string()),
-                            model_name: Some(model_info.name.clone()),
-                            error: Some(error_msg.clone()),
-                        },
-                    );
-                    anyhow::anyhow!(error_msg)
-                })?;
-
-        // Create new state
-        let state = context.create_state().map_err(|e| {
-            let error_msg = format!("Failed to create state for model {}: {}", model_id, e);
-            let _ = self.app_handle.emit(
-                "model-state-changed",
-                ModelStateEvent {
-                    event_type: "loading_failed".to_string(),
-                    model_id: Some(model_id.to_string()),
-                    model_name: Some(model_info.name.clone()),
-                    error: Some(error_msg.clone()),
-                },
-            );
-            anyhow::anyhow!(error_msg)
-        })?;
-
-        // Update the current context and state
-        {
-            let mut current_context = self.context.lock().unwrap();
-            *current_context = Some(context);
+        let mut engine = self.host.create_engine(model_info.engine);
+        let backend = match load_with_cpu_fallback(engine.as_mut(), &model_path, options) {
+            Ok(backend) => backend,
+            Err(e) => {
+                let error_msg = format!("Failed to load model {}: {}", model_id, e);
+                self.emit(
+                    "model-state-changed",
+                    ModelStateEvent {
+                        event_type: "loading_failed".to_string(),
+                        model_id: Some(model_id.to_string()),
+                        model_name: Some(model_info.name.clone()),
+                        error: Some(error_msg.clone()),
+                    },
+                );
+                return Err(anyhow::anyhow!(error_msg));
+            }
+        };
+        if options.use_gpu && backend == ComputeBackend::Cpu {
+            self.gpu_failed.store(true, Ordering::SeqCst);
         }
-        {
-            let mut current_state = self.state.lock().unwrap();
-            *current_state = Some(state);
+
+        // Swap in the new engine, the previous model stays usable until here
+        let previous = self.engine.lock().unwrap().replace(engine);
+        if let Some(mut previous) = previous {
+            previous.unload();
         }
         {
             let mut current_model = self.current_model_id.lock().unwrap();
             *current_model = Some(model_id.to_string());
         }
+        *self.current_load_options.lock().unwrap() = LoadOptions {
+            use_gpu: backend == ComputeBackend::Gpu,
+            ..*options
+        };
+        *self.current_backend.lock().unwrap() = Some(backend);
 
         // Emit loading completed event
-        let _ = self.app_handle.emit(
+        self.emit(
             "model-state-changed",
             ModelStateEvent {
                 event_type: "loading_completed".to_string(),
@@ -252,79 +476,387 @@ impl TranscriptionManager {
             },
         );
 
-        println!("Successfully loaded transcription model: {}", model_id);
+        *self.last_activity.lock().unwrap() = Instant::now();
+
+        println!(
+            "Successfully loaded transcription model: {} ({})",
+            model_id, backend
+        );
+        Ok(())
+    }
+
+    /// Whether the profile of the binding `settings` were resolved for selects
+    /// another model than the global settings.
+    fn uses_profile_model(&self, settings: &AppSettings) -> bool {
+        !settings.selected_model.is_empty()
+            && settings.selected_model != self.host.settings().selected_model
+    }
+
+    /// Loads the model `binding_id` transcribes with unless it is loaded
+    /// already. A model selected by the binding's profile goes into its own
+    /// slot, leaving the global model loaded.
+    pub fn preload_for_binding(&self, binding_id: Option<&str>) -> Result<()> {
+        let settings = self.settings_for(binding_id);
+        if settings.selected_model.is_empty() {
+            return Ok(());
+        }
+        let options =
+            decoder_settings_for_model(&settings, &settings.selected_model).load_options();
+        if self.uses_profile_model(&settings) {
+            self.ensure_profile_model_loaded(&settings.selected_model, &options)
+        } else {
+            self.ensure_model_loaded(&settings.selected_model, &options)
+        }
+    }
+
+    fn ensure_profile_model_loaded(&self, model_id: &str, options: &LoadOptions) -> Result<()> {
+        let options = self.effective_load_options(options);
+        let _load_guard = self.load_lock.lock().unwrap();
+        let mut slot = self.profile_engine.lock().unwrap();
+        let loaded = matches!(
+            &*slot,
+            Some((id, loaded_options, engine))
+                if id == model_id && *loaded_options == options && engine.is_loaded()
+        );
+        if loaded {
+            return Ok(());
+        }
+        if let Some((_, _, mut previous)) = slot.take() {
+            previous.unload();
+        }
+
+        let model_info = self
+            .host
+            .model_info(model_id)
+            .ok_or_else(|| anyhow::anyhow!("Model not found: {}", model_id))?;
+        if !model_info.is_downloaded {
+            return Err(anyhow::anyhow!("Model not downloaded"));
+        }
+        let model_path = self.host.model_path(model_id)?;
+
+        println!(
+            "Loading profile model {} next to {:?}, both stay in memory",
+            model_id,
+            self.get_current_model()
+        );
+        let mut engine = self.host.create_engine(model_info.engine);
+        load_with_cpu_fallback(engine.as_mut(), &model_path, &options)?;
+        *slot = Some((model_id.to_string(), options, engine));
+        *self.last_activity.lock().unwrap() = Instant::now();
         Ok(())
     }
 
+    fn emit<S: Serialize>(&self, event: &str, payload: S) {
+        match serde_json::to_value(payload) {
+            Ok(payload) => self.host.emit(event, payload),
+            Err(e) => eprintln!("Failed to serialize {} event: {}", event, e),
+        }
+    }
+
+    /// The settings with the profile of `binding_id` applied.
+    fn settings_for(&self, binding_id: Option<&str>) -> AppSettings {
+        resolve_binding_settings(self.host.settings(), binding_id)
+    }
+
     pub fn get_current_model(&self) -> Option<String> {
         let current_model = self.current_model_id.lock().unwrap();
         current_model.clone()
     }
 
-    pub fn transcribe(&self, audio: Vec<f32>) -> Result<String> {
+    /// The current model and where it runs, `None` before any model was
+    /// loaded.
+    pub fn get_model_status(&self) -> Option<TranscriptionModelStatus> {
+        let model_id = self.get_current_model()?;
+        let backend = *self.current_backend.lock().unwrap();
+        Some(TranscriptionModelStatus { model_id, backend })
+    }
+
+    /// Installs `engine` as if `model_id` had been loaded with it.
+    #[cfg(test)]
+    fn set_engine(&self, model_id: &str, engine: Box<dyn TranscriptionEngine>) {
+        *self.engine.lock().unwrap() = Some(engine);
+        *self.current_model_id.lock().unwrap() = Some(model_id.to_string());
+        *self.current_backend.lock().unwrap() = Some(ComputeBackend::Cpu);
+    }
+
+    pub fn is_model_loaded(&self) -> bool {
+        self.engine
+            .lock()
+            .unwrap()
+            .as_ref()
+            .map_or(false, |engine| engine.is_loaded())
+    }
+
+    /// Drops the loaded model to free its memory. The model id is kept so the
+    /// next transcription can reload it. Waits for a load or decode that is
+    /// running to finish.
+    pub fn unload_model(&self, reason: &str) {
+        let _load_guard = self.load_lock.lock().unwrap();
+        let engine = self.engine.lock().unwrap();
+        self.unload_locked(engine, reason);
+    }
+
+    /// Unloads every model. The caller holds the load lock and hands over the
+    /// engine lock, so no load or decode can start halfway through.
+    fn unload_locked(
+        &self,
+        mut engine: MutexGuard<Option<Box<dyn TranscriptionEngine>>>,
+        reason: &str,
+    ) {
+        if let Some((_, mut fallback)) = self.fallback_engine.lock().unwrap().take() {
+            fallback.unload();
+        }
+        if let Some((_, _, mut profile)) = self.profile_engine.lock().unwrap().take() {
+            profile.unload();
+        }
+        match engine.take() {
+            Some(mut engine) => engine.unload(),
+            None => return,
+        }
+        *self.current_backend.lock().unwrap() = None;
+
+        let model_id = self.get_current_model();
+        println!("Unloaded transcription model {:?} ({})", model_id, reason);
+
+        self.emit(
+            "model-state-changed",
+            ModelStateEvent {
+                event_type: "unloaded".to_string(),
+                model_id,
+                model_name: None,
+                error: None,
+            },
+        );
+    }
+
+    /// Starts a background thread that unloads the model after the configured
+    /// idle time, or when free system memory drops below the configured
+    /// threshold.
+    pub fn start_idle_watcher(self: &Arc<Self>) {
+        let manager: Weak<Self> = Arc::downgrade(self);
+        std::thread::spawn(move || {
+            let mut system = sysinfo::System::new();
+            loop {
+                std::thread::sleep(IDLE_CHECK_INTERVAL);
+                match manager.upgrade() {
+                    Some(manager) => manager.check_idle_unload(&mut system),
+                    None => break,
+                }
+            }
+        });
+    }
+
+    fn check_idle_unload(&self, system: &mut sysinfo::System) {
+        // Decide while holding the locks, so a job can't load the model or
+        // start decoding between the checks and the unload. A busy lock means
+        // the model is in use.
+        let Ok(_load_guard) = self.load_lock.try_lock() else {
+            return;
+        };
+        let Ok(engine) = self.engine.try_lock() else {
+            return;
+        };
+
+        // Never pull the model out from under an active dictation
+        let loaded = engine.as_ref().map_or(false, |engine| engine.is_loaded());
+        if self.is_streaming() || self.queue_depth() > 0 || !loaded {
+            return;
+        }
+
+        let settings = self.host.settings();
+
+        if settings.model_unload_timeout_minutes > 0 {
+            let timeout = Duration::from_secs(settings.model_unload_timeout_minutes as u64 * 60);
+            let idle_for = self.last_activity.lock().unwrap().elapsed();
+            if idle_for >= timeout {
+                self.unload_locked(engine, &format!("idle for {}s", idle_for.as_secs()));
+                return;
+            }
+        }
+
+        if settings.low_memory_unload_threshold_mb > 0 {
+            system.refresh_memory();
+            let available_mb = system.available_memory() / (1024 * 1024);
+            if available_mb < settings.low_memory_unload_threshold_mb {
+                self.unload_locked(
+                    engine,
+                    &format!("only {}MB of memory available", available_mb),
+                );
+            }
+        }
+    }
+
+    /// Transcribes 16 kHz mono samples using the settings resolved for
+    /// `binding_id`. The binding's model is loaded first if it isn't already,
+    /// either because its profile selects a different one or because the
+    /// model was unloaded while idle. The text is returned as decoded, run it
+    /// through `post_process` before showing it to the user. Only dictations
+    /// prime the prompt with, and are kept as, recent transcriptions, so
+    /// uploads and files don't steer what is dictated next.
+    pub fn transcribe(
+        &self,
+        audio: Vec<f32>,
+        binding_id: Option<&str>,
+        source: JobSource,
+    ) -> Result<TranscriptionResult> {
+        self.transcribe_with(audio, binding_id, source, &JobOverrides::default(), true)
+    }
+
+    /// Transcribes one chunk of a streamed dictation. Chunks skip the
+    /// no-speech estimate, which costs whisper an extra encoder pass per
+    /// chunk; the hallucination filter falls back to its own silence check.
+    fn transcribe_chunk(&self, audio: Vec<f32>, binding_id: &str) -> Result<TranscriptionResult> {
+        self.transcribe_with(
+            audio,
+            Some(binding_id),
+            JobSource::Dictation,
+            &JobOverrides::default(),
+            false,
+        )
+    }
+
+    fn transcribe_with(
+        &self,
+        audio: Vec<f32>,
+        binding_id: Option<&str>,
+        source: JobSource,
+        overrides: &JobOverrides,
+        estimate_no_speech: bool,
+    ) -> Result<TranscriptionResult> {
         let st = std::time::Instant::now();
 
-        let mut result = String::new();
         println!("Audio vector length: {}", audio.len());
 
         if audio.len() == 0 {
             println!("Empty audio vector");
-            return Ok(result);
+            return Ok(TranscriptionResult::default());
+        }
+
+        let mut settings = self.settings_for(binding_id);
+        if let Some(language) = &overrides.language {
+            settings.selected_language = language.clone();
         }
+        let decoder = decoder_settings_for_model(&settings, &settings.selected_model);
 
-        let mut state_guard = self.state.lock().unwrap();
-        let state = state_guard.as_mut().ok_or_else(|| {
+        // Reload the model if it was unloaded while idle, or load the one the
+        // binding's profile asks for
+        if let Err(e) = self.preload_for_binding(binding_id) {
+            eprintln!(
+                "Failed to load model {}, keeping current model: {}",
+                settings.selected_model, e
+            );
+        }
+
+        let use_profile_engine = self.uses_profile_model(&settings)
+            && matches!(
+                &*self.profile_engine.lock().unwrap(),
+                Some((id, _, engine)) if *id == settings.selected_model && engine.is_loaded()
+            );
+        let mut global_guard = None;
+        let mut profile_guard = None;
+        let engine = if use_profile_engine {
+            profile_guard
+                .insert(self.profile_engine.lock().unwrap())
+                .as_mut()
+                .map(|(_, _, engine)| engine)
+        } else {
+            global_guard.insert(self.engine.lock().unwrap()).as_mut()
+        };
+        let engine = engine.filter(|engine| engine.is_loaded()).ok_or_else(|| {
             anyhow::anyhow!(
                 "No model loaded. Please download and select a model from settings first."
             )
         })?;
 
-        // Get current settings to check translation preference
-        let settings = get_settings(&self.app_handle);
-
-        // Initialize parameters
-        let mut params = FullParams::new(SamplingStrategy::default());
-        let language = Some(settings.selected_language.as_str());
-        params.set_language(language);
-        params.set_print_special(false);
-        params.set_print_progress(false);
-        params.set_print_realtime(false);
-        params.set_print_timestamps(false);
-        params.set_suppress_blank(true);
-        params.set_suppress_non_speech_tokens(true);
-        params.set_no_speech_thold(0.2);
-
-        // Enable translation to English if requested
-        if settings.translate_to_english {
-            params.set_translate(true);
-        }
-
-        state
-            .full(params, &audio)
-            .expect("failed to convert samples");
-
-        let num_segments = state
-            .full_n_segments()
-            .expect("failed to get number of segments");
-
-        for i in 0..num_segments {
-            let segment = state
-                .full_get_segment_text(i)
-                .expect("failed to get segment");
-            result.push_str(&segment);
-        }
-
-        // Apply word correction if custom words are configured
-        let corrected_result = if !settings.custom_words.is_empty() {
-            apply_custom_words(
-                &result,
-                &settings.custom_words,
-                settings.word_correction_threshold,
+        let capabilities = engine.capabilities();
+        if settings.translate_to_english && !capabilities.translation {
+            println!("Current engine does not support translation, transcribing as-is");
+        }
+
+        let initial_prompt = if !capabilities.initial_prompt {
+            None
+        } else if let Some(prompt) = &overrides.prompt {
+            Some(prompt.clone())
+        } else {
+            let context: Vec<String> = if source == JobSource::Dictation {
+                let recent = self.recent_transcriptions.lock().unwrap();
+                let skip = recent.len().saturating_sub(settings.prompt_context_length);
+                recent.iter().skip(skip).cloned().collect()
+            } else {
+                Vec::new()
+            };
+            build_initial_prompt(
+                &settings.initial_prompt_template,
+                &custom_words_for_language(&settings),
+                &context,
             )
+        };
+
+        let cancel = self.cancel_token.lock().unwrap().clone();
+
+        let options = TranscribeOptions {
+            language: settings.selected_language.clone(),
+            detect_languages: settings.auto_detect_languages.clone(),
+            translate: settings.translate_to_english && capabilities.translation,
+            initial_prompt,
+            cancel: cancel.clone(),
+            timeout: (settings.max_decode_seconds > 0)
+                .then(|| Duration::from_secs(settings.max_decode_seconds as u64)),
+            // Only the hallucination filter looks at it
+            no_speech_probability: settings.hallucination_filter && estimate_no_speech,
+            decoder,
+        };
+
+        let result = engine.transcribe(&audio, &options);
+        drop(global_guard);
+        drop(profile_guard);
+        let model_id = if use_profile_engine {
+            Some(settings.selected_model.clone())
+        } else {
+            self.get_current_model()
+        };
+
+        // An unreachable server shouldn't lose the recording, decode it locally
+        let result = match result {
+            Err(e)
+                if capabilities.remote
+                    && e.downcast_ref::<TranscriptionInterrupted>().is_none() =>
+            {
+                self.transcribe_locally(model_id.as_deref(), &audio, &options, e)
+            }
+            result => result,
+        };
+        *self.last_activity.lock().unwrap() = Instant::now();
+        let result = result?;
+
+        // A cancel that arrives just as decoding finishes still discards the text
+        if cancel.is_cancelled() {
+            return Err(TranscriptionInterrupted::Cancelled.into());
+        }
+
+        // Drop text whisper made up from silence or noise before it can end up
+        // in the clipboard or the prompt context
+        let result = if settings.hallucination_filter {
+            HallucinationFilter::new(&settings.hallucination_blocklist).apply(result, &audio)
         } else {
             result
         };
 
+        let TranscriptionResult {
+            text,
+            segments,
+            language,
+            language_probability,
+            filtered,
+            ..
+        } = result;
+
+        // Only words of the segments the filter kept
+        let words = segments
+            .iter()
+            .flat_map(|segment| merge_tokens_into_words(&segment.tokens))
+            .collect();
+
         let et = std::time::Instant::now();
         let translation_note = if settings.translate_to_english {
             " (translated)"
@@ -333,6 +865,1259 @@ impl TranscriptionManager {
         };
         println!("\ntook {}ms{}", (et - st).as_millis(), translation_note);
 
-        Ok(corrected_result.trim().to_string())
+        let final_text = text.trim().to_string();
+
+        if source == JobSource::Dictation
+            && settings.prompt_context_length > 0
+            && !final_text.is_empty()
+        {
+            let mut recent = self.recent_transcriptions.lock().unwrap();
+            recent.push_back(final_text.clone());
+            while recent.len() > settings.prompt_context_length {
+                recent.pop_front();
+            }
+        }
+
+        if let (Some(language), Some(probability)) = (&language, language_probability) {
+            println!(
+                "Detected language: {} ({:.0}%)",
+                language,
+                probability * 100.0
+            );
+        }
+
+        Ok(TranscriptionResult {
+            text: final_text,
+            segments,
+            language,
+            language_probability,
+            filtered,
+            words,
+        })
+    }
+
+    /// Runs the processing chain selected for `binding_id` over a transcript.
+    /// `detected_language` picks the language rules when the language is set
+    /// to auto-detect.
+    pub fn post_process(
+        &self,
+        text: &str,
+        binding_id: Option<&str>,
+        detected_language: Option<&str>,
+    ) -> String {
+        let mut settings = self.settings_for(binding_id);
+        if settings.selected_language == "auto" {
+            if let Some(language) = detected_language {
+                settings.selected_language = language.to_string();
+            }
+        }
+        TextPipeline::from_settings(&settings).process(text)
+    }
+
+    /// Transcribes with the fallback model of the remote model `model_id` after
+    /// the server failed with `error`. Without a fallback model the error is
+    /// returned.
+    fn transcribe_locally(
+        &self,
+        model_id: Option<&str>,
+        audio: &[f32],
+        options: &TranscribeOptions,
+        error: anyhow::Error,
+    ) -> Result<TranscriptionResult> {
+        let Some(fallback_id) = model_id
+            .and_then(|id| self.host.remote_model(id))
+            .and_then(|config| config.fallback_model)
+        else {
+            return Err(error);
+        };
+        eprintln!(
+            "Remote transcription failed, falling back to {}: {}",
+            fallback_id, error
+        );
+        self.emit("remote-transcription-failed", error.to_string());
+
+        let settings = self.host.settings();
+        let decoder = decoder_settings_for_model(&settings, &fallback_id);
+
+        let mut fallback = self.fallback_engine.lock().unwrap();
+        let loaded =
+            matches!(&*fallback, Some((id, engine)) if *id == fallback_id && engine.is_loaded());
+        if !loaded {
+            let model_info = self
+                .host
+                .model_info(&fallback_id)
+                .ok_or_else(|| anyhow::anyhow!("Fallback model not found: {}", fallback_id))?;
+            if model_info.engine == EngineKind::Remote {
+                return Err(anyhow::anyhow!(
+                    "Fallback model {} is remote too",
+                    fallback_id
+                ));
+            }
+            let model_path = self.host.model_path(&fallback_id)?;
+            let load_options = self.effective_load_options(&decoder.load_options());
+            let mut engine = self.host.create_engine(model_info.engine);
+            load_with_cpu_fallback(engine.as_mut(), &model_path, &load_options)?;
+            println!("Loaded fallback model {}", fallback_id);
+            *fallback = Some((fallback_id.clone(), engine));
+        }
+
+        let (_, engine) = fallback.as_mut().unwrap();
+        let options = TranscribeOptions {
+            decoder,
+            ..options.clone()
+        };
+        engine.transcribe(audio, &options)
+    }
+
+    /// Rewrites a post-processed transcript with the language model prompt
+    /// selected for `binding_id`. Returns `text` unchanged when refinement is
+    /// off, when the model settings are invalid, and when the model fails or
+    /// times out. Only dictations switch the overlay to refining, nothing
+    /// else would switch it back.
+    pub fn refine(
+        &self,
+        text: &str,
+        binding_id: Option<&str>,
+        detected_language: Option<&str>,
+        source: JobSource,
+    ) -> String {
+        let settings = self.settings_for(binding_id);
+        let Some(prompt_id) = settings.selected_refinement_prompt.as_deref() else {
+            return text.to_string();
+        };
+        if text.trim().is_empty() {
+            return text.to_string();
+        }
+        let Some(prompt) = settings.refinement_prompts.get(prompt_id) else {
+            eprintln!("Refinement prompt '{}' not found, skipping", prompt_id);
+            return text.to_string();
+        };
+
+        if let Err(e) = settings.llm.validate() {
+            eprintln!("Refinement model settings are invalid, skipping: {}", e);
+            self.emit(
+                "refinement-failed",
+                format!("Refinement model settings are invalid: {}", e),
+            );
+            return text.to_string();
+        }
+
+        if source == JobSource::Dictation {
+            self.host.show_refining_overlay();
+        }
+
+        let language = match detected_language {
+            Some(language) => language,
+            None if settings.selected_language != "auto" => settings.selected_language.as_str(),
+            None => "the original language",
+        };
+
+        let st = Instant::now();
+        let refined = tauri::async_runtime::block_on(llm_refinement::refine(
+            &settings.llm,
+            prompt,
+            text,
+            language,
+        ));
+        match refined {
+            Ok(refined) => {
+                println!(
+                    "Refined transcript with '{}' in {}ms",
+                    prompt_id,
+                    st.elapsed().as_millis()
+                );
+                refined
+            }
+            Err(e) => {
+                eprintln!("Refinement failed, using the raw transcript: {}", e);
+                self.emit("refinement-failed", e.to_string());
+                text.to_string()
+            }
+        }
+    }
+
+    /// Stops the decode that is currently running and drops every queued job.
+    /// Interrupted and dropped work reports `TranscriptionInterrupted::Cancelled`.
+    pub fn cancel_transcription(&self) {
+        let mut token = self.cancel_token.lock().unwrap();
+        token.cancel();
+        // Work started after this point must not inherit the cancellation
+        *token = CancellationToken::default();
+    }
+
+    /* ---------- job queue ---------------------------------------------------- */
+
+    /// Starts the thread that decodes queued recordings one at a time.
+    pub fn start_job_worker(self: &Arc<Self>) {
+        let job_rx = match self.job_rx.lock().unwrap().take() {
+            Some(job_rx) => job_rx,
+            None => return,
+        };
+        let manager: Weak<Self> = Arc::downgrade(self);
+        std::thread::spawn(move || {
+            while let Ok(job) = job_rx.recv() {
+                match manager.upgrade() {
+                    Some(manager) => manager.run_job(job),
+                    None => break,
+                }
+            }
+        });
+    }
+
+    /// Queues a finished recording or an uploaded clip and returns
+    /// immediately, so a new recording can start while earlier ones are still
+    /// decoding. `stream` is the session taken with `take_stream` when the
+    /// recording was streamed.
+    pub fn enqueue(
+        &self,
+        binding_id: &str,
+        source: JobSource,
+        samples: Vec<f32>,
+        stream: Option<StreamSession>,
+        on_complete: JobCallback,
+    ) {
+        self.push_job(TranscriptionJob {
+            binding_id: binding_id.to_string(),
+            source,
+            samples,
+            overrides: JobOverrides::default(),
+            stream,
+            cancel: self.cancel_token.lock().unwrap().clone(),
+            on_progress: None,
+            on_complete,
+        });
+    }
+
+    /// Queues an upload to the local API, decoded with the binding's settings
+    /// and the request's `overrides`.
+    pub fn enqueue_upload(
+        &self,
+        binding_id: &str,
+        samples: Vec<f32>,
+        overrides: JobOverrides,
+        on_complete: JobCallback,
+    ) {
+        self.push_job(TranscriptionJob {
+            binding_id: binding_id.to_string(),
+            source: JobSource::Api,
+            samples,
+            overrides,
+            stream: None,
+            cancel: self.cancel_token.lock().unwrap().clone(),
+            on_progress: None,
+            on_complete,
+        });
+    }
+
+    /// Queues the decoded samples of an audio file behind the recordings
+    /// already waiting. `on_progress` is called after every chunk.
+    pub fn enqueue_file(
+        &self,
+        binding_id: Option<&str>,
+        samples: Vec<f32>,
+        on_progress: ProgressCallback,
+        on_complete: JobCallback,
+    ) {
+        self.push_job(TranscriptionJob {
+            binding_id: binding_id.unwrap_or_default().to_string(),
+            source: JobSource::File,
+            samples,
+            overrides: JobOverrides::default(),
+            stream: None,
+            cancel: self.cancel_token.lock().unwrap().clone(),
+            on_progress: Some(on_progress),
+            on_complete,
+        });
+    }
+
+    fn push_job(&self, job: TranscriptionJob) {
+        let binding_id = job.binding_id.clone();
+        let dictation = job.source == JobSource::Dictation;
+        let depth = self.queue_depth.fetch_add(1, Ordering::SeqCst) + 1;
+        if dictation {
+            self.pending_dictations.fetch_add(1, Ordering::SeqCst);
+        }
+        let sent = self.job_tx.lock().unwrap().send(job);
+        if let Err(mpsc::SendError(job)) = sent {
+            self.queue_depth.fetch_sub(1, Ordering::SeqCst);
+            if dictation {
+                self.pending_dictations.fetch_sub(1, Ordering::SeqCst);
+            }
+            (job.on_complete)(Err(anyhow::anyhow!("Transcription queue is not running")));
+            return;
+        }
+        println!(
+            "Queued transcription for binding {} ({} pending)",
+            binding_id, depth
+        );
+        if dictation {
+            self.emit_queue_depth();
+        }
+    }
+
+    /// Number of jobs waiting or being decoded.
+    pub fn queue_depth(&self) -> usize {
+        self.queue_depth.load(Ordering::SeqCst)
+    }
+
+    /// Number of dictations waiting or being decoded, leaving out API uploads
+    /// and files.
+    pub fn pending_dictations(&self) -> usize {
+        self.pending_dictations.load(Ordering::SeqCst)
+    }
+
+    fn run_job(&self, job: TranscriptionJob) {
+        let TranscriptionJob {
+            binding_id,
+            source,
+            samples,
+            overrides,
+            stream,
+            cancel,
+            on_progress,
+            on_complete,
+        } = job;
+        // File jobs without a binding use the global settings
+        let binding = Some(binding_id.as_str()).filter(|id| !id.is_empty());
+
+        let duration_ms = samples_to_ms(samples.len()) as u64;
+        let result = if cancel.is_cancelled() {
+            Err(TranscriptionInterrupted::Cancelled.into())
+        } else if let Some(on_progress) = on_progress {
+            self.transcribe_file(&samples, binding, on_progress)
+        } else {
+            match stream {
+                Some(session) => self.finish_session(session, samples),
+                None => self.transcribe_with(samples, binding, source, &overrides, true),
+            }
+            .map(|mut result| {
+                result.text = self.post_process(&result.text, binding, result.language.as_deref());
+                result.text =
+                    self.refine(&result.text, binding, result.language.as_deref(), source);
+                result
+            })
+            .and_then(|result| {
+                // The refinement may have taken a while, honour a cancel made meanwhile
+                if cancel.is_cancelled() {
+                    Err(TranscriptionInterrupted::Cancelled.into())
+                } else {
+                    Ok(result)
+                }
+            })
+        };
+
+        // Keep a copy so a paste that lands in the wrong window isn't lost.
+        // Files keep their transcript in the output file instead.
+        if let (Ok(result), JobSource::Dictation) = (&result, source) {
+            if !result.text.is_empty() {
+                let settings = self.settings_for(binding);
+                let model_id = if self.uses_profile_model(&settings) {
+                    Some(settings.selected_model)
+                } else {
+                    self.get_current_model()
+                };
+                let language = result
+                    .language
+                    .clone()
+                    .unwrap_or(settings.selected_language);
+                self.host
+                    .add_history_entry(&binding_id, duration_ms, model_id, language, result);
+            }
+        }
+
+        // Count the job as done before the callback so it sees the remaining depth
+        self.queue_depth.fetch_sub(1, Ordering::SeqCst);
+        if source == JobSource::Dictation {
+            self.pending_dictations.fetch_sub(1, Ordering::SeqCst);
+            self.emit_queue_depth();
+        }
+        on_complete(result);
+    }
+
+    /// Decodes a file chunk by chunk and post-processes the full text as well
+    /// as every segment, so subtitles read the same as the plain transcript.
+    fn transcribe_file(
+        &self,
+        samples: &[f32],
+        binding_id: Option<&str>,
+        on_progress: ProgressCallback,
+    ) -> Result<TranscriptionResult> {
+        let mut result = transcribe_in_chunks(
+            samples,
+            |chunk| self.transcribe(chunk, binding_id, JobSource::File),
+            on_progress,
+        )?;
+
+        let language = result.language.clone();
+        result.text = self.post_process(&result.text, binding_id, language.as_deref());
+        for segment in &mut result.segments {
+            segment.text = self.post_process(&segment.text, binding_id, language.as_deref());
+        }
+        Ok(result)
+    }
+
+    fn emit_queue_depth(&self) {
+        self.host.queue_depth_changed(self.pending_dictations());
+    }
+
+    /* ---------- streaming ---------------------------------------------------- */
+
+    /// Starts a streaming session for the given binding. Any previous session is
+    /// discarded.
+    pub fn start_stream(self: &Arc<Self>, binding_id: &str) {
+        self.cancel_stream();
+
+        let (chunk_tx, chunk_rx) = mpsc::channel::<(usize, Vec<f32>)>();
+        let chunks = Arc::new(Mutex::new(Vec::new()));
+
+        let worker = {
+            let manager = Arc::clone(self);
+            let chunks = Arc::clone(&chunks);
+            let binding_id = binding_id.to_string();
+            std::thread::spawn(move || {
+                // Ends once the session drops its sender
+                while let Ok((offset, chunk)) = chunk_rx.recv() {
+                    match manager.transcribe_chunk(chunk.clone(), &binding_id) {
+                        Ok(chunk_result) => {
+                            let has_text = !chunk_result.text.is_empty();
+                            let partial = {
+                                let mut chunks = chunks.lock().unwrap();
+                                chunks.push((offset, StreamChunk::Transcribed(chunk_result)));
+                                partial_text(&chunks)
+                            };
+                            if has_text {
+                                manager
+                                    .host
+                                    .partial_transcription(TranscriptionPartialEvent {
+                                        binding_id: binding_id.clone(),
+                                        text: partial,
+                                    });
+                            }
+                        }
+                        Err(e) => {
+                            eprintln!(
+                                "Streaming chunk transcription failed, retrying when recording stops: {}",
+                                e
+                            );
+                            chunks
+                                .lock()
+                                .unwrap()
+                                .push((offset, StreamChunk::Failed(chunk)));
+                        }
+                    }
+                }
+            })
+        };
+
+        *self.stream.lock().unwrap() = Some(StreamSession {
+            binding_id: binding_id.to_string(),
+            chunk_tx,
+            worker,
+            chunks,
+            consumed_samples: 0,
+        });
+        println!("Streaming transcription started for binding {}", binding_id);
+    }
+
+    pub fn is_streaming(&self) -> bool {
+        self.stream.lock().unwrap().is_some()
+    }
+
+    /// Queues a VAD-finalized chunk for the active session, if there is one.
+    pub fn push_stream_chunk(&self, chunk: Vec<f32>) {
+        if let Some(session) = self.stream.lock().unwrap().as_mut() {
+            let offset = session.consumed_samples;
+            session.consumed_samples += chunk.len();
+            if session.chunk_tx.send((offset, chunk)).is_err() {
+                eprintln!("Streaming worker is gone, dropping chunk");
+            }
+        }
+    }
+
+    /// Detaches the active session so it can be finished by a queued job while
+    /// the next recording starts its own session.
+    pub fn take_stream(&self) -> Option<StreamSession> {
+        self.stream.lock().unwrap().take()
+    }
+
+    /// Ends a detached session. `samples` is the complete recording; the part
+    /// not already covered by streamed chunks is transcribed here and appended
+    /// to the pre-transcribed text. Chunks that failed while streaming are
+    /// decoded again, so their words aren't lost.
+    fn finish_session(
+        &self,
+        session: StreamSession,
+        samples: Vec<f32>,
+    ) -> Result<TranscriptionResult> {
+        let StreamSession {
+            binding_id,
+            chunk_tx,
+            worker,
+            chunks,
+            consumed_samples,
+        } = session;
+
+        // Let the worker drain the remaining chunks and exit
+        drop(chunk_tx);
+        if worker.join().is_err() {
+            eprintln!("Streaming worker panicked");
+        }
+
+        let chunks = std::mem::take(&mut *chunks.lock().unwrap());
+        let mut result = TranscriptionResult::default();
+        for (offset, chunk) in chunks {
+            let chunk_result = match chunk {
+                StreamChunk::Transcribed(chunk_result) => chunk_result,
+                StreamChunk::Failed(chunk) => {
+                    println!(
+                        "Decoding the chunk at {}ms that failed while streaming",
+                        samples_to_ms(offset)
+                    );
+                    self.transcribe_chunk(chunk, &binding_id)?
+                }
+            };
+            result.append(chunk_result, samples_to_ms(offset));
+        }
+
+        let mut tail = samples[consumed_samples.min(samples.len())..].to_vec();
+        if !tail.is_empty() {
+            // Pad short tails the same way short recordings are padded
+            if tail.len() < WHISPER_SAMPLE_RATE as usize {
+                tail.resize(WHISPER_SAMPLE_RATE as usize * 5 / 4, 0.0);
+            }
+            let tail_result = self.transcribe_chunk(tail, &binding_id)?;
+            result.append(tail_result, samples_to_ms(consumed_samples));
+        }
+
+        println!(
+            "Streaming transcription finished with {} segments",
+            result.segments.len()
+        );
+        Ok(result)
+    }
+
+    /// Drops the active session without waiting for pending chunks.
+    pub fn cancel_stream(&self) {
+        if let Some(session) = self.stream.lock().unwrap().take() {
+            drop(session.chunk_tx);
+            println!("Streaming transcription cancelled");
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::engines::{FakeEngine, FakeLoadFailure, FakeResponse, RemoteEngine};
+    use crate::settings::get_default_settings;
+    use crate::test_server::{StubResponse, StubServer};
+    use crate::text_processing::{ProcessingChain, ProcessorConfig, ProcessorStep};
+
+    /// Host without an app: settings live in memory and UI updates are
+    /// recorded.
+    struct FakeHost {
+        settings: Mutex<AppSettings>,
+        events: Mutex<Vec<(String, serde_json::Value)>>,
+        depths: Mutex<Vec<usize>>,
+        /// Binding and text of each history entry
+        history: Mutex<Vec<(String, String)>>,
+        /// How often the overlay was switched to refining
+        refining_overlays: Mutex<usize>,
+        /// Models that can be loaded
+        models: Vec<String>,
+        /// Remote models by id
+        remote_models: Vec<(String, RemoteModelConfig)>,
+        /// Handed out in order when a model is loaded
+        engines: Mutex<VecDeque<FakeEngine>>,
+    }
+
+    impl FakeHost {
+        fn new() -> Self {
+            let mut settings = get_default_settings();
+            // Keep the installed fake engine instead of loading a model
+            settings.selected_model = String::new();
+            settings.hallucination_filter = false;
+            Self {
+                settings: Mutex::new(settings),
+                events: Mutex::new(Vec::new()),
+                depths: Mutex::new(Vec::new()),
+                history: Mutex::new(Vec::new()),
+                refining_overlays: Mutex::new(0),
+                models: Vec::new(),
+                remote_models: Vec::new(),
+                engines: Mutex::new(VecDeque::new()),
+            }
+        }
+    }
+
+    impl TranscriptionHost for FakeHost {
+        fn settings(&self) -> AppSettings {
+            self.settings.lock().unwrap().clone()
+        }
+
+        fn model_info(&self, model_id: &str) -> Option<ModelInfo> {
+            self.models
+                .iter()
+                .any(|id| id == model_id)
+                .then(|| ModelInfo {
+                    id: model_id.to_string(),
+                    name: model_id.to_string(),
+                    description: String::new(),
+                    filename: format!("{}.bin", model_id),
+                    url: None,
+                    size_mb: 0,
+                    is_downloaded: true,
+                    is_downloading: false,
+                    partial_size: 0,
+                    engine: EngineKind::Whisper,
+                    benchmark: None,
+                })
+        }
+
+        fn model_path(&self, model_id: &str) -> Result<PathBuf> {
+            Ok(PathBuf::from(format!("{}.bin", model_id)))
+        }
+
+        fn remote_model(&self, model_id: &str) -> Option<RemoteModelConfig> {
+            self.remote_models
+                .iter()
+                .find(|(id, _)| id == model_id)
+                .map(|(_, config)| config.clone())
+        }
+
+        fn create_engine(&self, _kind: EngineKind) -> Box<dyn TranscriptionEngine> {
+            let engine = self.engines.lock().unwrap().pop_front();
+            Box::new(engine.expect("no fake engine left to load"))
+        }
+
+        fn emit(&self, event: &str, payload: serde_json::Value) {
+            self.events
+                .lock()
+                .unwrap()
+                .push((event.to_string(), payload));
+        }
+
+        fn queue_depth_changed(&self, depth: usize) {
+            self.depths.lock().unwrap().push(depth);
+        }
+
+        fn partial_transcription(&self, _event: TranscriptionPartialEvent) {}
+
+        fn show_refining_overlay(&self) {
+            *self.refining_overlays.lock().unwrap() += 1;
+        }
+
+        fn add_history_entry(
+            &self,
+            binding_id: &str,
+            _duration_ms: u64,
+            _model_id: Option<String>,
+            _language: String,
+            result: &TranscriptionResult,
+        ) {
+            self.history
+                .lock()
+                .unwrap()
+                .push((binding_id.to_string(), result.text.clone()));
+        }
+    }
+
+    fn manager_with(
+        responses: Vec<FakeResponse>,
+    ) -> (Arc<TranscriptionManager>, Arc<FakeHost>, FakeEngine) {
+        let host = Arc::new(FakeHost::new());
+        let manager = Arc::new(TranscriptionManager::with_host(host.clone()).unwrap());
+        let engine = FakeEngine::new(responses);
+        manager.set_engine("fake", Box::new(engine.clone()));
+        manager.start_job_worker();
+        (manager, host, engine)
+    }
+
+    /// Queues a second of silence, the result arrives on the returned channel.
+    fn enqueue(manager: &TranscriptionManager) -> mpsc::Receiver<Result<TranscriptionResult>> {
+        enqueue_from(manager, JobSource::Dictation)
+    }
+
+    fn enqueue_from(
+        manager: &TranscriptionManager,
+        source: JobSource,
+    ) -> mpsc::Receiver<Result<TranscriptionResult>> {
+        let (tx, rx) = mpsc::channel();
+        manager.enqueue(
+            "transcribe",
+            source,
+            vec![0.0; WHISPER_SAMPLE_RATE as usize],
+            None,
+            Box::new(move |result| {
+                let _ = tx.send(result);
+            }),
+        );
+        rx
+    }
+
+    fn wait(rx: &mpsc::Receiver<Result<TranscriptionResult>>) -> Result<TranscriptionResult> {
+        rx.recv_timeout(Duration::from_secs(5))
+            .expect("job did not complete")
+    }
+
+    fn is_cancelled(result: &Result<TranscriptionResult>) -> bool {
+        matches!(
+            result
+                .as_ref()
+                .err()
+                .and_then(|e| e.downcast_ref::<TranscriptionInterrupted>()),
+            Some(TranscriptionInterrupted::Cancelled)
+        )
+    }
+
+    #[test]
+    fn jobs_complete_in_queue_order() {
+        let (manager, host, engine) = manager_with(vec![
+            FakeResponse::Text("first".to_string()),
+            FakeResponse::Text("second".to_string()),
+            FakeResponse::Text("third".to_string()),
+        ]);
+
+        let receivers: Vec<_> = (0..3).map(|_| enqueue(&manager)).collect();
+        let texts: Vec<String> = receivers.iter().map(|rx| wait(rx).unwrap().text).collect();
+
+        assert_eq!(texts, ["first", "second", "third"]);
+        assert_eq!(engine.calls().len(), 3);
+        assert_eq!(manager.queue_depth(), 0);
+        assert_eq!(host.depths.lock().unwrap().last(), Some(&0));
+    }
+
+    #[test]
+    fn failed_job_does_not_stop_the_queue() {
+        let (manager, _host, _engine) = manager_with(vec![
+            FakeResponse::Error("decoder exploded".to_string()),
+            FakeResponse::Text("still here".to_string()),
+        ]);
+
+        let failed = enqueue(&manager);
+        let next = enqueue(&manager);
+
+        let error = wait(&failed).unwrap_err();
+        assert!(error.to_string().contains("decoder exploded"));
+        assert_eq!(wait(&next).unwrap().text, "still here");
+    }
+
+    #[test]
+    fn cancel_drops_running_and_queued_jobs() {
+        let (manager, _host, engine) = manager_with(vec![
+            FakeResponse::Hang,
+            FakeResponse::Text("after cancel".to_string()),
+        ]);
+
+        let running = enqueue(&manager);
+        let queued = enqueue(&manager);
+        engine.wait_for_calls(1);
+        manager.cancel_transcription();
+
+        assert!(is_cancelled(&wait(&running)));
+        assert!(is_cancelled(&wait(&queued)));
+        // The queued job never reached the engine
+        assert_eq!(engine.calls().len(), 1);
+
+        // Jobs queued after the cancel run normally
+        let later = enqueue(&manager);
+        assert_eq!(wait(&later).unwrap().text, "after cancel");
+        assert_eq!(manager.queue_depth(), 0);
+    }
+
+    #[test]
+    fn decode_timeout_is_reported() {
+        let (manager, host, _engine) = manager_with(vec![FakeResponse::Hang]);
+        host.settings.lock().unwrap().max_decode_seconds = 1;
+
+        let error = wait(&enqueue(&manager)).unwrap_err();
+        assert!(matches!(
+            error.downcast_ref::<TranscriptionInterrupted>(),
+            Some(TranscriptionInterrupted::TimedOut(_))
+        ));
+    }
+
+    /// Selects a processing chain that only fixes "fryday".
+    fn select_fixes_chain(host: &FakeHost) {
+        let mut settings = host.settings.lock().unwrap();
+        settings.processing_chains.insert(
+            "fixes".to_string(),
+            ProcessingChain {
+                id: "fixes".to_string(),
+                name: "Fixes".to_string(),
+                steps: vec![ProcessorStep {
+                    enabled: true,
+                    config: ProcessorConfig::Replace {
+                        find: "fryday".to_string(),
+                        replace: "Friday".to_string(),
+                        case_sensitive: false,
+                        whole_word: true,
+                    },
+                }],
+            },
+        );
+        settings.selected_processing_chain = "fixes".to_string();
+    }
+
+    #[test]
+    fn selected_chain_post_processes_the_text() {
+        let (manager, host, _engine) =
+            manager_with(vec![FakeResponse::Text("ship it on fryday".to_string())]);
+        select_fixes_chain(&host);
+
+        let result = wait(&enqueue(&manager)).unwrap();
+        assert_eq!(result.text, "ship it on Friday");
+        // Segments keep what the decoder produced
+        assert_eq!(result.segments[0].text, "ship it on fryday");
+    }
+
+    #[test]
+    fn file_jobs_are_queued_and_post_process_every_segment() {
+        let (manager, host, engine) = manager_with(vec![
+            FakeResponse::Text("see you fryday".to_string()),
+            FakeResponse::Text("or the fryday after".to_string()),
+            FakeResponse::Text("a recording".to_string()),
+        ]);
+        select_fixes_chain(&host);
+
+        // Forty seconds are decoded in two chunks
+        let (tx, rx) = mpsc::channel();
+        let progress = Arc::new(Mutex::new(Vec::new()));
+        let progress_log = progress.clone();
+        manager.enqueue_file(
+            None,
+            vec![0.0; WHISPER_SAMPLE_RATE as usize * 40],
+            Box::new(move |processed, total| progress_log.lock().unwrap().push((processed, total))),
+            Box::new(move |result| {
+                let _ = tx.send(result);
+            }),
+        );
+        let recording = enqueue(&manager);
+
+        let result = wait(&rx).unwrap();
+        assert_eq!(result.text, "see you Friday or the Friday after");
+        let segments: Vec<&str> = result.segments.iter().map(|s| s.text.as_str()).collect();
+        assert_eq!(segments, ["see you Friday", "or the Friday after"]);
+        let total = WHISPER_SAMPLE_RATE as usize * 40;
+        assert_eq!(progress.lock().unwrap().last(), Some(&(total, total)));
+
+        // The recording queued behind the file waits for it
+        assert_eq!(wait(&recording).unwrap().text, "a recording");
+        assert_eq!(engine.calls().len(), 3);
+        // Only the recording is kept in history
+        assert_eq!(
+            *host.history.lock().unwrap(),
+            [("transcribe".to_string(), "a recording".to_string())]
+        );
+    }
+
+    #[test]
+    fn idle_watcher_leaves_a_decoding_model_alone() {
+        let (manager, host, engine) = manager_with(vec![FakeResponse::Hang]);
+        host.settings.lock().unwrap().model_unload_timeout_minutes = 1;
+        let long_ago = Instant::now().checked_sub(Duration::from_secs(3600));
+        let mut system = sysinfo::System::new();
+
+        let running = enqueue(&manager);
+        engine.wait_for_calls(1);
+        *manager.last_activity.lock().unwrap() = long_ago.unwrap();
+        manager.check_idle_unload(&mut system);
+
+        manager.cancel_transcription();
+        assert!(is_cancelled(&wait(&running)));
+        assert!(manager.is_model_loaded());
+
+        // Once the queue is empty the idle model goes
+        *manager.last_activity.lock().unwrap() = long_ago.unwrap();
+        manager.check_idle_unload(&mut system);
+        assert!(!manager.is_model_loaded());
+    }
+
+    #[test]
+    fn recent_transcriptions_prime_the_next_prompt() {
+        let (manager, host, engine) = manager_with(vec![
+            FakeResponse::Text("Meet Anneliese at noon.".to_string()),
+            FakeResponse::Text("Sounds good.".to_string()),
+        ]);
+        {
+            let mut settings = host.settings.lock().unwrap();
+            settings.prompt_context_length = 1;
+            settings.initial_prompt_template = "{context}".to_string();
+        }
+
+        wait(&enqueue(&manager)).unwrap();
+        wait(&enqueue(&manager)).unwrap();
+
+        let calls = engine.calls();
+        assert_eq!(calls[0].options.initial_prompt, None);
+        assert_eq!(
+            calls[1].options.initial_prompt.as_deref(),
+            Some("Meet Anneliese at noon.")
+        );
+    }
+
+    #[test]
+    fn finished_jobs_are_kept_in_history() {
+        let (manager, host, _engine) = manager_with(vec![
+            FakeResponse::Text("keep me".to_string()),
+            FakeResponse::Text(String::new()),
+            FakeResponse::Error("no luck".to_string()),
+        ]);
+
+        for _ in 0..3 {
+            let _ = wait(&enqueue(&manager));
+        }
+
+        // Empty and failed transcriptions leave no entry
+        assert_eq!(
+            *host.history.lock().unwrap(),
+            [("transcribe".to_string(), "keep me".to_string())]
+        );
+    }
+
+    #[test]
+    fn failed_stream_chunks_are_decoded_again() {
+        let (manager, _host, engine) = manager_with(vec![
+            FakeResponse::Error("chunk failed".to_string()),
+            FakeResponse::Text("jumps over".to_string()),
+            FakeResponse::Text("the quick fox".to_string()),
+            FakeResponse::Text("the lazy dog".to_string()),
+        ]);
+        let second = WHISPER_SAMPLE_RATE as usize;
+
+        manager.start_stream("transcribe");
+        manager.push_stream_chunk(vec![0.0; second]);
+        manager.push_stream_chunk(vec![0.0; second]);
+        engine.wait_for_calls(2);
+        let stream = manager.take_stream();
+
+        let (tx, rx) = mpsc::channel();
+        manager.enqueue(
+            "transcribe",
+            JobSource::Dictation,
+            vec![0.0; second * 3],
+            stream,
+            Box::new(move |result| {
+                let _ = tx.send(result);
+            }),
+        );
+        let result = wait(&rx).unwrap();
+
+        // The failed first chunk keeps its place before the second one
+        assert_eq!(result.text, "the quick fox jumps over the lazy dog");
+        let starts: Vec<i64> = result.segments.iter().map(|s| s.start_ms).collect();
+        assert_eq!(starts, [0, 1000, 2000]);
+    }
+
+    #[test]
+    fn stream_chunks_skip_the_no_speech_estimate() {
+        let (manager, host, engine) = manager_with(vec![
+            FakeResponse::Text("whole".to_string()),
+            FakeResponse::Text("chunk".to_string()),
+            FakeResponse::Text("tail".to_string()),
+        ]);
+        host.settings.lock().unwrap().hallucination_filter = true;
+        let second = WHISPER_SAMPLE_RATE as usize;
+        wait(&enqueue(&manager)).unwrap();
+
+        manager.start_stream("transcribe");
+        manager.push_stream_chunk(vec![0.0; second]);
+        engine.wait_for_calls(2);
+        let stream = manager.take_stream();
+
+        let (tx, rx) = mpsc::channel();
+        manager.enqueue(
+            "transcribe",
+            JobSource::Dictation,
+            vec![0.0; second * 2],
+            stream,
+            Box::new(move |result| {
+                let _ = tx.send(result);
+            }),
+        );
+        wait(&rx).unwrap();
+
+        // Only the whole dictation pays for the extra encoder pass
+        let estimated: Vec<bool> = engine
+            .calls()
+            .iter()
+            .map(|call| call.options.no_speech_probability)
+            .collect();
+        assert_eq!(estimated, [true, false, false]);
+    }
+
+    /// A host with the model "base" and `engines` to load it with.
+    fn host_with_engines(engines: &[FakeEngine]) -> FakeHost {
+        let mut host = FakeHost::new();
+        host.models = vec!["base".to_string()];
+        host.engines = Mutex::new(engines.iter().cloned().collect());
+        host
+    }
+
+    #[test]
+    fn cpu_only_mode_never_tries_the_gpu() {
+        let engine = FakeEngine::default();
+        let host = host_with_engines(&[engine.clone()]);
+        host.settings.lock().unwrap().force_cpu = true;
+        let manager = TranscriptionManager::with_host(Arc::new(host)).unwrap();
+
+        manager.load_model("base").unwrap();
+
+        assert!(engine.loads().iter().all(|options| !options.use_gpu));
+        assert_eq!(engine.loads().len(), 1);
+        let status = manager.get_model_status().unwrap();
+        assert_eq!(status.backend, Some(ComputeBackend::Cpu));
+    }
+
+    #[test]
+    fn gpu_failure_is_remembered_for_later_loads() {
+        let first = FakeEngine::default();
+        first.fail_loads(vec![FakeLoadFailure::Panic]);
+        let second = FakeEngine::default();
+        let host = host_with_engines(&[first.clone(), second.clone()]);
+        let manager = TranscriptionManager::with_host(Arc::new(host)).unwrap();
+
+        manager.load_model("base").unwrap();
+        assert_eq!(
+            first.loads().iter().map(|o| o.use_gpu).collect::<Vec<_>>(),
+            [true, false]
+        );
+
+        // The next load goes straight to the CPU
+        manager.load_model("base").unwrap();
+        assert_eq!(
+            second.loads().iter().map(|o| o.use_gpu).collect::<Vec<_>>(),
+            [false]
+        );
+        let status = manager.get_model_status().unwrap();
+        assert_eq!(status.backend, Some(ComputeBackend::Cpu));
+    }
+
+    #[test]
+    fn uploads_use_the_requested_language_and_prompt() {
+        let (manager, _host, engine) = manager_with(vec![
+            FakeResponse::Text("hallo".to_string()),
+            FakeResponse::Text("hello".to_string()),
+        ]);
+
+        let (tx, rx) = mpsc::channel();
+        manager.enqueue_upload(
+            "transcribe",
+            vec![0.0; WHISPER_SAMPLE_RATE as usize],
+            JobOverrides {
+                language: Some("de".to_string()),
+                prompt: Some("Handy, Tauri".to_string()),
+            },
+            Box::new(move |result| {
+                let _ = tx.send(result);
+            }),
+        );
+        wait(&rx).unwrap();
+        wait(&enqueue_from(&manager, JobSource::Api)).unwrap();
+
+        let calls = engine.calls();
+        assert_eq!(calls[0].options.language, "de");
+        assert_eq!(
+            calls[0].options.initial_prompt.as_deref(),
+            Some("Handy, Tauri")
+        );
+        // Without overrides the binding's settings apply
+        assert_eq!(
+            calls[1].options.language,
+            get_default_settings().selected_language
+        );
+    }
+
+    #[test]
+    fn profile_model_is_loaded_next_to_the_global_one() {
+        let global = FakeEngine::new(vec![
+            FakeResponse::Text("global one".to_string()),
+            FakeResponse::Text("global two".to_string()),
+        ]);
+        let profile = FakeEngine::new(vec![FakeResponse::Text("profile".to_string())]);
+
+        let mut host = FakeHost::new();
+        host.models = vec!["base".to_string(), "tiny".to_string()];
+        host.engines = Mutex::new(VecDeque::from([global.clone(), profile.clone()]));
+        {
+            let settings = host.settings.get_mut().unwrap();
+            settings.selected_model = "base".to_string();
+            let mut binding = settings.bindings["transcribe"].clone();
+            binding.id = "fast".to_string();
+            binding.profile_id = Some("fast".to_string());
+            settings.bindings.insert("fast".to_string(), binding);
+            settings.profiles.insert(
+                "fast".to_string(),
+                serde_json::from_value(serde_json::json!({
+                    "id": "fast",
+                    "name": "Fast",
+                    "selected_model": "tiny",
+                }))
+                .unwrap(),
+            );
+        }
+        let host = Arc::new(host);
+        let manager = Arc::new(TranscriptionManager::with_host(host.clone()).unwrap());
+        manager.start_job_worker();
+
+        let texts: Vec<String> = ["transcribe", "fast", "transcribe"]
+            .iter()
+            .map(|binding_id| {
+                let (tx, rx) = mpsc::channel();
+                manager.enqueue(
+                    binding_id,
+                    JobSource::Dictation,
+                    vec![0.0; WHISPER_SAMPLE_RATE as usize],
+                    None,
+                    Box::new(move |result| {
+                        let _ = tx.send(result);
+                    }),
+                );
+                wait(&rx).unwrap().text
+            })
+            .collect();
+
+        assert_eq!(texts, ["global one", "profile", "global two"]);
+        // Neither model was reloaded or dropped for the other
+        assert!(host.engines.lock().unwrap().is_empty());
+        assert!(global.is_loaded() && profile.is_loaded());
+        assert_eq!(manager.get_current_model().as_deref(), Some("base"));
+    }
+
+    /// Selects the clean up prompt with the model served at `endpoint`.
+    fn enable_refinement(host: &FakeHost, endpoint: String) {
+        let mut settings = host.settings.lock().unwrap();
+        settings.selected_refinement_prompt = Some("clean_up".to_string());
+        settings.llm.endpoint = endpoint;
+        settings.llm.timeout_seconds = 1;
+    }
+
+    fn refinement_failures(host: &FakeHost) -> Vec<serde_json::Value> {
+        host.events
+            .lock()
+            .unwrap()
+            .iter()
+            .filter(|(event, _)| event == "refinement-failed")
+            .map(|(_, payload)| payload.clone())
+            .collect()
+    }
+
+    #[test]
+    fn dictations_are_refined() {
+        let server = StubServer::start(vec![StubResponse::json(
+            200,
+            serde_json::json!({"choices": [{"message": {"content": "Raw words."}}]}),
+        )]);
+        let (manager, host, _engine) =
+            manager_with(vec![FakeResponse::Text("raw words".to_string())]);
+        enable_refinement(&host, server.url.clone());
+
+        let result = wait(&enqueue(&manager)).unwrap();
+
+        assert_eq!(result.text, "Raw words.");
+        assert_eq!(server.requests().len(), 1);
+        assert_eq!(*host.refining_overlays.lock().unwrap(), 1);
+        assert!(refinement_failures(&host).is_empty());
+    }
+
+    #[test]
+    fn failed_refinement_keeps_the_raw_text() {
+        let server = StubServer::start(vec![StubResponse::json(
+            500,
+            serde_json::json!({"error": "out of memory"}),
+        )]);
+        let (manager, host, _engine) =
+            manager_with(vec![FakeResponse::Text("raw words".to_string())]);
+        enable_refinement(&host, server.url.clone());
+
+        let result = wait(&enqueue_from(&manager, JobSource::Api)).unwrap();
+
+        assert_eq!(result.text, "raw words");
+        assert_eq!(server.requests().len(), 1);
+        let failures = refinement_failures(&host);
+        assert_eq!(failures.len(), 1);
+        assert!(failures[0].as_str().unwrap().contains("500"));
+        // Nothing would take the overlay back from refining after an API job
+        assert_eq!(*host.refining_overlays.lock().unwrap(), 0);
+    }
+
+    #[test]
+    fn invalid_model_settings_skip_refinement() {
+        let server = StubServer::start(Vec::new());
+        let (manager, host, _engine) =
+            manager_with(vec![FakeResponse::Text("raw words".to_string())]);
+        enable_refinement(&host, server.url.replace("http://", "ftp://"));
+
+        let result = wait(&enqueue(&manager)).unwrap();
+
+        assert_eq!(result.text, "raw words");
+        assert!(server.requests().is_empty());
+        let failures = refinement_failures(&host);
+        assert_eq!(failures.len(), 1);
+        assert!(failures[0]
+            .as_str()
+            .unwrap()
+            .starts_with("Refinement model settings are invalid"));
+        assert_eq!(*host.refining_overlays.lock().unwrap(), 0);
+    }
+
+    #[test]
+    fn unreachable_server_falls_back_to_the_local_model() {
+        let server = StubServer::start(vec![StubResponse::json(
+            500,
+            serde_json::json!({"error": "CUDA out of memory"}),
+        )]);
+        let config = RemoteModelConfig {
+            name: "Server".to_string(),
+            endpoint: server.url.clone(),
+            model: "whisper-1".to_string(),
+            api_key: None,
+            timeout_seconds: 5,
+            retries: 0,
+            fallback_model: Some("base".to_string()),
+        };
+        let local = FakeEngine::new(vec![FakeResponse::Text("decoded locally".to_string())]);
+        let mut host = FakeHost::new();
+        host.models = vec!["base".to_string()];
+        host.remote_models = vec![("server".to_string(), config.clone())];
+        host.engines.lock().unwrap().push_back(local.clone());
+        let host = Arc::new(host);
+        let manager = Arc::new(TranscriptionManager::with_host(host.clone()).unwrap());
+        manager.set_engine("server", Box::new(RemoteEngine::with_config(config)));
+        manager.start_job_worker();
+
+        let result = wait(&enqueue(&manager)).unwrap();
+
+        assert_eq!(result.text, "decoded locally");
+        assert_eq!(server.requests().len(), 1);
+        assert_eq!(local.calls().len(), 1);
+        let events = host.events.lock().unwrap();
+        let (_, error) = events
+            .iter()
+            .find(|(event, _)| event == "remote-transcription-failed")
+            .expect("fallback was not reported");
+        assert!(error.as_str().unwrap().contains("500"));
+    }
+
+    #[test]
+    fn api_jobs_leave_prompt_context_and_overlay_alone() {
+        let (manager, host, engine) = manager_with(vec![
+            FakeResponse::Text("Uploaded meeting notes.".to_string()),
+            FakeResponse::Text("Dictated.".to_string()),
+        ]);
+        {
+            let mut settings = host.settings.lock().unwrap();
+            settings.prompt_context_length = 1;
+            settings.initial_prompt_template = "{context}".to_string();
+        }
+
+        wait(&enqueue_from(&manager, JobSource::Api)).unwrap();
+        assert!(host.depths.lock().unwrap().is_empty());
+        assert_eq!(manager.pending_dictations(), 0);
+
+        wait(&enqueue(&manager)).unwrap();
+        let calls = engine.calls();
+        assert_eq!(calls[1].options.initial_prompt, None);
+        assert_eq!(*host.depths.lock().unwrap(), [1, 0]);
     }
 }
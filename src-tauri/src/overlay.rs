@@ -1,3 +1,4 @@
+use crate::managers::transcription::{TranscriptionPartialEvent, TranscriptionQueueEvent};
 use crate::settings;
 use crate::settings::OverlayPosition;
 use log::debug;
@@ -109,6 +110,35 @@ pub fn show_transcribing_overlay(app_handle: &AppHandle) {
     }
 }
 
+/// Shows the refining overlay while a language model rewrites a transcript
+pub fn show_refining_overlay(app_handle: &AppHandle) {
+    // Check if overlay should be shown based on position setting
+    let settings = settings::get_settings(app_handle);
+    if settings.overlay_position == OverlayPosition::None {
+        return;
+    }
+
+    if let Some(overlay_window) = app_handle.get_webview_window("recording_overlay") {
+        let _ = overlay_window.show();
+        let _ = overlay_window.emit("show-overlay", "refining");
+    }
+}
+
+/// Shows the language the next recording will use, e.g. after it was switched
+/// with the cycle language shortcut
+pub fn show_language_overlay(app_handle: &AppHandle, language: &str) {
+    // Check if overlay should be shown based on position setting
+    let settings = settings::get_settings(app_handle);
+    if settings.overlay_position == OverlayPosition::None {
+        return;
+    }
+
+    if let Some(overlay_window) = app_handle.get_webview_window("recording_overlay") {
+        let _ = overlay_window.show();
+        let _ = overlay_window.emit("language-changed", language);
+    }
+}
+
 /// Updates the overlay window position based on current settings
 pub fn update_overlay_position(app_handle: &AppHandle) {
     if let Some(overlay_window) = app_handle.get_webview_window("recording_overlay") {
@@ -144,3 +174,23 @@ pub fn emit_levels(app_handle: &AppHandle, levels: &Vec<f32>) {
         let _ = overlay_window.emit("mic-level", levels);
     }
 }
+
+pub fn emit_partial_transcription(app_handle: &AppHandle, event: TranscriptionPartialEvent) {
+    // emit partial text to main app
+    let _ = app_handle.emit("transcription-partial", &event);
+
+    // also emit to the recording overlay if it's open
+    if let Some(overlay_window) = app_handle.get_webview_window("recording_overlay") {
+        let _ = overlay_window.emit("transcription-partial", &event);
+    }
+}
+
+pub fn emit_queue_depth(app_handle: &AppHandle, event: TranscriptionQueueEvent) {
+    // emit queue depth to main app
+    let _ = app_handle.emit("transcription-queue-changed", &event);
+
+    // also emit to the recording overlay if it's open
+    if let Some(overlay_window) = app_handle.get_webview_window("recording_overlay") {
+        let _ = overlay_window.emit("transcription-queue-changed", &event);
+    }
+}
@@ -1,3 +1,6 @@
+use crate::engines::DecoderSettings;
+use crate::llm_refinement::{self, LlmSettings, RefinementPrompt};
+use crate::text_processing::{self, ProcessingChain, SpokenCommand};
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use tauri::{App, AppHandle};
@@ -10,6 +13,44 @@ pub struct ShortcutBinding {
     pub description: String,
     pub default_binding: String,
     pub current_binding: String,
+    /// Action to run, defaults to the action named like the binding id
+    #[serde(default)]
+    pub action: Option<String>,
+    /// Transcription profile whose values override the global settings
+    #[serde(default)]
+    pub profile_id: Option<String>,
+}
+
+impl ShortcutBinding {
+    pub fn action_id(&self) -> &str {
+        self.action.as_deref().unwrap_or(&self.id)
+    }
+}
+
+/// A named set of overrides for the global transcription settings. Unset
+/// fields fall back to the global value.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct TranscriptionProfile {
+    pub id: String,
+    pub name: String,
+    /// Loaded next to the global model rather than in its place, so while the
+    /// profile is in use both models take up memory. Idle unloading frees both.
+    #[serde(default)]
+    pub selected_model: Option<String>,
+    #[serde(default)]
+    pub selected_language: Option<String>,
+    #[serde(default)]
+    pub translate_to_english: Option<bool>,
+    #[serde(default)]
+    pub push_to_talk: Option<bool>,
+    #[serde(default)]
+    pub processing_chain: Option<String>,
+    /// Replaces the decoder settings of the profile's model
+    #[serde(default)]
+    pub decoder: Option<DecoderSettings>,
+    /// Refinement prompt to use, an empty id turns refinement off
+    #[serde(default)]
+    pub refinement_prompt: Option<String>,
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
@@ -38,14 +79,86 @@ pub struct AppSettings {
     pub translate_to_english: bool,
     #[serde(default = "default_selected_language")]
     pub selected_language: String,
+    /// Languages auto-detection chooses from, every language when empty
+    #[serde(default)]
+    pub auto_detect_languages: Vec<String>,
+    /// Languages the cycle language shortcut switches between
+    #[serde(default)]
+    pub favorite_languages: Vec<String>,
     #[serde(default = "default_overlay_position")]
     pub overlay_position: OverlayPosition,
     #[serde(default = "default_debug_mode")]
     pub debug_mode: bool,
     #[serde(default)]
     pub custom_words: Vec<String>,
+    /// Extra custom words per base language code, on top of `custom_words`
+    #[serde(default)]
+    pub language_custom_words: HashMap<String, Vec<String>>,
+    /// Spellings that are always replaced by a custom word, keyed by the word
+    #[serde(default)]
+    pub custom_word_aliases: HashMap<String, Vec<String>>,
     #[serde(default = "default_word_correction_threshold")]
     pub word_correction_threshold: f64,
+    #[serde(default)]
+    pub streaming_transcription: bool,
+    #[serde(default = "default_initial_prompt_template")]
+    pub initial_prompt_template: String,
+    #[serde(default)]
+    pub prompt_context_length: usize,
+    #[serde(default)]
+    pub profiles: HashMap<String, TranscriptionProfile>,
+    /// Unload the model after this many minutes without use, 0 keeps it loaded
+    #[serde(default)]
+    pub model_unload_timeout_minutes: u32,
+    /// Unload the model when available system memory drops below this, 0 disables
+    #[serde(default)]
+    pub low_memory_unload_threshold_mb: u64,
+    /// Abort a decode that runs longer than this, 0 disables the limit
+    #[serde(default = "default_max_decode_seconds")]
+    pub max_decode_seconds: u32,
+    /// Post-processing chains by id
+    #[serde(default = "default_processing_chains")]
+    pub processing_chains: HashMap<String, ProcessingChain>,
+    #[serde(default = "default_selected_processing_chain")]
+    pub selected_processing_chain: String,
+    /// User spoken formatting commands, checked before the built-in ones
+    #[serde(default)]
+    pub spoken_commands: Vec<SpokenCommand>,
+    /// Keep spoken commands such as "comma" as words
+    #[serde(default)]
+    pub literal_mode: bool,
+    /// Extra filler words per base language code, on top of the built-in
+    /// hesitation sounds. Phrases such as "you know" only go when added here.
+    #[serde(default)]
+    pub filler_words: HashMap<String, Vec<String>>,
+    /// Drop segments that look like whisper hallucinations
+    #[serde(default = "default_hallucination_filter")]
+    pub hallucination_filter: bool,
+    /// Extra phrases dropped as hallucinations, on top of the built-in ones
+    #[serde(default)]
+    pub hallucination_blocklist: Vec<String>,
+    /// Decoder settings by model id, models without an entry use the defaults
+    #[serde(default)]
+    pub model_decoder_settings: HashMap<String, DecoderSettings>,
+    /// Never load models on the GPU, whatever their decoder settings say
+    #[serde(default)]
+    pub force_cpu: bool,
+    #[serde(default)]
+    pub llm: LlmSettings,
+    /// Refinement prompts by id
+    #[serde(default = "default_refinement_prompts")]
+    pub refinement_prompts: HashMap<String, RefinementPrompt>,
+    /// Prompt transcripts are refined with, refinement is off when unset
+    #[serde(default)]
+    pub selected_refinement_prompt: Option<String>,
+    /// Serve the local transcription API on 127.0.0.1
+    #[serde(default)]
+    pub api_server_enabled: bool,
+    #[serde(default = "default_api_server_port")]
+    pub api_server_port: u16,
+    /// Bearer token API clients must send, generated when the server is first enabled
+    #[serde(default)]
+    pub api_server_token: String,
 }
 
 fn default_model() -> String {
@@ -76,6 +189,36 @@ fn default_word_correction_threshold() -> f64 {
     0.18
 }
 
+fn default_hallucination_filter() -> bool {
+    true
+}
+
+fn default_max_decode_seconds() -> u32 {
+    300
+}
+
+fn default_processing_chains() -> HashMap<String, ProcessingChain> {
+    let chain = text_processing::default_chain();
+    HashMap::from([(chain.id.clone(), chain)])
+}
+
+fn default_selected_processing_chain() -> String {
+    text_processing::DEFAULT_CHAIN_ID.to_string()
+}
+
+fn default_refinement_prompts() -> HashMap<String, RefinementPrompt> {
+    llm_refinement::default_refinement_prompts()
+}
+
+fn default_api_server_port() -> u16 {
+    5557
+}
+
+/// `{words}` expands to the custom words, `{context}` to recent transcriptions.
+fn default_initial_prompt_template() -> String {
+    "{context} {words}".to_string()
+}
+
 pub const SETTINGS_STORE_PATH: &str = "settings_store.json";
 
 pub fn get_default_settings() -> AppSettings {
@@ -97,6 +240,8 @@ pub fn get_default_settings() -> AppSettings {
             description: "Converts your speech into text.".to_string(),
             default_binding: default_shortcut.to_string(),
             current_binding: default_shortcut.to_string(),
+            action: None,
+            profile_id: None,
         },
     );
 
@@ -110,10 +255,36 @@ pub fn get_default_settings() -> AppSettings {
         selected_output_device: None,
         translate_to_english: false,
         selected_language: "auto".to_string(),
+        auto_detect_languages: Vec::new(),
+        favorite_languages: Vec::new(),
         overlay_position: OverlayPosition::Bottom,
         debug_mode: false,
         custom_words: Vec::new(),
+        language_custom_words: HashMap::new(),
+        custom_word_aliases: HashMap::new(),
         word_correction_threshold: default_word_correction_threshold(),
+        streaming_transcription: false,
+        initial_prompt_template: default_initial_prompt_template(),
+        prompt_context_length: 0,
+        profiles: HashMap::new(),
+        model_unload_timeout_minutes: 0,
+        low_memory_unload_threshold_mb: 0,
+        max_decode_seconds: default_max_decode_seconds(),
+        processing_chains: default_processing_chains(),
+        selected_processing_chain: default_selected_processing_chain(),
+        spoken_commands: Vec::new(),
+        literal_mode: false,
+        filler_words: HashMap::new(),
+        hallucination_filter: default_hallucination_filter(),
+        hallucination_blocklist: Vec::new(),
+        model_decoder_settings: HashMap::new(),
+        force_cpu: false,
+        llm: LlmSettings::default(),
+        refinement_prompts: default_refinement_prompts(),
+        selected_refinement_prompt: None,
+        api_server_enabled: false,
+        api_server_port: default_api_server_port(),
+        api_server_token: String::new(),
     }
 }
 
@@ -189,3 +360,97 @@ pub fn get_stored_binding(app: &AppHandle, id: &str) -> ShortcutBinding {
 
     binding
 }
+
+/// Applies the profile of the given binding on top of the global settings.
+/// Without a binding, or when the binding has no profile, the global settings
+/// are returned unchanged.
+pub fn resolve_binding_settings(
+    mut settings: AppSettings,
+    binding_id: Option<&str>,
+) -> AppSettings {
+    let profile = binding_id
+        .and_then(|id| settings.bindings.get(id))
+        .and_then(|binding| binding.profile_id.as_ref())
+        .and_then(|profile_id| settings.profiles.get(profile_id))
+        .cloned();
+
+    if let Some(profile) = profile {
+        if let Some(model) = profile.selected_model {
+            settings.selected_model = model;
+        }
+        if let Some(language) = profile.selected_language {
+            settings.selected_language = language;
+        }
+        if let Some(translate) = profile.translate_to_english {
+            settings.translate_to_english = translate;
+        }
+        if let Some(push_to_talk) = profile.push_to_talk {
+            settings.push_to_talk = push_to_talk;
+        }
+        if let Some(chain) = profile.processing_chain {
+            settings.selected_processing_chain = chain;
+        }
+        if let Some(prompt) = profile.refinement_prompt {
+            settings.selected_refinement_prompt = Some(prompt).filter(|p| !p.is_empty());
+        }
+        if let Some(decoder) = profile.decoder {
+            settings
+                .model_decoder_settings
+                .insert(settings.selected_model.clone(), decoder);
+        }
+    }
+
+    settings
+}
+
+/// Decoder settings configured for `model_id`, or the defaults.
+pub fn decoder_settings_for_model(settings: &AppSettings, model_id: &str) -> DecoderSettings {
+    settings
+        .model_decoder_settings
+        .get(model_id)
+        .copied()
+        .unwrap_or_default()
+}
+
+/// Stores the decoder settings for `model_id`. Invalid settings are rejected
+/// and leave `settings` unchanged.
+pub fn set_decoder_settings_for_model(
+    settings: &mut AppSettings,
+    model_id: &str,
+    decoder: DecoderSettings,
+) -> anyhow::Result<()> {
+    decoder.validate()?;
+    settings
+        .model_decoder_settings
+        .insert(model_id.to_string(), decoder);
+    Ok(())
+}
+
+pub fn get_settings_for_binding(app: &AppHandle, binding_id: Option<&str>) -> AppSettings {
+    resolve_binding_settings(get_settings(app), binding_id)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn invalid_decoder_settings_are_not_saved() {
+        let mut settings = get_default_settings();
+        let decoder = DecoderSettings {
+            beam_size: 8,
+            ..Default::default()
+        };
+        set_decoder_settings_for_model(&mut settings, "base", decoder).unwrap();
+        assert_eq!(decoder_settings_for_model(&settings, "base"), decoder);
+
+        let invalid = DecoderSettings {
+            temperature: 1.5,
+            ..Default::default()
+        };
+        assert!(set_decoder_settings_for_model(&mut settings, "base", invalid).is_err());
+        assert!(set_decoder_settings_for_model(&mut settings, "tiny", invalid).is_err());
+        assert_eq!(decoder_settings_for_model(&settings, "base"), decoder);
+        assert!(!settings.model_decoder_settings.contains_key("tiny"));
+    }
+}
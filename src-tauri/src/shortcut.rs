@@ -1,11 +1,18 @@
 use serde::Serialize;
+use std::sync::Arc;
 use tauri::{App, AppHandle, Manager};
 use tauri_plugin_global_shortcut::GlobalShortcutExt;
 use tauri_plugin_global_shortcut::{Shortcut, ShortcutState};
 
 use crate::actions::ACTION_MAP;
-use crate::settings::ShortcutBinding;
-use crate::settings::{self, get_settings, OverlayPosition};
+use crate::engines::DecoderSettings;
+use crate::hallucination_filter;
+use crate::llm_refinement::{LlmSettings, RefinementPrompt};
+use crate::managers::api_server::{self, ApiServerManager, ApiServerStatus};
+use crate::managers::transcription::TranscriptionManager;
+use crate::settings::{self, get_settings_for_binding, OverlayPosition};
+use crate::settings::{ShortcutBinding, TranscriptionProfile};
+use crate::text_processing::{self, ProcessingChain, SpokenCommand};
 use crate::ManagedToggleState;
 
 pub fn init_shortcuts(app: &App) {
@@ -134,6 +141,44 @@ pub fn change_selected_language_setting(app: AppHandle, language: String) -> Res
     Ok(())
 }
 
+/// Trims, lowercases and dedups a list of language codes.
+fn clean_language_list(languages: Vec<String>) -> Vec<String> {
+    let mut cleaned: Vec<String> = Vec::with_capacity(languages.len());
+    for language in languages {
+        let language = language.trim().to_lowercase();
+        if !language.is_empty() && !cleaned.contains(&language) {
+            cleaned.push(language);
+        }
+    }
+    cleaned
+}
+
+#[tauri::command]
+pub fn change_auto_detect_languages_setting(
+    app: AppHandle,
+    languages: Vec<String>,
+) -> Result<(), String> {
+    let languages = clean_language_list(languages);
+    if languages.iter().any(|l| l == "auto") {
+        return Err("Auto-detection can only be limited to specific languages".into());
+    }
+    let mut settings = settings::get_settings(&app);
+    settings.auto_detect_languages = languages;
+    settings::write_settings(&app, settings);
+    Ok(())
+}
+
+#[tauri::command]
+pub fn change_favorite_languages_setting(
+    app: AppHandle,
+    languages: Vec<String>,
+) -> Result<(), String> {
+    let mut settings = settings::get_settings(&app);
+    settings.favorite_languages = clean_language_list(languages);
+    settings::write_settings(&app, settings);
+    Ok(())
+}
+
 #[tauri::command]
 pub fn change_overlay_position_setting(app: AppHandle, position: String) -> Result<(), String> {
     let mut settings = settings::get_settings(&app);
@@ -167,6 +212,302 @@ pub fn change_debug_mode_setting(app: AppHandle, enabled: bool) -> Result<(), St
 pub fn update_custom_words(app: AppHandle, words: Vec<String>) -> Result<(), String> {
     let mut settings = settings::get_settings(&app);
     settings.custom_words = words;
+    prune_custom_word_aliases(&mut settings);
+    settings::write_settings(&app, settings);
+    Ok(())
+}
+
+#[tauri::command]
+pub fn update_language_custom_words(
+    app: AppHandle,
+    language: String,
+    words: Vec<String>,
+) -> Result<(), String> {
+    let language = text_processing::base_language(&language).to_string();
+    let words: Vec<String> = words
+        .into_iter()
+        .map(|w| w.trim().to_string())
+        .filter(|w| !w.is_empty())
+        .collect();
+
+    let mut settings = settings::get_settings(&app);
+    if words.is_empty() {
+        settings.language_custom_words.remove(&language);
+    } else {
+        settings.language_custom_words.insert(language, words);
+    }
+    prune_custom_word_aliases(&mut settings);
+    settings::write_settings(&app, settings);
+    Ok(())
+}
+
+fn is_custom_word(settings: &settings::AppSettings, word: &str) -> bool {
+    settings.custom_words.iter().any(|w| w == word)
+        || settings
+            .language_custom_words
+            .values()
+            .any(|words| words.iter().any(|w| w == word))
+}
+
+/// Drops aliases of words that are no longer in any custom word list.
+fn prune_custom_word_aliases(settings: &mut settings::AppSettings) {
+    let aliases = std::mem::take(&mut settings.custom_word_aliases);
+    settings.custom_word_aliases = aliases
+        .into_iter()
+        .filter(|(word, _)| is_custom_word(settings, word))
+        .collect();
+}
+
+#[tauri::command]
+pub fn update_custom_word_aliases(
+    app: AppHandle,
+    word: String,
+    aliases: Vec<String>,
+) -> Result<(), String> {
+    let aliases: Vec<String> = aliases
+        .into_iter()
+        .map(|a| a.trim().to_string())
+        .filter(|a| !a.is_empty())
+        .collect();
+
+    let mut settings = settings::get_settings(&app);
+    if !is_custom_word(&settings, &word) {
+        return Err(format!("'{}' is not a custom word", word));
+    }
+    if aliases.is_empty() {
+        settings.custom_word_aliases.remove(&word);
+    } else {
+        settings.custom_word_aliases.insert(word, aliases);
+    }
+    settings::write_settings(&app, settings);
+    Ok(())
+}
+
+#[tauri::command]
+pub fn update_spoken_commands(app: AppHandle, commands: Vec<SpokenCommand>) -> Result<(), String> {
+    if commands.iter().any(|c| c.phrase.trim().is_empty()) {
+        return Err("Spoken command phrases must not be empty".into());
+    }
+
+    let mut settings = settings::get_settings(&app);
+    settings.spoken_commands = commands;
+    settings::write_settings(&app, settings);
+    Ok(())
+}
+
+/// Lists the built-in spoken commands for a language, for display next to the
+/// user's own.
+#[tauri::command]
+pub fn get_builtin_spoken_commands(language: String) -> Vec<SpokenCommand> {
+    text_processing::builtin_commands(text_processing::base_language(&language))
+}
+
+/// Replaces the user's extra filler words for a language.
+#[tauri::command]
+pub fn update_filler_words(
+    app: AppHandle,
+    language: String,
+    words: Vec<String>,
+) -> Result<(), String> {
+    let language = text_processing::base_language(&language).to_string();
+    let words: Vec<String> = words
+        .into_iter()
+        .map(|w| w.trim().to_string())
+        .filter(|w| !w.is_empty())
+        .collect();
+
+    let mut settings = settings::get_settings(&app);
+    if words.is_empty() {
+        settings.filler_words.remove(&language);
+    } else {
+        settings.filler_words.insert(language, words);
+    }
+    settings::write_settings(&app, settings);
+    Ok(())
+}
+
+#[tauri::command]
+pub fn get_builtin_filler_words(language: String) -> Vec<String> {
+    text_processing::builtin_fillers(text_processing::base_language(&language))
+}
+
+#[tauri::command]
+pub fn change_hallucination_filter_setting(app: AppHandle, enabled: bool) -> Result<(), String> {
+    let mut settings = settings::get_settings(&app);
+    settings.hallucination_filter = enabled;
+    settings::write_settings(&app, settings);
+    Ok(())
+}
+
+#[tauri::command]
+pub fn update_hallucination_blocklist(app: AppHandle, phrases: Vec<String>) -> Result<(), String> {
+    let mut settings = settings::get_settings(&app);
+    settings.hallucination_blocklist = phrases
+        .into_iter()
+        .map(|p| p.trim().to_string())
+        .filter(|p| !p.is_empty())
+        .collect();
+    settings::write_settings(&app, settings);
+    Ok(())
+}
+
+#[tauri::command]
+pub fn get_builtin_hallucination_phrases() -> Vec<String> {
+    hallucination_filter::builtin_hallucinations()
+}
+
+#[tauri::command]
+pub fn get_decoder_settings(app: AppHandle, model_id: String) -> DecoderSettings {
+    let settings = settings::get_settings(&app);
+    settings::decoder_settings_for_model(&settings, &model_id)
+}
+
+/// Saves the decoder settings of a model. Decoding settings apply from the
+/// next transcription. When GPU settings of the loaded model change, it is
+/// reloaded in the background.
+#[tauri::command]
+pub fn save_decoder_settings(
+    app: AppHandle,
+    model_id: String,
+    decoder: DecoderSettings,
+) -> Result<(), String> {
+    let mut settings = settings::get_settings(&app);
+    settings::set_decoder_settings_for_model(&mut settings, &model_id, decoder)
+        .map_err(|e| e.to_string())?;
+    settings::write_settings(&app, settings);
+
+    let tm = Arc::clone(&app.state::<Arc<TranscriptionManager>>());
+    if tm.is_model_loaded() && tm.get_current_model().as_deref() == Some(model_id.as_str()) {
+        std::thread::spawn(move || {
+            if let Err(e) = tm.ensure_model_loaded(&model_id, &decoder.load_options()) {
+                eprintln!("Failed to reload model {}: {}", model_id, e);
+            }
+        });
+    }
+    Ok(())
+}
+
+/// Switches CPU-only mode and reloads the loaded model on the new backend in
+/// the background.
+#[tauri::command]
+pub fn change_force_cpu_setting(app: AppHandle, enabled: bool) -> Result<(), String> {
+    let mut settings = settings::get_settings(&app);
+    settings.force_cpu = enabled;
+    settings::write_settings(&app, settings.clone());
+
+    let tm = Arc::clone(&app.state::<Arc<TranscriptionManager>>());
+    if let Some(model_id) = tm.get_current_model().filter(|_| tm.is_model_loaded()) {
+        let load_options =
+            settings::decoder_settings_for_model(&settings, &model_id).load_options();
+        std::thread::spawn(move || {
+            if let Err(e) = tm.ensure_model_loaded(&model_id, &load_options) {
+                eprintln!("Failed to reload model {}: {}", model_id, e);
+            }
+        });
+    }
+    Ok(())
+}
+
+#[tauri::command]
+pub fn change_llm_settings(app: AppHandle, llm: LlmSettings) -> Result<(), String> {
+    llm.validate().map_err(|e| e.to_string())?;
+    let mut settings = settings::get_settings(&app);
+    settings.llm = llm;
+    settings::write_settings(&app, settings);
+    Ok(())
+}
+
+/// Adds a refinement prompt or replaces the one with the same id.
+#[tauri::command]
+pub fn save_refinement_prompt(app: AppHandle, prompt: RefinementPrompt) -> Result<(), String> {
+    if prompt.id.trim().is_empty() {
+        return Err("Prompt id must not be empty".into());
+    }
+    if prompt.template.trim().is_empty() {
+        return Err("Prompt must not be empty".into());
+    }
+    let mut settings = settings::get_settings(&app);
+    settings
+        .refinement_prompts
+        .insert(prompt.id.clone(), prompt);
+    settings::write_settings(&app, settings);
+    Ok(())
+}
+
+/// Deletes a refinement prompt. Refinement is turned off wherever it was
+/// selected.
+#[tauri::command]
+pub fn delete_refinement_prompt(app: AppHandle, id: String) -> Result<(), String> {
+    let mut settings = settings::get_settings(&app);
+    if settings.refinement_prompts.remove(&id).is_none() {
+        return Err(format!("Refinement prompt with id '{}' not found", id));
+    }
+    if settings.selected_refinement_prompt.as_deref() == Some(id.as_str()) {
+        settings.selected_refinement_prompt = None;
+    }
+    for profile in settings.profiles.values_mut() {
+        if profile.refinement_prompt.as_deref() == Some(id.as_str()) {
+            profile.refinement_prompt = Some(String::new());
+        }
+    }
+    settings::write_settings(&app, settings);
+    Ok(())
+}
+
+/// Selects the prompt transcripts are refined with, `None` turns refinement off.
+#[tauri::command]
+pub fn change_selected_refinement_prompt(app: AppHandle, id: Option<String>) -> Result<(), String> {
+    let mut settings = settings::get_settings(&app);
+    if let Some(id) = &id {
+        if !settings.refinement_prompts.contains_key(id) {
+            return Err(format!("Refinement prompt with id '{}' not found", id));
+        }
+    }
+    settings.selected_refinement_prompt = id;
+    settings::write_settings(&app, settings);
+    Ok(())
+}
+
+#[tauri::command]
+pub fn change_api_server_setting(app: AppHandle, enabled: bool, port: u16) -> Result<(), String> {
+    if port == 0 {
+        return Err("Port must be between 1 and 65535".to_string());
+    }
+    let mut settings = settings::get_settings(&app);
+    settings.api_server_enabled = enabled;
+    settings.api_server_port = port;
+    settings::write_settings(&app, settings);
+
+    let server = app.state::<Arc<ApiServerManager>>();
+    if let Err(e) = server.apply_settings() {
+        // Leave the server off rather than claiming it runs
+        let mut settings = settings::get_settings(&app);
+        settings.api_server_enabled = false;
+        settings::write_settings(&app, settings);
+        return Err(e.to_string());
+    }
+    Ok(())
+}
+
+/// Replaces the API token, clients using the old one are rejected from now on.
+#[tauri::command]
+pub fn regenerate_api_server_token(app: AppHandle) -> Result<String, String> {
+    let mut settings = settings::get_settings(&app);
+    settings.api_server_token = api_server::generate_token();
+    let token = settings.api_server_token.clone();
+    settings::write_settings(&app, settings);
+    Ok(token)
+}
+
+#[tauri::command]
+pub fn get_api_server_status(app: AppHandle) -> ApiServerStatus {
+    app.state::<Arc<ApiServerManager>>().status()
+}
+
+#[tauri::command]
+pub fn change_literal_mode_setting(app: AppHandle, enabled: bool) -> Result<(), String> {
+    let mut settings = settings::get_settings(&app);
+    settings.literal_mode = enabled;
     settings::write_settings(&app, settings);
     Ok(())
 }
@@ -182,6 +523,343 @@ pub fn change_word_correction_threshold_setting(
     Ok(())
 }
 
+#[tauri::command]
+pub fn change_streaming_transcription_setting(app: AppHandle, enabled: bool) -> Result<(), String> {
+    let mut settings = settings::get_settings(&app);
+    settings.streaming_transcription = enabled;
+    settings::write_settings(&app, settings);
+    Ok(())
+}
+
+#[tauri::command]
+pub fn change_initial_prompt_template_setting(
+    app: AppHandle,
+    template: String,
+) -> Result<(), String> {
+    let mut settings = settings::get_settings(&app);
+    settings.initial_prompt_template = template;
+    settings::write_settings(&app, settings);
+    Ok(())
+}
+
+#[tauri::command]
+pub fn change_prompt_context_length_setting(app: AppHandle, length: usize) -> Result<(), String> {
+    let mut settings = settings::get_settings(&app);
+    settings.prompt_context_length = length;
+    settings::write_settings(&app, settings);
+    Ok(())
+}
+
+#[tauri::command]
+pub fn change_model_unload_timeout_setting(app: AppHandle, minutes: u32) -> Result<(), String> {
+    let mut settings = settings::get_settings(&app);
+    settings.model_unload_timeout_minutes = minutes;
+    settings::write_settings(&app, settings);
+    Ok(())
+}
+
+#[tauri::command]
+pub fn change_low_memory_unload_threshold_setting(
+    app: AppHandle,
+    threshold_mb: u64,
+) -> Result<(), String> {
+    let mut settings = settings::get_settings(&app);
+    settings.low_memory_unload_threshold_mb = threshold_mb;
+    settings::write_settings(&app, settings);
+    Ok(())
+}
+
+#[tauri::command]
+pub fn change_max_decode_seconds_setting(app: AppHandle, seconds: u32) -> Result<(), String> {
+    let mut settings = settings::get_settings(&app);
+    settings.max_decode_seconds = seconds;
+    settings::write_settings(&app, settings);
+    Ok(())
+}
+
+#[tauri::command]
+pub fn get_transcription_profiles(app: AppHandle) -> Result<Vec<TranscriptionProfile>, String> {
+    let settings = settings::get_settings(&app);
+    Ok(settings.profiles.into_values().collect())
+}
+
+/// Creates or replaces a transcription profile.
+#[tauri::command]
+pub fn save_transcription_profile(
+    app: AppHandle,
+    profile: TranscriptionProfile,
+) -> Result<(), String> {
+    if profile.id.trim().is_empty() {
+        return Err("Profile id must not be empty".into());
+    }
+
+    if let Some(decoder) = &profile.decoder {
+        decoder.validate().map_err(|e| e.to_string())?;
+    }
+
+    let mut settings = settings::get_settings(&app);
+    if let Some(prompt) = profile.refinement_prompt.as_ref().filter(|p| !p.is_empty()) {
+        if !settings.refinement_prompts.contains_key(prompt) {
+            return Err(format!("Refinement prompt with id '{}' not found", prompt));
+        }
+    }
+    if let Some(chain) = &profile.processing_chain {
+        if !settings.processing_chains.contains_key(chain) {
+            return Err(format!("Processing chain with id '{}' not found", chain));
+        }
+    }
+    settings.profiles.insert(profile.id.clone(), profile);
+    settings::write_settings(&app, settings);
+    Ok(())
+}
+
+/// Deletes a profile and detaches it from every binding that used it.
+#[tauri::command]
+pub fn delete_transcription_profile(app: AppHandle, id: String) -> Result<(), String> {
+    let mut settings = settings::get_settings(&app);
+    if settings.profiles.remove(&id).is_none() {
+        return Err(format!("Profile with id '{}' not found", id));
+    }
+    for binding in settings.bindings.values_mut() {
+        if binding.profile_id.as_deref() == Some(id.as_str()) {
+            binding.profile_id = None;
+        }
+    }
+    settings::write_settings(&app, settings);
+    Ok(())
+}
+
+#[tauri::command]
+pub fn get_processing_chains(app: AppHandle) -> Result<Vec<ProcessingChain>, String> {
+    let settings = settings::get_settings(&app);
+    Ok(settings.processing_chains.into_values().collect())
+}
+
+/// Creates or replaces a processing chain after checking every step builds.
+#[tauri::command]
+pub fn save_processing_chain(app: AppHandle, chain: ProcessingChain) -> Result<(), String> {
+    if chain.id.trim().is_empty() {
+        return Err("Processing chain id must not be empty".into());
+    }
+
+    let mut settings = settings::get_settings(&app);
+    text_processing::validate_chain(&chain, &settings).map_err(|e| e.to_string())?;
+    settings.processing_chains.insert(chain.id.clone(), chain);
+    settings::write_settings(&app, settings);
+    Ok(())
+}
+
+/// Deletes a processing chain. Profiles using it fall back to the global chain.
+#[tauri::command]
+pub fn delete_processing_chain(app: AppHandle, id: String) -> Result<(), String> {
+    if id == text_processing::DEFAULT_CHAIN_ID {
+        return Err("The default processing chain cannot be removed".into());
+    }
+
+    let mut settings = settings::get_settings(&app);
+    if settings.processing_chains.remove(&id).is_none() {
+        return Err(format!("Processing chain with id '{}' not found", id));
+    }
+    if settings.selected_processing_chain == id {
+        settings.selected_processing_chain = text_processing::DEFAULT_CHAIN_ID.to_string();
+    }
+    for profile in settings.profiles.values_mut() {
+        if profile.processing_chain.as_deref() == Some(id.as_str()) {
+            profile.processing_chain = None;
+        }
+    }
+    settings::write_settings(&app, settings);
+    Ok(())
+}
+
+#[tauri::command]
+pub fn change_processing_chain_setting(app: AppHandle, chain_id: String) -> Result<(), String> {
+    let mut settings = settings::get_settings(&app);
+    if !settings.processing_chains.contains_key(&chain_id) {
+        return Err(format!("Processing chain with id '{}' not found", chain_id));
+    }
+    settings.selected_processing_chain = chain_id;
+    settings::write_settings(&app, settings);
+    Ok(())
+}
+
+#[tauri::command]
+pub fn set_binding_profile(
+    app: AppHandle,
+    binding_id: String,
+    profile_id: Option<String>,
+) -> Result<(), String> {
+    let mut settings = settings::get_settings(&app);
+
+    if let Some(profile_id) = &profile_id {
+        if !settings.profiles.contains_key(profile_id) {
+            return Err(format!("Profile with id '{}' not found", profile_id));
+        }
+    }
+
+    let binding = settings
+        .bindings
+        .get_mut(&binding_id)
+        .ok_or_else(|| format!("Binding with id '{}' not found", binding_id))?;
+    binding.profile_id = profile_id;
+
+    settings::write_settings(&app, settings);
+    Ok(())
+}
+
+/// Adds another transcribe shortcut, e.g. one that dictates with a different
+/// profile than the default binding.
+#[tauri::command]
+pub fn add_transcribe_binding(
+    app: AppHandle,
+    id: String,
+    name: String,
+    binding: String,
+    profile_id: Option<String>,
+) -> Result<BindingResponse, String> {
+    let mut settings = settings::get_settings(&app);
+
+    if settings.bindings.contains_key(&id) {
+        return Err(format!("Binding with id '{}' already exists", id));
+    }
+    if let Some(profile_id) = &profile_id {
+        if !settings.profiles.contains_key(profile_id) {
+            return Err(format!("Profile with id '{}' not found", profile_id));
+        }
+    }
+    validate_shortcut_string(&binding)?;
+
+    let new_binding = ShortcutBinding {
+        id: id.clone(),
+        name,
+        description: "Converts your speech into text.".to_string(),
+        default_binding: binding.clone(),
+        current_binding: binding,
+        action: Some("transcribe".to_string()),
+        profile_id,
+    };
+
+    if let Err(e) = _register_shortcut(&app, new_binding.clone()) {
+        return Ok(BindingResponse {
+            success: false,
+            binding: None,
+            error: Some(e),
+        });
+    }
+
+    settings.bindings.insert(id, new_binding.clone());
+    settings::write_settings(&app, settings);
+
+    Ok(BindingResponse {
+        success: true,
+        binding: Some(new_binding),
+        error: None,
+    })
+}
+
+/// Adds the optional shortcut that cancels recording and any running decode.
+#[tauri::command]
+pub fn add_cancel_binding(app: AppHandle, binding: String) -> Result<BindingResponse, String> {
+    let mut settings = settings::get_settings(&app);
+
+    if settings.bindings.contains_key("cancel") {
+        return Err("A cancel binding already exists".into());
+    }
+    validate_shortcut_string(&binding)?;
+
+    let new_binding = ShortcutBinding {
+        id: "cancel".to_string(),
+        name: "Cancel".to_string(),
+        description: "Cancels the current recording or transcription.".to_string(),
+        default_binding: binding.clone(),
+        current_binding: binding,
+        action: Some("cancel".to_string()),
+        profile_id: None,
+    };
+
+    if let Err(e) = _register_shortcut(&app, new_binding.clone()) {
+        return Ok(BindingResponse {
+            success: false,
+            binding: None,
+            error: Some(e),
+        });
+    }
+
+    settings
+        .bindings
+        .insert("cancel".to_string(), new_binding.clone());
+    settings::write_settings(&app, settings);
+
+    Ok(BindingResponse {
+        success: true,
+        binding: Some(new_binding),
+        error: None,
+    })
+}
+
+/// Adds the optional shortcut that switches to the next favorite language.
+#[tauri::command]
+pub fn add_cycle_language_binding(
+    app: AppHandle,
+    binding: String,
+) -> Result<BindingResponse, String> {
+    let mut settings = settings::get_settings(&app);
+
+    if settings.bindings.contains_key("cycle_language") {
+        return Err("A cycle language binding already exists".into());
+    }
+    validate_shortcut_string(&binding)?;
+
+    let new_binding = ShortcutBinding {
+        id: "cycle_language".to_string(),
+        name: "Cycle Language".to_string(),
+        description: "Switches to the next favorite language.".to_string(),
+        default_binding: binding.clone(),
+        current_binding: binding,
+        action: Some("cycle_language".to_string()),
+        profile_id: None,
+    };
+
+    if let Err(e) = _register_shortcut(&app, new_binding.clone()) {
+        return Ok(BindingResponse {
+            success: false,
+            binding: None,
+            error: Some(e),
+        });
+    }
+
+    settings
+        .bindings
+        .insert("cycle_language".to_string(), new_binding.clone());
+    settings::write_settings(&app, settings);
+
+    Ok(BindingResponse {
+        success: true,
+        binding: Some(new_binding),
+        error: None,
+    })
+}
+
+#[tauri::command]
+pub fn remove_binding(app: AppHandle, id: String) -> Result<(), String> {
+    if id == "transcribe" {
+        return Err("The default transcribe binding cannot be removed".into());
+    }
+
+    let mut settings = settings::get_settings(&app);
+    let binding = settings
+        .bindings
+        .remove(&id)
+        .ok_or_else(|| format!("Binding with id '{}' not found", id))?;
+
+    if let Err(e) = _unregister_shortcut(&app, binding) {
+        eprintln!("remove_binding error for id '{}': {}", id, e);
+    }
+
+    settings::write_settings(&app, settings);
+    Ok(())
+}
+
 /// Determine whether a shortcut string contains at least one non-modifier key.
 /// We allow single non-modifier keys (e.g. "f5" or "space") but disallow
 /// modifier-only combos (e.g. "ctrl" or "ctrl+shift").
@@ -255,16 +933,17 @@ fn _register_shortcut(app: &AppHandle, binding: ShortcutBinding) -> Result<(), S
         return Err(error_msg);
     }
 
-    // Clone binding.id for use in the closure
+    // Clone binding.id and its action for use in the closure
     let binding_id_for_closure = binding.id.clone();
+    let action_id_for_closure = binding.action_id().to_string();
 
     app.global_shortcut()
         .on_shortcut(shortcut, move |ah, scut, event| {
             if scut == &shortcut {
                 let shortcut_string = scut.into_string();
-                let settings = get_settings(ah);
+                let settings = get_settings_for_binding(ah, Some(&binding_id_for_closure));
 
-                if let Some(action) = ACTION_MAP.get(&binding_id_for_closure) {
+                if let Some(action) = ACTION_MAP.get(&action_id_for_closure) {
                     if settings.push_to_talk {
                         if event.state == ShortcutState::Pressed {
                             action.start(ah, &binding_id_for_closure, &shortcut_string);
@@ -0,0 +1,571 @@
+use super::phonetic::{fold_accents, separates_words, sounds_alike};
+use super::TextProcessor;
+use crate::settings::AppSettings;
+use std::collections::HashMap;
+use std::ops::Range;
+use strsim::{generic_levenshtein, levenshtein};
+use unicode_segmentation::UnicodeSegmentation;
+
+/// Words shorter than this are only corrected when they match exactly or via
+/// an alias, fuzzily they too often hit real words like "is" for "iOS".
+const MIN_FUZZY_LEN: usize = 3;
+
+/// The custom words that apply to `settings.selected_language`: the ones for
+/// every language followed by the ones for that language. With "auto" only
+/// the shared list is known to apply.
+pub fn custom_words_for_language(settings: &AppSettings) -> Vec<String> {
+    let mut words = settings.custom_words.clone();
+    if settings.selected_language != "auto" {
+        let language = super::base_language(&settings.selected_language);
+        if let Some(extra) = settings.language_custom_words.get(language) {
+            for word in extra {
+                if !words.contains(word) {
+                    words.push(word.clone());
+                }
+            }
+        }
+    }
+    words
+}
+
+/// A configured custom word or phrase.
+struct Entry {
+    /// Written exactly like this when matched
+    replacement: String,
+    /// Configured with capitals, e.g. "iOS", so the transcript's case is not
+    /// applied to it
+    keep_case: bool,
+    /// Lowercase, accent-folded letters and digits only, compared against
+    /// transcript windows
+    key: String,
+    /// Number of words in the configured phrase
+    words: usize,
+    /// Normalized words of each alias
+    aliases: Vec<Vec<String>>,
+    /// Lowercase graphemes of the phrase without spaces, for languages that
+    /// are matched character by character
+    graphemes: Vec<String>,
+    alias_graphemes: Vec<Vec<String>>,
+}
+
+/// A transcript word split from the punctuation around it.
+struct Word<'a> {
+    prefix: &'a str,
+    core: &'a str,
+    suffix: &'a str,
+    normalized: String,
+    /// Byte range of the word, punctuation included, in the transcript
+    span: Range<usize>,
+    /// Whether a line break separates it from the previous word
+    new_line: bool,
+}
+
+/// Replaces words and phrases that sound or look like one of the user's
+/// custom words. Explicit aliases are replaced first, everything else is
+/// matched fuzzily against windows of up to one word more or less than the
+/// configured phrase, so "git hub actions" still finds "GitHub Actions".
+///
+/// Languages written without spaces, such as Chinese or Thai, are matched
+/// grapheme by grapheme instead, without a phonetic comparison.
+pub struct CustomWordsProcessor {
+    entries: Vec<Entry>,
+    threshold: f64,
+    /// Base language code, picks the phonetic algorithm
+    language: String,
+}
+
+fn normalize(word: &str) -> String {
+    fold_accents(
+        &word
+            .chars()
+            .filter(|c| c.is_alphanumeric())
+            .flat_map(|c| c.to_lowercase())
+            .collect::<String>(),
+    )
+}
+
+/// Graphemes that can be part of a word. Thai and Devanagari clusters start
+/// with a letter, so a whole cluster counts.
+fn is_word_grapheme(grapheme: &str) -> bool {
+    grapheme
+        .chars()
+        .next()
+        .map_or(false, |c| c.is_alphanumeric())
+}
+
+fn to_graphemes(text: &str) -> Vec<String> {
+    text.graphemes(true)
+        .filter(|g| is_word_grapheme(g))
+        .map(|g| g.to_lowercase())
+        .collect()
+}
+
+/// Splits a transcript into words, remembering where each one is so the
+/// whitespace between them can be kept.
+fn split_words(text: &str) -> Vec<Word<'_>> {
+    let mut words: Vec<Word> = Vec::new();
+    for raw in text.split_whitespace() {
+        let start = raw.as_ptr() as usize - text.as_ptr() as usize;
+        let previous_end = words.last().map_or(0, |w| w.span.end);
+        let mut word = split_word(raw);
+        word.span = start..start + raw.len();
+        word.new_line = !words.is_empty() && text[previous_end..start].contains('\n');
+        words.push(word);
+    }
+    words
+}
+
+fn split_word(word: &str) -> Word<'_> {
+    let start = word
+        .char_indices()
+        .find(|(_, c)| c.is_alphanumeric())
+        .map_or(word.len(), |(i, _)| i);
+    let end = word
+        .char_indices()
+        .rev()
+        .find(|(_, c)| c.is_alphanumeric())
+        .map_or(start, |(i, c)| i + c.len_utf8());
+    let core = &word[start..end];
+    Word {
+        prefix: &word[..start],
+        core,
+        suffix: &word[end..],
+        normalized: normalize(core),
+        span: 0..word.len(),
+        new_line: false,
+    }
+}
+
+/// Applies the case pattern of the matched transcript words to a lowercase
+/// custom word.
+fn match_case(original: &str, replacement: &str) -> String {
+    let letters: Vec<char> = original.chars().filter(|c| c.is_alphabetic()).collect();
+    if letters.len() > 1 && letters.iter().all(|c| c.is_uppercase()) {
+        replacement.to_uppercase()
+    } else if letters.first().map_or(false, |c| c.is_uppercase()) {
+        let mut chars: Vec<char> = replacement.chars().collect();
+        if let Some(first_char) = chars.get_mut(0) {
+            *first_char = first_char.to_uppercase().next().unwrap_or(*first_char);
+        }
+        chars.into_iter().collect()
+    } else {
+        replacement.to_string()
+    }
+}
+
+impl CustomWordsProcessor {
+    /// `aliases` maps a custom word, as configured, to spellings that are
+    /// always replaced by it. `language` is a base language code.
+    pub fn new(
+        words: Vec<String>,
+        aliases: &HashMap<String, Vec<String>>,
+        threshold: f64,
+        language: &str,
+    ) -> Self {
+        let entries = words
+            .into_iter()
+            .filter_map(|word| {
+                let phrase: Vec<String> = word
+                    .split_whitespace()
+                    .map(normalize)
+                    .filter(|w| !w.is_empty())
+                    .collect();
+                if phrase.is_empty() {
+                    return None;
+                }
+
+                let word_aliases: Vec<&String> = aliases.get(&word).into_iter().flatten().collect();
+                let mut entry_aliases: Vec<Vec<String>> = word_aliases
+                    .iter()
+                    .map(|alias| {
+                        alias
+                            .split_whitespace()
+                            .map(normalize)
+                            .filter(|w| !w.is_empty())
+                            .collect::<Vec<_>>()
+                    })
+                    .filter(|alias| !alias.is_empty())
+                    .collect();
+                entry_aliases.sort_by(|a, b| b.len().cmp(&a.len()));
+
+                let mut alias_graphemes: Vec<Vec<String>> = word_aliases
+                    .iter()
+                    .map(|alias| to_graphemes(alias))
+                    .filter(|alias| !alias.is_empty())
+                    .collect();
+                alias_graphemes.sort_by(|a, b| b.len().cmp(&a.len()));
+
+                Some(Entry {
+                    keep_case: word.chars().any(|c| c.is_uppercase()),
+                    key: phrase.concat(),
+                    words: phrase.len(),
+                    aliases: entry_aliases,
+                    graphemes: to_graphemes(&word),
+                    alias_graphemes,
+                    replacement: word.trim().to_string(),
+                })
+            })
+            .collect();
+
+        Self {
+            entries,
+            threshold,
+            language: language.to_string(),
+        }
+    }
+
+    /// Longest alias matching at the start of `words`, as (entry, length).
+    fn match_alias(&self, words: &[Word]) -> Option<(&Entry, usize)> {
+        self.entries
+            .iter()
+            .flat_map(|entry| entry.aliases.iter().map(move |alias| (entry, alias)))
+            .filter(|(_, alias)| {
+                alias.len() <= words.len()
+                    && joined(&words[..alias.len()])
+                    && words
+                        .iter()
+                        .zip(alias.iter())
+                        .all(|(w, a)| w.normalized == *a)
+            })
+            .max_by_key(|(_, alias)| alias.len())
+            .map(|(entry, alias)| (entry, alias.len()))
+    }
+
+    /// Closest entry to a window at the start of `words`, as (entry, length,
+    /// score). Longer windows win ties so a phrase is replaced as a whole.
+    fn match_fuzzy(&self, words: &[Word]) -> Option<(&Entry, usize, f64)> {
+        let mut best: Option<(&Entry, usize, f64)> = None;
+        let mut best_score = f64::MAX;
+
+        for entry in &self.entries {
+            let key_len = entry.key.chars().count();
+            let longest = (entry.words + 1).min(words.len());
+            let shortest = entry.words.saturating_sub(1).max(1);
+            for len in (shortest..=longest).rev() {
+                let window = &words[..len];
+                if !joined(window) || window.iter().any(|w| w.normalized.is_empty()) {
+                    continue;
+                }
+                let candidate: String = window.iter().map(|w| w.normalized.as_str()).collect();
+                let candidate_len = candidate.chars().count();
+
+                // Skip extremely long words to avoid performance issues
+                if candidate_len > 50 {
+                    continue;
+                }
+                if candidate_len < MIN_FUZZY_LEN && candidate != entry.key {
+                    continue;
+                }
+
+                // Skip if lengths are too different (optimization)
+                let len_diff = (candidate_len as i32 - key_len as i32).abs();
+                if len_diff > 5 {
+                    continue;
+                }
+
+                // Calculate Levenshtein distance (normalized by length)
+                let levenshtein_dist = levenshtein(&candidate, &entry.key);
+                let max_len = candidate_len.max(key_len) as f64;
+                let levenshtein_score = if max_len > 0.0 {
+                    levenshtein_dist as f64 / max_len
+                } else {
+                    1.0
+                };
+
+                // Combine scores: favor phonetic matches, but also consider
+                // string similarity
+                let combined_score = if sounds_alike(&self.language, &candidate, &entry.key) {
+                    levenshtein_score * 0.3
+                } else {
+                    levenshtein_score
+                };
+
+                if combined_score < self.threshold && combined_score < best_score {
+                    best = Some((entry, len, combined_score));
+                    best_score = combined_score;
+                }
+            }
+        }
+
+        best
+    }
+
+    /// Like `match_fuzzy` for graphemes of an unspaced language, aliases
+    /// first. Returns (entry, length, score).
+    fn match_graphemes(&self, graphemes: &[String]) -> Option<(&Entry, usize, f64)> {
+        let alias = self
+            .entries
+            .iter()
+            .flat_map(|entry| {
+                entry
+                    .alias_graphemes
+                    .iter()
+                    .map(move |alias| (entry, alias))
+            })
+            .filter(|(_, alias)| graphemes.starts_with(alias))
+            .max_by_key(|(_, alias)| alias.len());
+        if let Some((entry, alias)) = alias {
+            return Some((entry, alias.len(), 0.0));
+        }
+
+        let run = graphemes
+            .iter()
+            .position(|g| !is_word_grapheme(g))
+            .unwrap_or(graphemes.len());
+
+        let mut best: Option<(&Entry, usize, f64)> = None;
+        let mut best_score = f64::MAX;
+
+        for entry in &self.entries {
+            let key_len = entry.graphemes.len();
+            if key_len == 0 {
+                continue;
+            }
+            let longest = (key_len + 1).min(run);
+            let shortest = key_len.saturating_sub(1).max(1);
+            for len in (shortest..=longest).rev() {
+                let window = &graphemes[..len];
+                let distance = generic_levenshtein(&window.to_vec(), &entry.graphemes);
+                let score = distance as f64 / len.max(key_len) as f64;
+
+                if score < self.threshold && score < best_score {
+                    best = Some((entry, len, score));
+                    best_score = score;
+                }
+            }
+        }
+
+        best
+    }
+
+    fn process_words(&self, text: &str) -> String {
+        let words = split_words(text);
+        let mut out = String::with_capacity(text.len());
+        // End of the text already copied to `out`
+        let mut copied = 0;
+
+        let mut i = 0;
+        while i < words.len() {
+            // Keep the original whitespace, line breaks included
+            out.push_str(&text[copied..words[i].span.start]);
+
+            if words[i].normalized.is_empty() {
+                out.push_str(&text[words[i].span.clone()]);
+                copied = words[i].span.end;
+                i += 1;
+                continue;
+            }
+
+            let found = self.match_alias(&words[i..]).or_else(|| {
+                let (entry, len, score) = self.match_fuzzy(&words[i..])?;
+                // A window starting one word later that matches better wins,
+                // so "a kubernetes operator" keeps its "a"
+                let later = self.match_fuzzy(&words[i + 1..]);
+                if len > 1 && later.map_or(false, |(_, _, later_score)| later_score < score) {
+                    return None;
+                }
+                Some((entry, len))
+            });
+
+            match found {
+                Some((entry, len)) => {
+                    let window = &words[i..i + len];
+                    let corrected = if entry.keep_case {
+                        entry.replacement.clone()
+                    } else {
+                        let original: Vec<&str> = window.iter().map(|w| w.core).collect();
+                        match_case(&original.join(" "), &entry.replacement)
+                    };
+
+                    // Preserve punctuation from the original words
+                    out.push_str(window[0].prefix);
+                    out.push_str(&corrected);
+                    out.push_str(window[len - 1].suffix);
+                    copied = window[len - 1].span.end;
+                    i += len;
+                }
+                None => {
+                    out.push_str(&text[words[i].span.clone()]);
+                    copied = words[i].span.end;
+                    i += 1;
+                }
+            }
+        }
+
+        out.push_str(&text[copied..]);
+        out
+    }
+
+    fn process_graphemes(&self, text: &str) -> String {
+        let original: Vec<&str> = text.graphemes(true).collect();
+        let lowered: Vec<String> = original.iter().map(|g| g.to_lowercase()).collect();
+        let mut out = String::with_capacity(text.len());
+
+        let mut i = 0;
+        while i < original.len() {
+            if !is_word_grapheme(original[i]) {
+                out.push_str(original[i]);
+                i += 1;
+                continue;
+            }
+
+            let found = self
+                .match_graphemes(&lowered[i..])
+                .and_then(|(entry, len, score)| {
+                    let later = self.match_graphemes(&lowered[i + 1..]);
+                    if len > 1 && later.map_or(false, |(_, _, later_score)| later_score < score) {
+                        return None;
+                    }
+                    Some((entry, len))
+                });
+
+            match found {
+                Some((entry, len)) => {
+                    out.push_str(&entry.replacement);
+                    i += len;
+                }
+                None => {
+                    out.push_str(original[i]);
+                    i += 1;
+                }
+            }
+        }
+
+        out
+    }
+}
+
+/// Whether a run of words has no punctuation or line break between them.
+/// Phrases are not matched across "Tauri. App".
+fn joined(words: &[Word]) -> bool {
+    words
+        .windows(2)
+        .all(|pair| pair[0].suffix.is_empty() && pair[1].prefix.is_empty() && !pair[1].new_line)
+}
+
+impl TextProcessor for CustomWordsProcessor {
+    fn name(&self) -> &'static str {
+        "custom_words"
+    }
+
+    fn process(&self, text: &str) -> String {
+        if self.entries.is_empty() {
+            return text.to_string();
+        }
+
+        if separates_words(&self.language) {
+            self.process_words(text)
+        } else {
+            self.process_graphemes(text)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn correct(language: &str, words: &[&str], aliases: &[(&str, &str)], text: &str) -> String {
+        let mut alias_map: HashMap<String, Vec<String>> = HashMap::new();
+        for (word, alias) in aliases {
+            alias_map
+                .entry(word.to_string())
+                .or_default()
+                .push(alias.to_string());
+        }
+        let words = words.iter().map(|w| w.to_string()).collect();
+        CustomWordsProcessor::new(words, &alias_map, 0.18, language).process(text)
+    }
+
+    #[test]
+    fn aliases_are_replaced() {
+        assert_eq!(
+            correct(
+                "en",
+                &["Kubernetes"],
+                &[("Kubernetes", "cube nettis")],
+                "Deploy it to cube nettis, then test."
+            ),
+            "Deploy it to Kubernetes, then test."
+        );
+    }
+
+    #[test]
+    fn phrases_match_across_word_splits() {
+        assert_eq!(
+            correct("en", &["GitHub Actions"], &[], "Set up git hub actions."),
+            "Set up GitHub Actions."
+        );
+        // Not across punctuation
+        assert_eq!(
+            correct(
+                "en",
+                &["GitHub Actions"],
+                &[],
+                "Use git. Hub actions follow."
+            ),
+            "Use git. Hub actions follow."
+        );
+    }
+
+    #[test]
+    fn lowercase_words_take_the_transcript_case() {
+        assert_eq!(
+            correct("en", &["tauri"], &[], "Tauri apps and tauri plugins"),
+            "Tauri apps and tauri plugins"
+        );
+        assert_eq!(correct("en", &["tauri"], &[], "TAURY rocks"), "TAURI rocks");
+    }
+
+    #[test]
+    fn digits_are_part_of_the_word() {
+        assert_eq!(
+            correct("en", &["MP3"], &[], "Save it as mp3."),
+            "Save it as MP3."
+        );
+        assert_eq!(correct("en", &["3D"], &[], "a 3d model"), "a 3D model");
+    }
+
+    #[test]
+    fn german_names_match_by_sound() {
+        assert_eq!(
+            correct("de", &["Schmidt"], &[], "Herr Schmitt kommt."),
+            "Herr Schmidt kommt."
+        );
+    }
+
+    #[test]
+    fn unspaced_languages_match_graphemes() {
+        assert_eq!(
+            correct(
+                "zh",
+                &["深度求索"],
+                &[("深度求索", "深度球索")],
+                "我在深度球索工作。"
+            ),
+            "我在深度求索工作。"
+        );
+        assert_eq!(
+            correct("zh", &["人工智能研究所"], &[], "他去了人工智能研究锁。"),
+            "他去了人工智能研究所。"
+        );
+    }
+
+    #[test]
+    fn line_breaks_and_spacing_are_kept() {
+        assert_eq!(
+            correct(
+                "en",
+                &["Kubernetes", "GitHub Actions"],
+                &[("Kubernetes", "cube nettis")],
+                "Deploy to cube nettis.\nThen\n\nset up  git hub actions\n"
+            ),
+            "Deploy to Kubernetes.\nThen\n\nset up  GitHub Actions\n"
+        );
+        // A phrase doesn't span a line break
+        assert_eq!(
+            correct("en", &["GitHub Actions"], &[], "git\nhub actions"),
+            "git\nhub actions"
+        );
+    }
+}
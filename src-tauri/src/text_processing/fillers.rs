@@ -0,0 +1,284 @@
+use super::TextProcessor;
+
+/// Longest run of words that is checked for an immediate repetition, so "I
+/// think I think" collapses as well as "I I".
+const MAX_REPEAT_WORDS: usize = 3;
+
+const SENTENCE_END: &[char] = &['.', '!', '?', '…'];
+
+/// Built-in filler words for a base language code. Only sounds that are never
+/// meant as words: "er", "ah" or "é" carry meaning often enough that dropping
+/// them would change what was said. Phrases such as "you know" are left to
+/// `AppSettings::filler_words` for the same reason.
+pub fn builtin_fillers(language: &str) -> Vec<String> {
+    let fillers: &[&str] = match language {
+        "en" => &["um", "umm", "uh", "uhh", "uhm", "erm", "hmm", "mm"],
+        "de" => &["äh", "ähm", "öh", "öhm", "hm", "hmm"],
+        "fr" => &["euh", "heu", "hum"],
+        "es" => &["eh", "em", "emm", "mmm"],
+        "it" => &["ehm", "eh", "uhm"],
+        "pt" => &["hã", "hum"],
+        "nl" => &["eh", "uh", "ehm", "uhm"],
+        _ => &[],
+    };
+    fillers.iter().map(|f| f.to_string()).collect()
+}
+
+/// Words that are doubled on purpose, e.g. "he had had enough" or "die Frau,
+/// die die Blumen kauft". They are never collapsed as a stutter.
+fn doubled_words(language: &str) -> &'static [&'static str] {
+    match language {
+        "en" => &["had", "that", "is"],
+        "de" => &["die", "der", "das", "den", "dem"],
+        "fr" => &["nous", "vous"],
+        "nl" => &["die", "dat"],
+        _ => &[],
+    }
+}
+
+/// Lowercases a word and drops surrounding punctuation for comparison.
+fn normalize(word: &str) -> String {
+    word.chars()
+        .filter(|c| c.is_alphanumeric())
+        .flat_map(|c| c.to_lowercase())
+        .collect()
+}
+
+fn trailing_punctuation(word: &str) -> &str {
+    let kept = word.trim_end_matches(|c: char| !c.is_alphanumeric());
+    &word[kept.len()..]
+}
+
+fn starts_uppercase(word: &str) -> bool {
+    word.chars()
+        .find(|c| c.is_alphabetic())
+        .map_or(false, |c| c.is_uppercase())
+}
+
+fn capitalize(word: &str) -> String {
+    let mut out = String::with_capacity(word.len());
+    let mut done = false;
+    for c in word.chars() {
+        if !done && c.is_alphabetic() {
+            out.extend(c.to_uppercase());
+            done = true;
+        } else {
+            out.push(c);
+        }
+    }
+    out
+}
+
+struct Kept {
+    word: String,
+    normalized: String,
+}
+
+/// Removes filler words and collapses stutters such as "I I I think", keeping
+/// the sentence punctuation and capitalization intact.
+pub struct FillerRemovalProcessor {
+    /// Normalized filler phrases, longest first
+    fillers: Vec<Vec<String>>,
+    collapse_repetitions: bool,
+    /// Words of the language that may follow themselves
+    doubled_words: &'static [&'static str],
+}
+
+impl FillerRemovalProcessor {
+    pub fn new(fillers: &[String], collapse_repetitions: bool, language: &str) -> Self {
+        let mut fillers: Vec<Vec<String>> = fillers
+            .iter()
+            .map(|f| {
+                f.split_whitespace()
+                    .map(normalize)
+                    .filter(|w| !w.is_empty())
+                    .collect::<Vec<_>>()
+            })
+            .filter(|words| !words.is_empty())
+            .collect();
+        fillers.sort_by(|a, b| b.len().cmp(&a.len()));
+        fillers.dedup();
+
+        Self {
+            fillers,
+            collapse_repetitions,
+            doubled_words: doubled_words(language),
+        }
+    }
+
+    fn match_filler(&self, words: &[String]) -> Option<usize> {
+        self.fillers
+            .iter()
+            .find(|f| words.len() >= f.len() && words[..f.len()] == f[..])
+            .map(|f| f.len())
+    }
+
+    /// Length of the run at the start of `upcoming` that repeats the end of
+    /// `kept`, if any.
+    fn match_repetition(&self, kept: &[Kept], upcoming: &[String]) -> Option<usize> {
+        (1..=MAX_REPEAT_WORDS).rev().find(|&n| {
+            n <= kept.len()
+                && n <= upcoming.len()
+                // A sentence boundary inside the run means it's not a stutter
+                && !kept[kept.len() - n..]
+                    .iter()
+                    .any(|k| k.word.ends_with(SENTENCE_END))
+                && kept[kept.len() - n..]
+                    .iter()
+                    .zip(&upcoming[..n])
+                    .all(|(k, u)| !u.is_empty() && k.normalized == *u)
+                && !(n == 1 && self.doubled_words.contains(&upcoming[0].as_str()))
+        })
+    }
+
+    fn process_line(&self, text: &str) -> String {
+        let words: Vec<&str> = text.split_whitespace().collect();
+        let normalized: Vec<String> = words.iter().map(|w| normalize(w)).collect();
+
+        let mut kept: Vec<Kept> = Vec::with_capacity(words.len());
+        let mut capitalize_next = false;
+
+        let mut i = 0;
+        while i < words.len() {
+            if let Some(len) = self.match_filler(&normalized[i..]) {
+                let first = words[i];
+                let trailing = trailing_punctuation(words[i + len - 1]);
+                let at_sentence_start =
+                    kept.last().map_or(true, |k| k.word.ends_with(SENTENCE_END));
+
+                if let Some(mark) = trailing.chars().find(|c| SENTENCE_END.contains(c)) {
+                    // "so, uh." keeps its full stop on the previous word
+                    if let Some(prev) = kept.last_mut() {
+                        let base_len = prev.word.trim_end_matches(&[',', ';', ':'][..]).len();
+                        prev.word.truncate(base_len);
+                        if !prev.word.ends_with(SENTENCE_END) {
+                            prev.word.push(mark);
+                        }
+                    }
+                } else if trailing.starts_with(',') {
+                    // "I think, um, we" reads as "I think we"
+                    if let Some(prev) = kept.last_mut() {
+                        if prev.word.ends_with(',') {
+                            prev.word.pop();
+                        }
+                    }
+                }
+
+                if at_sentence_start && starts_uppercase(first) {
+                    capitalize_next = true;
+                }
+                i += len;
+                continue;
+            }
+
+            if self.collapse_repetitions {
+                if let Some(n) = self.match_repetition(&kept, &normalized[i..]) {
+                    // Drop the earlier copy and keep the later one, it carries
+                    // the punctuation that belongs to the rest of the sentence
+                    let earlier = kept.split_off(kept.len() - n);
+                    if starts_uppercase(&earlier[0].word) {
+                        capitalize_next = true;
+                    }
+                }
+            }
+
+            let word = if capitalize_next {
+                capitalize_next = false;
+                capitalize(words[i])
+            } else {
+                words[i].to_string()
+            };
+            kept.push(Kept {
+                word,
+                normalized: normalized[i].clone(),
+            });
+            i += 1;
+        }
+
+        kept.into_iter()
+            .map(|k| k.word)
+            .collect::<Vec<_>>()
+            .join(" ")
+    }
+}
+
+impl TextProcessor for FillerRemovalProcessor {
+    fn name(&self) -> &'static str {
+        "filler_removal"
+    }
+
+    fn process(&self, text: &str) -> String {
+        // Line breaks from earlier steps are kept, stutters don't span lines
+        text.split('\n')
+            .map(|line| self.process_line(line))
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn remove_fillers(language: &str, text: &str) -> String {
+        FillerRemovalProcessor::new(&builtin_fillers(language), true, language).process(text)
+    }
+
+    #[test]
+    fn hesitation_sounds_are_removed() {
+        assert_eq!(
+            remove_fillers("en", "Um, I think, uh, we should go."),
+            "I think we should go."
+        );
+        assert_eq!(remove_fillers("en", "So we leave, uh."), "So we leave.");
+        assert_eq!(remove_fillers("de", "Ähm, das passt."), "Das passt.");
+    }
+
+    #[test]
+    fn words_that_sound_like_fillers_are_kept() {
+        let cases = [
+            ("en", "You know the answer."),
+            ("en", "Ah, now I see it, er, sort of."),
+            ("pt", "A casa é grande."),
+            ("pt", "Ele é tipo assim mesmo."),
+            ("it", "Il capo, cioè Marco, arriva domani."),
+            ("fr", "C'est ben vrai."),
+            ("es", "O sea que vienes mañana."),
+        ];
+        for (language, text) in cases {
+            assert_eq!(remove_fillers(language, text), text);
+        }
+    }
+
+    #[test]
+    fn filler_phrases_are_removed_once_added() {
+        let mut fillers = builtin_fillers("en");
+        fillers.push("you know".to_string());
+        let processor = FillerRemovalProcessor::new(&fillers, false, "en");
+        assert_eq!(processor.process("It was, you know, fine."), "It was fine.");
+    }
+
+    #[test]
+    fn stutters_are_collapsed() {
+        assert_eq!(remove_fillers("en", "I I think so."), "I think so.");
+        assert_eq!(
+            remove_fillers("en", "I think I think we can."),
+            "I think we can."
+        );
+        // A sentence boundary is not a stutter
+        assert_eq!(remove_fillers("en", "Go. Go now."), "Go. Go now.");
+    }
+
+    #[test]
+    fn doubled_words_are_kept() {
+        let cases = [
+            ("en", "He had had enough."),
+            ("en", "I know that that works."),
+            ("de", "Die Frau, die die Blumen kauft."),
+            ("fr", "Nous nous sommes vus."),
+        ];
+        for (language, text) in cases {
+            assert_eq!(remove_fillers(language, text), text);
+        }
+    }
+}
@@ -0,0 +1,250 @@
+use super::TextProcessor;
+use serde::{Deserialize, Serialize};
+
+const CLOSING_PUNCTUATION: &[char] = &[',', '.', '!', '?', ';', ':', ')', ']', '}', '…'];
+const SENTENCE_END: &[char] = &['.', '!', '?', '…'];
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum CasingMode {
+    Lowercase,
+    Uppercase,
+    /// Capitalizes the first letter of every sentence, leaves the rest alone
+    Sentence,
+}
+
+pub struct CasingProcessor {
+    mode: CasingMode,
+}
+
+impl CasingProcessor {
+    pub fn new(mode: CasingMode) -> Self {
+        Self { mode }
+    }
+}
+
+impl TextProcessor for CasingProcessor {
+    fn name(&self) -> &'static str {
+        "casing"
+    }
+
+    fn process(&self, text: &str) -> String {
+        match self.mode {
+            CasingMode::Lowercase => text.to_lowercase(),
+            CasingMode::Uppercase => text.to_uppercase(),
+            CasingMode::Sentence => {
+                let mut out = String::with_capacity(text.len());
+                let mut at_sentence_start = true;
+                // A sentence only ends at punctuation followed by whitespace,
+                // so "e.g." and "3.5" stay untouched
+                let mut after_end = false;
+                for c in text.chars() {
+                    if at_sentence_start && c.is_alphanumeric() {
+                        out.extend(c.to_uppercase());
+                        at_sentence_start = false;
+                        after_end = false;
+                        continue;
+                    }
+                    out.push(c);
+                    if SENTENCE_END.contains(&c) {
+                        after_end = true;
+                    } else if c == '\n' || (c.is_whitespace() && after_end) {
+                        at_sentence_start = true;
+                        after_end = false;
+                    } else if !c.is_whitespace() && !is_opening(c) {
+                        at_sentence_start = false;
+                        after_end = false;
+                    }
+                }
+                out
+            }
+        }
+    }
+}
+
+fn is_opening(c: char) -> bool {
+    matches!(c, '"' | '\'' | '(' | '[' | '“' | '‘' | '«' | '¿' | '¡')
+}
+
+/// Collapses runs of spaces, drops spaces before closing punctuation and after
+/// opening brackets, and trims every line. Line breaks are kept, at most one
+/// empty line in a row.
+pub struct WhitespaceProcessor;
+
+impl TextProcessor for WhitespaceProcessor {
+    fn name(&self) -> &'static str {
+        "whitespace"
+    }
+
+    fn process(&self, text: &str) -> String {
+        let mut lines: Vec<String> = Vec::new();
+        let mut empty_run = 0;
+
+        for line in text.split('\n') {
+            let mut out = String::with_capacity(line.len());
+            let mut pending_space = false;
+            for c in line.chars() {
+                if c.is_whitespace() {
+                    pending_space = !out.is_empty();
+                    continue;
+                }
+                if pending_space
+                    && !CLOSING_PUNCTUATION.contains(&c)
+                    && !out.ends_with(|c| matches!(c, '(' | '[' | '{'))
+                {
+                    out.push(' ');
+                }
+                pending_space = false;
+                out.push(c);
+            }
+
+            if out.is_empty() {
+                empty_run += 1;
+                if empty_run > 1 {
+                    continue;
+                }
+            } else {
+                empty_run = 0;
+            }
+            lines.push(out);
+        }
+
+        lines.join("\n").trim().to_string()
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum TrailingPunctuationMode {
+    /// Strips punctuation at the end, handy for search boxes and commands
+    Remove,
+    /// Adds a period when the text doesn't already end with punctuation
+    Ensure,
+}
+
+pub struct TrailingPunctuationProcessor {
+    mode: TrailingPunctuationMode,
+}
+
+impl TrailingPunctuationProcessor {
+    pub fn new(mode: TrailingPunctuationMode) -> Self {
+        Self { mode }
+    }
+}
+
+impl TextProcessor for TrailingPunctuationProcessor {
+    fn name(&self) -> &'static str {
+        "trailing_punctuation"
+    }
+
+    fn process(&self, text: &str) -> String {
+        let trimmed = text.trim_end();
+        match self.mode {
+            TrailingPunctuationMode::Remove => trimmed
+                .trim_end_matches(|c: char| matches!(c, '.' | ',' | ';' | ':' | '!' | '?' | '…'))
+                .to_string(),
+            TrailingPunctuationMode::Ensure => {
+                if trimmed
+                    .chars()
+                    .last()
+                    .map_or(false, |c| c.is_alphanumeric())
+                {
+                    format!("{}.", trimmed)
+                } else {
+                    trimmed.to_string()
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn casing(mode: CasingMode, text: &str) -> String {
+        CasingProcessor::new(mode).process(text)
+    }
+
+    fn trailing(mode: TrailingPunctuationMode, text: &str) -> String {
+        TrailingPunctuationProcessor::new(mode).process(text)
+    }
+
+    #[test]
+    fn lower_and_upper_case() {
+        assert_eq!(casing(CasingMode::Lowercase, "Hello WORLD"), "hello world");
+        assert_eq!(casing(CasingMode::Uppercase, "Straße"), "STRASSE");
+    }
+
+    #[test]
+    fn sentence_case_capitalizes_sentence_starts() {
+        assert_eq!(
+            casing(CasingMode::Sentence, "hello. how are you? fine!"),
+            "Hello. How are you? Fine!"
+        );
+        assert_eq!(
+            casing(CasingMode::Sentence, "first line\nsecond line"),
+            "First line\nSecond line"
+        );
+        // Opening quotes and brackets are skipped, the rest is left alone
+        assert_eq!(
+            casing(CasingMode::Sentence, "ok. \"quoted\" and (aside). iPhone"),
+            "Ok. \"Quoted\" and (aside). IPhone"
+        );
+    }
+
+    #[test]
+    fn sentence_case_ignores_dots_inside_words() {
+        assert_eq!(
+            casing(
+                CasingMode::Sentence,
+                "version 3.5 is out, see example.com now"
+            ),
+            "Version 3.5 is out, see example.com now"
+        );
+    }
+
+    #[test]
+    fn whitespace_is_collapsed_and_lines_are_kept() {
+        let processor = WhitespaceProcessor;
+        assert_eq!(
+            processor.process("  hello   world  ,  ok ( yes ) \n\n\n  next  "),
+            "hello world, ok (yes)\n\nnext"
+        );
+        assert_eq!(processor.process("wait . what ?"), "wait. what?");
+        assert_eq!(processor.process(" \n "), "");
+    }
+
+    #[test]
+    fn trailing_punctuation_is_removed() {
+        assert_eq!(
+            trailing(TrailingPunctuationMode::Remove, "search this..."),
+            "search this"
+        );
+        assert_eq!(
+            trailing(TrailingPunctuationMode::Remove, "done!?  "),
+            "done"
+        );
+        assert_eq!(
+            trailing(TrailingPunctuationMode::Remove, "(aside)"),
+            "(aside)"
+        );
+    }
+
+    #[test]
+    fn trailing_period_is_ensured() {
+        assert_eq!(
+            trailing(TrailingPunctuationMode::Ensure, "hello  "),
+            "hello."
+        );
+        assert_eq!(
+            trailing(TrailingPunctuationMode::Ensure, "hello?"),
+            "hello?"
+        );
+        assert_eq!(
+            trailing(TrailingPunctuationMode::Ensure, "(aside)"),
+            "(aside)"
+        );
+        assert_eq!(trailing(TrailingPunctuationMode::Ensure, ""), "");
+    }
+}
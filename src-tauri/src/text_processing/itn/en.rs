@@ -0,0 +1,800 @@
+use super::{tokenize, InverseNormalizer, Token};
+
+const UNITS: &[&str] = &[
+    "zero", "one", "two", "three", "four", "five", "six", "seven", "eight", "nine",
+];
+const TEENS: &[&str] = &[
+    "ten",
+    "eleven",
+    "twelve",
+    "thirteen",
+    "fourteen",
+    "fifteen",
+    "sixteen",
+    "seventeen",
+    "eighteen",
+    "nineteen",
+];
+const TENS: &[&str] = &[
+    "", "", "twenty", "thirty", "forty", "fifty", "sixty", "seventy", "eighty", "ninety",
+];
+const ORDINAL_UNITS: &[&str] = &[
+    "", "first", "second", "third", "fourth", "fifth", "sixth", "seventh", "eighth", "ninth",
+];
+const ORDINAL_TEENS: &[&str] = &[
+    "tenth",
+    "eleventh",
+    "twelfth",
+    "thirteenth",
+    "fourteenth",
+    "fifteenth",
+    "sixteenth",
+    "seventeenth",
+    "eighteenth",
+    "nineteenth",
+];
+const ORDINAL_TENS: &[&str] = &[
+    "",
+    "",
+    "twentieth",
+    "thirtieth",
+    "fortieth",
+    "fiftieth",
+    "sixtieth",
+    "seventieth",
+    "eightieth",
+    "ninetieth",
+];
+const MONTHS: &[&str] = &[
+    "january",
+    "february",
+    "march",
+    "april",
+    "may",
+    "june",
+    "july",
+    "august",
+    "september",
+    "october",
+    "november",
+    "december",
+];
+
+/// Words after which "eleven thirty" is a year rather than a time.
+const YEAR_CUES: &[&str] = &["in", "since", "year"];
+
+/// Spoken unit phrases and their symbols, longest phrases first.
+const UNIT_SYMBOLS: &[(&[&str], &str)] = &[
+    (&["kilometers", "per", "hour"], "km/h"),
+    (&["kilometres", "per", "hour"], "km/h"),
+    (&["miles", "per", "hour"], "mph"),
+    (&["degrees", "celsius"], "°C"),
+    (&["degrees", "fahrenheit"], "°F"),
+    (&["kilometers"], "km"),
+    (&["kilometres"], "km"),
+    (&["kilometer"], "km"),
+    (&["kilometre"], "km"),
+    (&["meters"], "m"),
+    (&["metres"], "m"),
+    (&["meter"], "m"),
+    (&["metre"], "m"),
+    (&["centimeters"], "cm"),
+    (&["centimetres"], "cm"),
+    (&["centimeter"], "cm"),
+    (&["millimeters"], "mm"),
+    (&["millimetres"], "mm"),
+    (&["millimeter"], "mm"),
+    (&["kilograms"], "kg"),
+    (&["kilogram"], "kg"),
+    (&["kilos"], "kg"),
+    (&["grams"], "g"),
+    (&["gram"], "g"),
+    (&["milligrams"], "mg"),
+    (&["liters"], "L"),
+    (&["litres"], "L"),
+    (&["liter"], "L"),
+    (&["milliliters"], "mL"),
+    (&["millilitres"], "mL"),
+    (&["kilobytes"], "KB"),
+    (&["megabytes"], "MB"),
+    (&["gigabytes"], "GB"),
+    (&["terabytes"], "TB"),
+    (&["degrees"], "°"),
+];
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Part {
+    Units,
+    Teens,
+    Tens,
+    Hundred,
+    Scale,
+}
+
+fn index_of(list: &[&str], word: &str) -> Option<u64> {
+    list.iter()
+        .position(|w| !w.is_empty() && *w == word)
+        .map(|i| i as u64)
+}
+
+/// Classifies a number word. Returns its part of speech in a number, its
+/// value and whether it is an ordinal.
+fn number_word(word: &str) -> Option<(Part, u64, bool)> {
+    if let Some(v) = index_of(UNITS, word) {
+        return Some((Part::Units, v, false));
+    }
+    if let Some(v) = index_of(TEENS, word) {
+        return Some((Part::Teens, v + 10, false));
+    }
+    if let Some(v) = index_of(TENS, word) {
+        return Some((Part::Tens, v * 10, false));
+    }
+    if let Some(v) = index_of(ORDINAL_UNITS, word) {
+        return Some((Part::Units, v, true));
+    }
+    if let Some(v) = index_of(ORDINAL_TEENS, word) {
+        return Some((Part::Teens, v + 10, true));
+    }
+    if let Some(v) = index_of(ORDINAL_TENS, word) {
+        return Some((Part::Tens, v * 10, true));
+    }
+    match word {
+        "hundred" => Some((Part::Hundred, 100, false)),
+        "hundredth" => Some((Part::Hundred, 100, true)),
+        "thousand" => Some((Part::Scale, 1_000, false)),
+        "thousandth" => Some((Part::Scale, 1_000, true)),
+        "million" => Some((Part::Scale, 1_000_000, false)),
+        "millionth" => Some((Part::Scale, 1_000_000, true)),
+        "billion" => Some((Part::Scale, 1_000_000_000, false)),
+        "billionth" => Some((Part::Scale, 1_000_000_000, true)),
+        _ => None,
+    }
+}
+
+fn is_number_word(word: &str) -> bool {
+    number_word(word).is_some()
+}
+
+fn allowed_after(part: Part, last: Option<Part>) -> bool {
+    match part {
+        Part::Units => matches!(
+            last,
+            None | Some(Part::Tens) | Some(Part::Hundred) | Some(Part::Scale)
+        ),
+        Part::Teens | Part::Tens => {
+            matches!(last, None | Some(Part::Hundred) | Some(Part::Scale))
+        }
+        Part::Hundred => matches!(
+            last,
+            Some(Part::Units) | Some(Part::Teens) | Some(Part::Tens)
+        ),
+        Part::Scale => last.is_some(),
+    }
+}
+
+fn ordinal_suffix(value: u64) -> &'static str {
+    match (value % 10, value % 100) {
+        (_, 11..=13) => "th",
+        (1, _) => "st",
+        (2, _) => "nd",
+        (3, _) => "rd",
+        _ => "th",
+    }
+}
+
+struct Number {
+    /// Written form without ordinal suffix, e.g. "23" or "3.5"
+    digits: String,
+    /// Integer value, `None` for decimals
+    value: Option<u64>,
+    ordinal: bool,
+    /// A single spoken word below ten, such as "one" or "third". These stay
+    /// words unless a rule gives them context ("one percent", "May third").
+    small: bool,
+    /// Parsed from a four digit year such as "nineteen ninety nine"
+    year: bool,
+}
+
+impl Number {
+    fn written(&self) -> String {
+        match (self.ordinal, self.value) {
+            (true, Some(value)) => format!("{}{}", self.digits, ordinal_suffix(value)),
+            _ => self.digits.clone(),
+        }
+    }
+}
+
+/// A word or a run of number words, with the punctuation around it.
+struct Item {
+    prefix: String,
+    /// The original text between prefix and suffix
+    word: String,
+    core: String,
+    suffix: String,
+    number: Option<Number>,
+}
+
+impl Item {
+    fn text(&self) -> String {
+        format!("{}{}{}", self.prefix, self.word, self.suffix)
+    }
+
+    fn int(&self) -> Option<u64> {
+        self.number
+            .as_ref()
+            .filter(|n| !n.ordinal)
+            .and_then(|n| n.value)
+    }
+}
+
+/// Whether the number starting at `tokens[i]` may be read as a year: after
+/// "in", "since" or "year", after a month name, or after the day of a date as
+/// in "May first, twenty twenty four".
+fn year_cue(tokens: &[Token], i: usize) -> bool {
+    let Some(previous) = i.checked_sub(1).map(|k| &tokens[k]) else {
+        return false;
+    };
+    if previous.suffix.is_empty() && YEAR_CUES.contains(&previous.core.as_str()) {
+        return true;
+    }
+    if is_month(previous.word, &previous.core) && previous.suffix.is_empty() {
+        return true;
+    }
+    i >= 2
+        && is_month(tokens[i - 2].word, &tokens[i - 2].core)
+        && tokens[i - 2].suffix.is_empty()
+        && is_number_word(&previous.core)
+        && matches!(previous.suffix, "" | ",")
+}
+
+/// Parses "nineteen ninety nine", "twenty twenty four" or "nineteen oh five".
+fn parse_year(tokens: &[Token]) -> Option<(u64, usize)> {
+    let century = match number_word(&tokens.first()?.core)? {
+        (Part::Teens, v, false) if v >= 11 => v,
+        (Part::Tens, 20, false) => 20,
+        _ => return None,
+    };
+    if !tokens[0].suffix.is_empty() || tokens.len() < 2 {
+        return None;
+    }
+
+    let (rest, len) = match number_word(&tokens[1].core) {
+        Some((Part::Teens, v, false)) => (v, 1),
+        Some((Part::Tens, v, false)) => {
+            match tokens[1].suffix.is_empty().then(|| tokens.get(2)).flatten() {
+                Some(next) => match number_word(&next.core) {
+                    Some((Part::Units, u, false)) if u > 0 => (v + u, 2),
+                    _ => (v, 1),
+                },
+                None => (v, 1),
+            }
+        }
+        None if tokens[1].core == "oh" && tokens[1].suffix.is_empty() => {
+            match number_word(&tokens.get(2)?.core)? {
+                (Part::Units, u, false) if u > 0 => (u, 2),
+                _ => return None,
+            }
+        }
+        _ => return None,
+    };
+
+    // "eleven thirty pm" is a time, not the year 1130
+    let followed_by_time = tokens.get(len + 1).map_or(false, |t| {
+        is_am_pm(&t.core).is_some() || t.core == "o'clock"
+    });
+    if followed_by_time {
+        return None;
+    }
+
+    Some((century * 100 + rest, len + 1))
+}
+
+/// Parses a cardinal or ordinal number, with an optional "point five"
+/// decimal part. Returns the number and how many tokens it used.
+fn parse_number(tokens: &[Token]) -> Option<(Number, usize)> {
+    let mut total: u64 = 0;
+    let mut current: u64 = 0;
+    let mut last: Option<Part> = None;
+    let mut ordinal = false;
+    let mut words = 0;
+    let mut i = 0;
+
+    while i < tokens.len() {
+        let token = &tokens[i];
+        let next_continues = tokens.get(i + 1).map_or(false, |t| {
+            number_word(&t.core).map_or(false, |(part, _, _)| allowed_after(part, last))
+        });
+
+        // "one hundred and five", "a hundred"
+        if token.core == "and"
+            && matches!(last, Some(Part::Hundred) | Some(Part::Scale))
+            && token.suffix.is_empty()
+            && next_continues
+        {
+            i += 1;
+            continue;
+        }
+        if token.core == "a"
+            && last.is_none()
+            && token.suffix.is_empty()
+            && tokens.get(i + 1).map_or(false, |t| {
+                matches!(
+                    number_word(&t.core),
+                    Some((Part::Hundred | Part::Scale, _, _))
+                )
+            })
+        {
+            current = 1;
+            last = Some(Part::Units);
+            i += 1;
+            continue;
+        }
+
+        let (part, value, is_ordinal) = match number_word(&token.core) {
+            Some(found) => found,
+            None => break,
+        };
+        if !allowed_after(part, last) {
+            break;
+        }
+
+        match part {
+            Part::Units | Part::Teens | Part::Tens => current += value,
+            Part::Hundred => current *= 100,
+            Part::Scale => {
+                total += current * value;
+                current = 0;
+            }
+        }
+        last = Some(part);
+        words += 1;
+        i += 1;
+
+        if is_ordinal {
+            ordinal = true;
+            break;
+        }
+        if !token.suffix.is_empty() {
+            break;
+        }
+    }
+
+    if words == 0 {
+        return None;
+    }
+    let value = total + current;
+    let mut number = Number {
+        digits: value.to_string(),
+        value: Some(value),
+        ordinal,
+        small: words == 1 && value < 10,
+        year: false,
+    };
+
+    // "three point one four"
+    let ended_clean = tokens[i - 1].suffix.is_empty();
+    if !ordinal && ended_clean && tokens.get(i).map_or(false, |t| t.core == "point") {
+        let mut decimals = String::new();
+        let mut j = i + 1;
+        while let Some(token) = tokens.get(j) {
+            let digit = match token.core.as_str() {
+                "oh" => Some(0),
+                core => match number_word(core) {
+                    Some((Part::Units, v, false)) => Some(v),
+                    _ => None,
+                },
+            };
+            match digit {
+                Some(d) => decimals.push_str(&d.to_string()),
+                None => break,
+            }
+            j += 1;
+            if !token.suffix.is_empty() {
+                break;
+            }
+        }
+        if !decimals.is_empty() && tokens[i].suffix.is_empty() {
+            number.digits = format!("{}.{}", value, decimals);
+            number.value = None;
+            number.small = false;
+            i = j;
+        }
+    }
+
+    Some((number, i))
+}
+
+/// Reads a number written with digits, e.g. "5", "1,000" or "3.5".
+fn parse_digits(core: &str) -> Option<Number> {
+    if !core.chars().next()?.is_ascii_digit() {
+        return None;
+    }
+    if !core
+        .chars()
+        .all(|c| c.is_ascii_digit() || c == ',' || c == '.')
+    {
+        return None;
+    }
+    let plain: String = core.chars().filter(|c| *c != ',').collect();
+    Some(Number {
+        value: plain.parse::<u64>().ok(),
+        digits: core.to_string(),
+        ordinal: false,
+        small: false,
+        year: false,
+    })
+}
+
+/// Groups tokens into items, merging runs of number words into one item.
+fn to_items(tokens: &[Token]) -> Vec<Item> {
+    let mut items = Vec::with_capacity(tokens.len());
+    let mut i = 0;
+    while i < tokens.len() {
+        let parsed = year_cue(tokens, i)
+            .then(|| parse_year(&tokens[i..]))
+            .flatten()
+            .map(|(value, len)| {
+                let number = Number {
+                    digits: value.to_string(),
+                    value: Some(value),
+                    ordinal: false,
+                    small: false,
+                    year: true,
+                };
+                (number, len)
+            })
+            .or_else(|| parse_number(&tokens[i..]));
+
+        match parsed {
+            Some((number, len)) => {
+                let run = &tokens[i..i + len];
+                let last = &run[len - 1];
+                items.push(Item {
+                    prefix: run[0].prefix.to_string(),
+                    word: run
+                        .iter()
+                        .enumerate()
+                        .map(|(k, t)| {
+                            let mut text = String::new();
+                            if k > 0 {
+                                text.push_str(t.prefix);
+                            }
+                            text.push_str(t.word);
+                            if k < len - 1 {
+                                text.push_str(t.suffix);
+                            }
+                            text
+                        })
+                        .collect::<Vec<_>>()
+                        .join(" "),
+                    core: String::new(),
+                    suffix: last.suffix.to_string(),
+                    number: Some(number),
+                });
+                i += len;
+            }
+            None => {
+                let token = &tokens[i];
+                items.push(Item {
+                    prefix: token.prefix.to_string(),
+                    word: token.word.to_string(),
+                    core: token.core.clone(),
+                    suffix: token.suffix.to_string(),
+                    number: parse_digits(&token.core),
+                });
+                i += 1;
+            }
+        }
+    }
+    items
+}
+
+fn is_am_pm(core: &str) -> Option<&'static str> {
+    match core.replace('.', "").as_str() {
+        "am" => Some("AM"),
+        "pm" => Some("PM"),
+        _ => None,
+    }
+}
+
+fn is_month(word: &str, core: &str) -> bool {
+    // Month names are capitalized by whisper, which keeps "may" and "march"
+    // the verbs out
+    let starts_upper = word.chars().next().is_some_and(|c| c.is_uppercase());
+    starts_upper && MONTHS.contains(&core)
+}
+
+fn month_name(item: &Item) -> Option<&str> {
+    is_month(&item.word, &item.core).then_some(item.word.as_str())
+}
+
+/// Whether `items[start..start + len]` has no punctuation between items.
+fn joined(items: &[Item], start: usize, len: usize) -> bool {
+    start + len <= items.len()
+        && items[start..start + len - 1]
+            .iter()
+            .all(|i| i.suffix.is_empty())
+}
+
+fn matches_words(items: &[Item], start: usize, words: &[&str]) -> bool {
+    start + words.len() <= items.len()
+        && items[start..start + words.len()]
+            .iter()
+            .zip(words)
+            .all(|(item, word)| item.number.is_none() && item.core == *word)
+}
+
+/// Output of a rule: the written text and how many items it replaced.
+struct Rewrite {
+    text: String,
+    len: usize,
+}
+
+fn rewrite_time(items: &[Item], i: usize) -> Option<Rewrite> {
+    let hour = items[i].int().filter(|h| (1..=12).contains(h))?;
+
+    // "ten o'clock"
+    if joined(items, i, 2) && items[i + 1].core == "o'clock" {
+        return Some(Rewrite {
+            text: format!("{} o'clock", hour),
+            len: 2,
+        });
+    }
+    // "three pm"
+    if joined(items, i, 2) {
+        if let Some(period) = is_am_pm(&items[i + 1].core) {
+            return Some(Rewrite {
+                text: format!("{} {}", hour, period),
+                len: 2,
+            });
+        }
+    }
+    // "three thirty pm", "ten oh five am"
+    let (minutes, len) = if joined(items, i, 2) && items[i + 1].number.is_some() {
+        (items[i + 1].int().filter(|m| (10..=59).contains(m))?, 2)
+    } else if joined(items, i, 3) && items[i + 1].core == "oh" {
+        (items[i + 2].int().filter(|m| (1..=9).contains(m))?, 3)
+    } else {
+        return None;
+    };
+    if joined(items, i, len + 1) {
+        if let Some(period) = is_am_pm(&items[i + len].core) {
+            return Some(Rewrite {
+                text: format!("{}:{:02} {}", hour, minutes, period),
+                len: len + 1,
+            });
+        }
+    }
+
+    // "at eleven thirty" is a time, a bare "twelve fifteen" could be anything
+    // and keeps its words
+    let after_at = i.checked_sub(1).map(|k| &items[k]).is_some_and(|item| {
+        item.suffix.is_empty() && item.number.is_none() && matches!(item.core.as_str(), "at" | "by")
+    });
+    let text = if after_at {
+        format!("{}:{:02}", hour, minutes)
+    } else {
+        let mut text = items[i].word.clone();
+        for item in &items[i + 1..i + len] {
+            text.push(' ');
+            text.push_str(&item.prefix);
+            text.push_str(&item.word);
+        }
+        text
+    };
+    Some(Rewrite { text, len })
+}
+
+fn day_of(item: &Item) -> Option<u64> {
+    let number = item.number.as_ref()?;
+    number
+        .value
+        .filter(|d| (1..=31).contains(d) && !number.year)
+}
+
+fn rewrite_date(items: &[Item], i: usize) -> Option<Rewrite> {
+    // "May first", "May first, twenty twenty four"
+    if let Some(month) = month_name(&items[i]) {
+        if !joined(items, i, 2) {
+            return None;
+        }
+        let day = day_of(&items[i + 1])?;
+        let year = items.get(i + 2).and_then(|item| {
+            let day_suffix = items[i + 1].suffix.as_str();
+            let number = item.number.as_ref()?;
+            let value = number.value?;
+            (!number.ordinal
+                && (day_suffix.is_empty() || day_suffix == ",")
+                && (1000..=2999).contains(&value))
+            .then_some(value)
+        });
+        return Some(match year {
+            Some(year) => Rewrite {
+                text: format!("{} {}, {}", month, day, year),
+                len: 3,
+            },
+            None => Rewrite {
+                text: format!("{} {}", month, day),
+                len: 2,
+            },
+        });
+    }
+
+    // "first of May"
+    let number = items[i].number.as_ref()?;
+    if number.ordinal && joined(items, i, 3) && items[i + 1].core == "of" {
+        let day = day_of(&items[i])?;
+        let month = month_name(&items[i + 2])?;
+        return Some(Rewrite {
+            text: format!("{} {}", month, day),
+            len: 3,
+        });
+    }
+    None
+}
+
+fn rewrite_amount(items: &[Item], i: usize) -> Option<Rewrite> {
+    let number = items[i].number.as_ref().filter(|n| !n.ordinal)?;
+    let amount = number.digits.clone();
+
+    if !joined(items, i, 2) {
+        return None;
+    }
+
+    // "five percent", "five per cent"
+    if matches_words(items, i + 1, &["percent"]) {
+        return Some(Rewrite {
+            text: format!("{}%", amount),
+            len: 2,
+        });
+    }
+    if joined(items, i, 3) && matches_words(items, i + 1, &["per", "cent"]) {
+        return Some(Rewrite {
+            text: format!("{}%", amount),
+            len: 3,
+        });
+    }
+
+    // "five dollars and twenty cents"
+    let currency = match items[i + 1].core.as_str() {
+        "dollar" | "dollars" if items[i + 1].number.is_none() => Some("$"),
+        "euro" | "euros" if items[i + 1].number.is_none() => Some("€"),
+        _ => None,
+    };
+    if let Some(symbol) = currency {
+        if joined(items, i, 5)
+            && items[i + 2].core == "and"
+            && matches_words(items, i + 4, &["cents"])
+        {
+            if let Some(cents) = items[i + 3].int().filter(|c| *c < 100) {
+                return Some(Rewrite {
+                    text: format!("{}{}.{:02}", symbol, amount, cents),
+                    len: 5,
+                });
+            }
+        }
+        return Some(Rewrite {
+            text: format!("{}{}", symbol, amount),
+            len: 2,
+        });
+    }
+
+    // "five kilometers"
+    for (words, symbol) in UNIT_SYMBOLS {
+        if joined(items, i, words.len() + 1) && matches_words(items, i + 1, words) {
+            // "20°C" but "5 km"
+            let separator = if symbol.starts_with('°') { "" } else { " " };
+            return Some(Rewrite {
+                text: format!("{}{}{}", amount, separator, symbol),
+                len: words.len() + 1,
+            });
+        }
+    }
+
+    None
+}
+
+/// English inverse text normalization.
+pub struct EnglishNormalizer;
+
+impl InverseNormalizer for EnglishNormalizer {
+    fn normalize(&self, text: &str) -> String {
+        let tokens = tokenize(text, |word| word.split('-').all(is_number_word));
+        let items = to_items(&tokens);
+
+        let mut out: Vec<String> = Vec::with_capacity(items.len());
+        let mut i = 0;
+        while i < items.len() {
+            let rewrite = rewrite_time(&items, i)
+                .or_else(|| rewrite_date(&items, i))
+                .or_else(|| rewrite_amount(&items, i));
+
+            match rewrite {
+                Some(Rewrite { text, len }) => {
+                    // "the first of May" becomes "May 1"
+                    if items[i].number.is_some()
+                        && len == 3
+                        && items[i + 1].core == "of"
+                        && out.last().map_or(false, |w| w.eq_ignore_ascii_case("the"))
+                    {
+                        out.pop();
+                    }
+                    out.push(format!(
+                        "{}{}{}",
+                        items[i].prefix,
+                        text,
+                        items[i + len - 1].suffix
+                    ));
+                    i += len;
+                }
+                None => {
+                    let item = &items[i];
+                    let text = match &item.number {
+                        Some(number) if !number.small => {
+                            format!("{}{}{}", item.prefix, number.written(), item.suffix)
+                        }
+                        _ => item.text(),
+                    };
+                    out.push(text);
+                    i += 1;
+                }
+            }
+        }
+
+        out.join(" ")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn spoken_forms_are_written() {
+        let cases = [
+            // Dates
+            ("Let's meet on May first.", "Let's meet on May 1."),
+            ("May first, twenty twenty four", "May 1, 2024"),
+            ("It's due the first of May.", "It's due May 1."),
+            ("on the twenty first of March", "on March 21"),
+            // Ordinals below ten stay words without a date around them
+            ("She came second.", "She came second."),
+            ("Wait a second.", "Wait a second."),
+            ("the twenty first century", "the 21st century"),
+            // Cardinals
+            ("one hundred and five people", "105 people"),
+            ("a hundred people", "100 people"),
+            ("two thousand three hundred", "2300"),
+            ("one of them", "one of them"),
+            // Times and years
+            ("See you at eleven thirty pm.", "See you at 11:30 PM."),
+            (
+                "The castle was built in eleven thirty.",
+                "The castle was built in 1130.",
+            ),
+            ("ten oh five am", "10:05 AM"),
+            ("at ten o'clock", "at 10 o'clock"),
+            ("back in nineteen ninety nine", "back in 1999"),
+            ("since nineteen oh five", "since 1905"),
+            ("in March nineteen eighty", "in March 1980"),
+            ("the year twenty twenty", "the year 2020"),
+            ("let's meet at eleven thirty", "let's meet at 11:30"),
+            ("Done by ten oh five.", "Done by 10:05."),
+            ("at twelve fifteen pm", "at 12:15 PM"),
+            // Without a cue two numbers are neither a year nor a time
+            ("twelve fifteen", "twelve fifteen"),
+            ("Call me at eleven thirty, okay?", "Call me at 11:30, okay?"),
+            // Decimals, amounts and units
+            ("pi is three point one four", "pi is 3.14"),
+            ("twenty three percent", "23%"),
+            ("five dollars and twenty cents", "$5.20"),
+            ("twenty degrees celsius", "20°C"),
+            ("five kilometers", "5 km"),
+            // Lowercase "may" and "march" are verbs
+            ("you may go first", "you may go first"),
+        ];
+        for (spoken, written) in cases {
+            assert_eq!(EnglishNormalizer.normalize(spoken), written, "{}", spoken);
+        }
+    }
+}
@@ -0,0 +1,122 @@
+mod en;
+
+use super::TextProcessor;
+use once_cell::sync::Lazy;
+use std::collections::HashMap;
+use std::sync::Arc;
+
+/// Rewrites spoken-form numbers, dates, times and units of one language into
+/// their written form, e.g. "twenty three percent" into "23%".
+pub trait InverseNormalizer: Send + Sync {
+    fn normalize(&self, text: &str) -> String;
+}
+
+/// Inverse normalizers by base language code. Languages without an entry are
+/// passed through unchanged.
+pub static ITN_REGISTRY: Lazy<HashMap<&'static str, Arc<dyn InverseNormalizer>>> =
+    Lazy::new(|| {
+        let mut map: HashMap<&'static str, Arc<dyn InverseNormalizer>> = HashMap::new();
+        map.insert("en", Arc::new(en::EnglishNormalizer));
+        map
+    });
+
+pub struct InverseNormalizationProcessor {
+    normalizer: Option<Arc<dyn InverseNormalizer>>,
+}
+
+impl InverseNormalizationProcessor {
+    /// `language` is a base language code such as "en".
+    pub fn new(language: &str) -> Self {
+        let normalizer = ITN_REGISTRY.get(language).cloned();
+        if normalizer.is_none() {
+            println!(
+                "No inverse text normalization rules for '{}', leaving numbers as spoken",
+                language
+            );
+        }
+        Self { normalizer }
+    }
+}
+
+impl TextProcessor for InverseNormalizationProcessor {
+    fn name(&self) -> &'static str {
+        "inverse_normalization"
+    }
+
+    fn process(&self, text: &str) -> String {
+        match &self.normalizer {
+            Some(normalizer) => text
+                .split('\n')
+                .map(|line| normalizer.normalize(line))
+                .collect::<Vec<_>>()
+                .join("\n"),
+            None => text.to_string(),
+        }
+    }
+}
+
+/// A whitespace separated word split into the punctuation around it and the
+/// lowercased word itself.
+struct Token<'a> {
+    prefix: &'a str,
+    /// The word as written, without the surrounding punctuation
+    word: &'a str,
+    /// `word` lowercased, used for matching
+    core: String,
+    suffix: &'a str,
+}
+
+/// Splits a line into tokens. Hyphenated words for which `split_hyphen`
+/// returns true, such as "twenty-three", become one token per part.
+fn tokenize<'a>(text: &'a str, split_hyphen: impl Fn(&str) -> bool) -> Vec<Token<'a>> {
+    let mut tokens = Vec::new();
+    for raw in text.split_whitespace() {
+        let start = raw
+            .char_indices()
+            .find(|(_, c)| c.is_alphanumeric())
+            .map(|(i, _)| i);
+        let start = match start {
+            Some(start) => start,
+            None => {
+                tokens.push(Token {
+                    prefix: raw,
+                    word: "",
+                    core: String::new(),
+                    suffix: "",
+                });
+                continue;
+            }
+        };
+        let end = raw
+            .char_indices()
+            .rev()
+            .find(|(_, c)| c.is_alphanumeric())
+            .map(|(i, c)| i + c.len_utf8())
+            .unwrap_or(raw.len());
+
+        let prefix = &raw[..start];
+        let core = &raw[start..end];
+        let suffix = &raw[end..];
+
+        if core.contains('-') && split_hyphen(&core.to_lowercase()) {
+            let parts: Vec<&str> = core.split('-').collect();
+            let last = parts.len() - 1;
+            for (i, part) in parts.into_iter().enumerate() {
+                tokens.push(Token {
+                    prefix: if i == 0 { prefix } else { "" },
+                    word: part,
+                    core: part.to_lowercase(),
+                    suffix: if i == last { suffix } else { "" },
+                });
+            }
+        } else {
+            tokens.push(Token {
+                prefix,
+                word: core,
+                core: core.to_lowercase(),
+                suffix,
+            });
+        }
+    }
+    tokens
+}
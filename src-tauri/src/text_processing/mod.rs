@@ -0,0 +1,328 @@
+mod custom_words;
+mod fillers;
+mod format;
+mod itn;
+mod phonetic;
+mod replace;
+mod spoken_punctuation;
+
+pub use custom_words::{custom_words_for_language, CustomWordsProcessor};
+pub use fillers::{builtin_fillers, FillerRemovalProcessor};
+pub use format::{
+    CasingMode, CasingProcessor, TrailingPunctuationMode, TrailingPunctuationProcessor,
+    WhitespaceProcessor,
+};
+pub use itn::InverseNormalizationProcessor;
+pub use replace::{RegexReplaceProcessor, ReplaceProcessor};
+pub use spoken_punctuation::{builtin_commands, SpokenCommand, SpokenPunctuationProcessor};
+
+use crate::settings::AppSettings;
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+
+/// One step of the post-processing applied to a transcript before it is
+/// pasted.
+pub trait TextProcessor: Send + Sync {
+    /// Short identifier used in logs and previews
+    fn name(&self) -> &'static str;
+    fn process(&self, text: &str) -> String;
+}
+
+/// Serialized form of a single processing step, as stored in the settings.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum ProcessorConfig {
+    /// Alias and fuzzy correction towards `AppSettings::custom_words`
+    CustomWords,
+    /// Literal find and replace
+    Replace {
+        find: String,
+        replace: String,
+        #[serde(default)]
+        case_sensitive: bool,
+        #[serde(default)]
+        whole_word: bool,
+    },
+    /// Regular expression replace, `replace` may reference groups as `$1`
+    Regex {
+        pattern: String,
+        replace: String,
+    },
+    Casing {
+        mode: CasingMode,
+    },
+    /// Collapses repeated spaces and removes spaces before punctuation
+    Whitespace,
+    TrailingPunctuation {
+        mode: TrailingPunctuationMode,
+    },
+    /// Dictated commands such as "comma" or "new line", off in literal mode
+    SpokenPunctuation,
+    /// Drops filler words like "um" and, optionally, stutters like "I I"
+    FillerRemoval {
+        #[serde(default = "default_enabled")]
+        collapse_repetitions: bool,
+    },
+    /// Writes spoken numbers, dates, times, amounts and units as digits and
+    /// symbols, e.g. "twenty three percent" as "23%"
+    InverseNormalization,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct ProcessorStep {
+    #[serde(default = "default_enabled")]
+    pub enabled: bool,
+    #[serde(flatten)]
+    pub config: ProcessorConfig,
+}
+
+fn default_enabled() -> bool {
+    true
+}
+
+/// A named, ordered list of processing steps.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct ProcessingChain {
+    pub id: String,
+    pub name: String,
+    pub steps: Vec<ProcessorStep>,
+}
+
+pub const DEFAULT_CHAIN_ID: &str = "default";
+
+/// The chain used until the user configures their own: the custom word
+/// correction that used to be hard-wired into transcription. Spoken
+/// punctuation is listed but off, "period" or "punto" are ordinary words too
+/// and only someone dictating commands wants them replaced.
+pub fn default_chain() -> ProcessingChain {
+    ProcessingChain {
+        id: DEFAULT_CHAIN_ID.to_string(),
+        name: "Default".to_string(),
+        steps: vec![
+            ProcessorStep {
+                enabled: true,
+                config: ProcessorConfig::CustomWords,
+            },
+            ProcessorStep {
+                enabled: false,
+                config: ProcessorConfig::SpokenPunctuation,
+            },
+        ],
+    }
+}
+
+/// Reduces a language setting such as "zh-Hans" to the code rule sets are
+/// keyed by. "auto" falls back to English.
+pub fn base_language(language: &str) -> &str {
+    match language.split(|c| c == '-' || c == '_').next() {
+        Some("auto") | Some("") | None => "en",
+        Some(code) => code,
+    }
+}
+
+/// Builds the processor for a step. Fails when the step is invalid, e.g. a
+/// regex that does not compile.
+pub fn build_processor(
+    config: &ProcessorConfig,
+    settings: &AppSettings,
+) -> Result<Box<dyn TextProcessor>> {
+    let processor: Box<dyn TextProcessor> = match config {
+        ProcessorConfig::CustomWords => Box::new(CustomWordsProcessor::new(
+            custom_words_for_language(settings),
+            &settings.custom_word_aliases,
+            settings.word_correction_threshold,
+            base_language(&settings.selected_language),
+        )),
+        ProcessorConfig::Replace {
+            find,
+            replace,
+            case_sensitive,
+            whole_word,
+        } => Box::new(ReplaceProcessor::new(
+            find,
+            replace,
+            *case_sensitive,
+            *whole_word,
+        )?),
+        ProcessorConfig::Regex { pattern, replace } => {
+            Box::new(RegexReplaceProcessor::new(pattern, replace)?)
+        }
+        ProcessorConfig::Casing { mode } => Box::new(CasingProcessor::new(*mode)),
+        ProcessorConfig::Whitespace => Box::new(WhitespaceProcessor),
+        ProcessorConfig::TrailingPunctuation { mode } => {
+            Box::new(TrailingPunctuationProcessor::new(*mode))
+        }
+        ProcessorConfig::SpokenPunctuation => Box::new(SpokenPunctuationProcessor::new(
+            base_language(&settings.selected_language),
+            &settings.spoken_commands,
+        )),
+        ProcessorConfig::FillerRemoval {
+            collapse_repetitions,
+        } => {
+            let language = base_language(&settings.selected_language);
+            let mut fillers = builtin_fillers(language);
+            if let Some(extra) = settings.filler_words.get(language) {
+                fillers.extend(extra.iter().cloned());
+            }
+            Box::new(FillerRemovalProcessor::new(
+                &fillers,
+                *collapse_repetitions,
+                language,
+            ))
+        }
+        ProcessorConfig::InverseNormalization => Box::new(InverseNormalizationProcessor::new(
+            base_language(&settings.selected_language),
+        )),
+    };
+    Ok(processor)
+}
+
+/// Checks that every step of a chain can be built.
+pub fn validate_chain(chain: &ProcessingChain, settings: &AppSettings) -> Result<()> {
+    for (i, step) in chain.steps.iter().enumerate() {
+        build_processor(&step.config, settings)
+            .map_err(|e| anyhow::anyhow!("Step {} is invalid: {}", i + 1, e))?;
+    }
+    Ok(())
+}
+
+#[derive(Serialize, Debug, Clone)]
+pub struct ProcessedStep {
+    pub name: String,
+    pub text: String,
+}
+
+/// A ready to run chain of processors.
+pub struct TextPipeline {
+    processors: Vec<Box<dyn TextProcessor>>,
+}
+
+impl TextPipeline {
+    /// Builds the chain selected in `settings`. Invalid steps are skipped with
+    /// a warning so one bad regex doesn't stop transcripts from being pasted.
+    pub fn from_settings(settings: &AppSettings) -> Self {
+        let chain = settings
+            .processing_chains
+            .get(&settings.selected_processing_chain)
+            .cloned()
+            .unwrap_or_else(|| {
+                if settings.selected_processing_chain != DEFAULT_CHAIN_ID {
+                    eprintln!(
+                        "Processing chain '{}' not found, using the default chain",
+                        settings.selected_processing_chain
+                    );
+                }
+                default_chain()
+            });
+        Self::from_chain(&chain, settings)
+    }
+
+    pub fn from_chain(chain: &ProcessingChain, settings: &AppSettings) -> Self {
+        let processors = chain
+            .steps
+            .iter()
+            .filter(|step| step.enabled)
+            .filter(|step| {
+                // Literal mode keeps "comma" as the word instead of the mark
+                !(settings.literal_mode
+                    && matches!(step.config, ProcessorConfig::SpokenPunctuation))
+            })
+            .filter_map(|step| match build_processor(&step.config, settings) {
+                Ok(processor) => Some(processor),
+                Err(e) => {
+                    eprintln!("Skipping invalid processing step: {}", e);
+                    None
+                }
+            })
+            .collect();
+        Self { processors }
+    }
+
+    pub fn process(&self, text: &str) -> String {
+        let mut text = text.to_string();
+        for processor in &self.processors {
+            text = processor.process(&text);
+        }
+        trim_spaces(&text).to_string()
+    }
+
+    /// Like `process`, but also returns the text after every step.
+    pub fn process_with_steps(&self, text: &str) -> (String, Vec<ProcessedStep>) {
+        let mut steps = Vec::with_capacity(self.processors.len());
+        let mut current = text.to_string();
+        for processor in &self.processors {
+            current = processor.process(&current);
+            steps.push(ProcessedStep {
+                name: processor.name().to_string(),
+                text: current.clone(),
+            });
+        }
+        (trim_spaces(&current).to_string(), steps)
+    }
+}
+
+/// Trims whitespace at both ends but keeps line breaks, a dictated "new line"
+/// at the end is meant to be pasted.
+fn trim_spaces(text: &str) -> &str {
+    text.trim_matches(|c: char| c.is_whitespace() && c != '\n')
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::settings::get_default_settings;
+
+    fn process_with_default_chain(language: &str, text: &str) -> String {
+        let mut settings = get_default_settings();
+        settings.selected_language = language.to_string();
+        TextPipeline::from_settings(&settings).process(text)
+    }
+
+    #[test]
+    fn default_chain_leaves_prose_alone() {
+        let cases = [
+            ("en", "The trial period ends in May."),
+            ("en", "A colon is part of the large intestine."),
+            ("en", "Add a dash of salt."),
+            ("en", "They launched a new line of shoes."),
+            ("de", "Das ist ein wichtiger Punkt."),
+            ("es", "El punto de partida fue la coma del paciente."),
+        ];
+        for (language, text) in cases {
+            assert_eq!(process_with_default_chain(language, text), text);
+        }
+    }
+
+    #[test]
+    fn enabled_spoken_punctuation_replaces_commands() {
+        let mut settings = get_default_settings();
+        let chain = ProcessingChain {
+            id: "dictation".to_string(),
+            name: "Dictation".to_string(),
+            steps: vec![ProcessorStep {
+                enabled: true,
+                config: ProcessorConfig::SpokenPunctuation,
+            }],
+        };
+        let pipeline = TextPipeline::from_chain(&chain, &settings);
+        assert_eq!(
+            pipeline.process("hello comma world period"),
+            "hello, world."
+        );
+
+        // A line break at the end is kept, other whitespace is trimmed
+        assert_eq!(pipeline.process(" hello new line "), "hello\n");
+        assert_eq!(pipeline.process("new paragraph hello"), "\n\nhello");
+
+        // Literal mode keeps the words even when the step is on
+        settings.literal_mode = true;
+        let pipeline = TextPipeline::from_chain(&chain, &settings);
+        assert_eq!(
+            pipeline.process("hello comma world period"),
+            "hello comma world period"
+        );
+        let (output, steps) = pipeline.process_with_steps("hello new line");
+        assert_eq!(output, "hello new line");
+        assert!(steps.is_empty());
+    }
+}
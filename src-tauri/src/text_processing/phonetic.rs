@@ -0,0 +1,146 @@
+use natural::phonetics::soundex;
+use unicode_normalization::UnicodeNormalization;
+
+/// Languages written without spaces between words. Custom words are matched
+/// character by character in these.
+const UNSPACED_LANGUAGES: &[&str] = &["zh", "yue", "ja", "th", "lo", "km", "my", "bo"];
+
+/// Whether `language`, a base language code, separates words with spaces.
+pub fn separates_words(language: &str) -> bool {
+    !UNSPACED_LANGUAGES.contains(&language)
+}
+
+/// Removes accents from Latin, Greek and Cyrillic letters, so "café" and
+/// "cafe" compare equal. Marks of other scripts, such as Devanagari vowel
+/// signs, are part of the letter and kept.
+pub fn fold_accents(word: &str) -> String {
+    word.nfd()
+        .filter(|c| !('\u{0300}'..='\u{036f}').contains(c))
+        .nfc()
+        .collect()
+}
+
+/// Whether two lowercase, accent-folded words sound alike in `language`.
+/// English uses Soundex, German the Kölner Phonetik, and other languages
+/// written in Latin script fall back to Soundex. Other scripts have no
+/// phonetic comparison.
+pub fn sounds_alike(language: &str, a: &str, b: &str) -> bool {
+    if !is_latin(a) || !is_latin(b) {
+        return false;
+    }
+    match language {
+        "de" => {
+            let code = cologne_code(a);
+            !code.is_empty() && code == cologne_code(b)
+        }
+        _ => soundex(a, b),
+    }
+}
+
+fn is_latin(word: &str) -> bool {
+    !word.is_empty() && word.chars().all(|c| c.is_ascii_alphanumeric() || c == 'ß')
+}
+
+/// Kölner Phonetik code of a lowercase word.
+fn cologne_code(word: &str) -> String {
+    let chars: Vec<char> = word
+        .replace('ß', "s")
+        .chars()
+        .filter(|c| c.is_ascii_alphabetic())
+        .collect();
+
+    let mut digits: Vec<char> = Vec::with_capacity(chars.len() + 1);
+    for (i, &c) in chars.iter().enumerate() {
+        let prev = if i > 0 { Some(chars[i - 1]) } else { None };
+        let next = chars.get(i + 1).copied();
+
+        let code: &str = match c {
+            'a' | 'e' | 'i' | 'j' | 'o' | 'u' | 'y' => "0",
+            'h' => "",
+            'b' => "1",
+            'p' => {
+                if next == Some('h') {
+                    "3"
+                } else {
+                    "1"
+                }
+            }
+            'd' | 't' => {
+                if matches!(next, Some('c') | Some('s') | Some('z')) {
+                    "8"
+                } else {
+                    "2"
+                }
+            }
+            'f' | 'v' | 'w' => "3",
+            'g' | 'k' | 'q' => "4",
+            'c' => {
+                let hard_next = matches!(next, Some('a' | 'h' | 'k' | 'o' | 'q' | 'u' | 'x'));
+                match prev {
+                    None if hard_next || next == Some('l') || next == Some('r') => "4",
+                    None => "8",
+                    Some('s') | Some('z') => "8",
+                    Some(_) if hard_next => "4",
+                    Some(_) => "8",
+                }
+            }
+            'x' => {
+                if matches!(prev, Some('c') | Some('k') | Some('q')) {
+                    "8"
+                } else {
+                    "48"
+                }
+            }
+            'l' => "5",
+            'm' | 'n' => "6",
+            'r' => "7",
+            's' | 'z' => "8",
+            _ => "",
+        };
+        digits.extend(code.chars());
+    }
+
+    digits.dedup();
+    digits
+        .iter()
+        .enumerate()
+        .filter(|(i, &d)| *i == 0 || d != '0')
+        .map(|(_, &d)| d)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn cologne_codes() {
+        let cases = [
+            ("müller-lüdenscheidt", "65752682"),
+            ("wikipedia", "3412"),
+            ("breschnew", "17863"),
+            ("schmidt", "862"),
+            ("schmitt", "862"),
+            ("", ""),
+        ];
+        for (word, code) in cases {
+            assert_eq!(cologne_code(&fold_accents(word)), code, "{}", word);
+        }
+    }
+
+    #[test]
+    fn accents_are_folded_for_latin_letters_only() {
+        assert_eq!(fold_accents("café"), "cafe");
+        assert_eq!(fold_accents("Müller"), "Muller");
+        // Devanagari vowel signs are part of the letter
+        assert_eq!(fold_accents("हिंदी"), "हिंदी");
+    }
+
+    #[test]
+    fn german_uses_the_cologne_phonetics() {
+        assert!(sounds_alike("de", "meier", "mayer"));
+        assert!(!sounds_alike("de", "meier", "muller"));
+        // No phonetic comparison outside the Latin script
+        assert!(!sounds_alike("en", "привет", "привет"));
+    }
+}
@@ -0,0 +1,199 @@
+use super::TextProcessor;
+use anyhow::Result;
+use regex::{NoExpand, Regex, RegexBuilder};
+
+/// Replaces every occurrence of a literal string.
+pub struct ReplaceProcessor {
+    pattern: Regex,
+    replace: String,
+    /// Whole word matching, per side. A side is only checked when the search
+    /// text starts or ends with a word character, so "c++" or ".NET" still
+    /// match.
+    word_start: bool,
+    word_end: bool,
+}
+
+impl ReplaceProcessor {
+    pub fn new(find: &str, replace: &str, case_sensitive: bool, whole_word: bool) -> Result<Self> {
+        if find.is_empty() {
+            return Err(anyhow::anyhow!("Replacement needs a non-empty search text"));
+        }
+
+        let pattern = RegexBuilder::new(&regex::escape(find))
+            .case_insensitive(!case_sensitive)
+            .build()?;
+
+        Ok(Self {
+            pattern,
+            replace: replace.to_string(),
+            word_start: whole_word && find.chars().next().is_some_and(is_word_char),
+            word_end: whole_word && find.chars().last().is_some_and(is_word_char),
+        })
+    }
+
+    fn is_whole_word(&self, text: &str, start: usize, end: usize) -> bool {
+        let before = text[..start].chars().next_back();
+        let after = text[end..].chars().next();
+        let joins_word = (self.word_start && before.is_some_and(is_word_char))
+            || (self.word_end && after.is_some_and(is_word_char));
+        !joins_word
+    }
+}
+
+/// Same notion of a word character as `\w` in `regex`
+fn is_word_char(c: char) -> bool {
+    c.is_alphanumeric() || c == '_'
+}
+
+impl TextProcessor for ReplaceProcessor {
+    fn name(&self) -> &'static str {
+        "replace"
+    }
+
+    fn process(&self, text: &str) -> String {
+        if !self.word_start && !self.word_end {
+            // The replacement is literal text, `$` must not expand to a group
+            return self
+                .pattern
+                .replace_all(text, NoExpand(&self.replace))
+                .into_owned();
+        }
+
+        let mut out = String::with_capacity(text.len());
+        let mut copied = 0;
+        let mut at = 0;
+        while let Some(m) = self.pattern.find_at(text, at) {
+            if self.is_whole_word(text, m.start(), m.end()) {
+                out.push_str(&text[copied..m.start()]);
+                out.push_str(&self.replace);
+                copied = m.end();
+                at = m.end();
+            } else {
+                // Retry one character later, a whole word match may overlap
+                // the rejected one
+                at = m.start() + text[m.start()..].chars().next().map_or(1, char::len_utf8);
+            }
+        }
+        out.push_str(&text[copied..]);
+        out
+    }
+}
+
+/// Replaces matches of a regular expression, with `$1`/`${name}` group
+/// references in the replacement.
+pub struct RegexReplaceProcessor {
+    pattern: Regex,
+    replace: String,
+}
+
+impl RegexReplaceProcessor {
+    pub fn new(pattern: &str, replace: &str) -> Result<Self> {
+        let pattern = Regex::new(pattern).map_err(|e| anyhow::anyhow!("Invalid regex: {}", e))?;
+        Ok(Self {
+            pattern,
+            replace: replace.to_string(),
+        })
+    }
+}
+
+impl TextProcessor for RegexReplaceProcessor {
+    fn name(&self) -> &'static str {
+        "regex"
+    }
+
+    fn process(&self, text: &str) -> String {
+        self.pattern
+            .replace_all(text, self.replace.as_str())
+            .into_owned()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn replace(
+        find: &str,
+        replace: &str,
+        case_sensitive: bool,
+        whole_word: bool,
+        text: &str,
+    ) -> String {
+        ReplaceProcessor::new(find, replace, case_sensitive, whole_word)
+            .unwrap()
+            .process(text)
+    }
+
+    #[test]
+    fn literal_text_is_replaced() {
+        assert_eq!(
+            replace("teh", "the", true, false, "teh cat, tehe"),
+            "the cat, thee"
+        );
+        // Regex syntax in the search text and `$` in the replacement are literal
+        assert_eq!(replace("a.b", "$1", true, false, "a.b axb"), "$1 axb");
+        assert!(ReplaceProcessor::new("", "x", true, false).is_err());
+    }
+
+    #[test]
+    fn case_sensitivity() {
+        assert_eq!(
+            replace("handy", "Handy", false, false, "HANDY handy"),
+            "Handy Handy"
+        );
+        assert_eq!(
+            replace("handy", "Handy", true, false, "HANDY handy"),
+            "HANDY Handy"
+        );
+    }
+
+    #[test]
+    fn whole_words_only() {
+        assert_eq!(
+            replace("cat", "dog", true, true, "cat concat cats cat_ cat."),
+            "dog concat cats cat_ dog."
+        );
+        assert_eq!(
+            replace("über", "ueber", true, true, "über überall"),
+            "ueber überall"
+        );
+        // A rejected match must not hide an overlapping one
+        assert_eq!(replace("ab ab", "x", true, true, "xab ab ab"), "xab x");
+    }
+
+    #[test]
+    fn whole_words_with_punctuation_at_the_edges() {
+        assert_eq!(
+            replace("c++", "C++", false, true, "I like c++, not abc++."),
+            "I like C++, not abc++."
+        );
+        assert_eq!(
+            replace(
+                ".net",
+                ".NET",
+                false,
+                true,
+                "Use .net today, or asp.netcore"
+            ),
+            "Use .NET today, or asp.netcore"
+        );
+        assert_eq!(
+            replace(
+                "@team",
+                "@everyone",
+                true,
+                true,
+                "ping @team now, not @teams"
+            ),
+            "ping @everyone now, not @teams"
+        );
+        assert_eq!(replace("c++", "C++", false, true, "c++"), "C++");
+    }
+
+    #[test]
+    fn regex_replace_expands_groups() {
+        let processor = RegexReplaceProcessor::new(r"(\d+) ?pct", "$1%").unwrap();
+        assert_eq!(processor.process("20 pct and 5pct"), "20% and 5%");
+        assert!(RegexReplaceProcessor::new("(", "").is_err());
+    }
+}
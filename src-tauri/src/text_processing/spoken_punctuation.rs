@@ -0,0 +1,448 @@
+use super::TextProcessor;
+use serde::{Deserialize, Serialize};
+
+/// What a spoken command turns into.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum SpokenAction {
+    /// Attaches to the previous word, e.g. "comma" or "close quote"
+    Punctuation {
+        text: String,
+    },
+    /// Attaches to the following word, e.g. "open quote"
+    Open {
+        text: String,
+    },
+    /// Inserted as a word of its own, e.g. "ampersand"
+    Insert {
+        text: String,
+    },
+    NewLine,
+    NewParagraph,
+    CapitalizeNext,
+    UppercaseNext,
+    LowercaseNext,
+}
+
+/// A phrase that is replaced by an action when it is dictated.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct SpokenCommand {
+    pub phrase: String,
+    pub action: SpokenAction,
+    /// Language the command applies to, every language when unset
+    #[serde(default)]
+    pub language: Option<String>,
+}
+
+/// Sentence marks whisper tends to guess itself. When one of these is dictated
+/// it replaces whatever whisper already put after the previous word.
+const SENTENCE_MARKS: &[char] = &[',', '.', ';', ':', '!', '?'];
+
+fn punct(text: &str) -> SpokenAction {
+    SpokenAction::Punctuation {
+        text: text.to_string(),
+    }
+}
+
+fn open(text: &str) -> SpokenAction {
+    SpokenAction::Open {
+        text: text.to_string(),
+    }
+}
+
+fn insert(text: &str) -> SpokenAction {
+    SpokenAction::Insert {
+        text: text.to_string(),
+    }
+}
+
+/// Built-in commands for a base language code such as "en" or "de".
+pub fn builtin_commands(language: &str) -> Vec<SpokenCommand> {
+    let rules: Vec<(&str, SpokenAction)> = match language {
+        "en" => vec![
+            ("comma", punct(",")),
+            ("period", punct(".")),
+            ("full stop", punct(".")),
+            ("question mark", punct("?")),
+            ("exclamation mark", punct("!")),
+            ("exclamation point", punct("!")),
+            ("colon", punct(":")),
+            ("semicolon", punct(";")),
+            ("ellipsis", punct("...")),
+            ("dash", insert("-")),
+            ("ampersand", insert("&")),
+            ("open quote", open("\"")),
+            ("close quote", punct("\"")),
+            ("end quote", punct("\"")),
+            ("unquote", punct("\"")),
+            ("open paren", open("(")),
+            ("open parenthesis", open("(")),
+            ("close paren", punct(")")),
+            ("close parenthesis", punct(")")),
+            ("new line", SpokenAction::NewLine),
+            ("new paragraph", SpokenAction::NewParagraph),
+            ("cap next word", SpokenAction::CapitalizeNext),
+            ("capitalize next word", SpokenAction::CapitalizeNext),
+            ("all caps next word", SpokenAction::UppercaseNext),
+            ("lowercase next word", SpokenAction::LowercaseNext),
+        ],
+        "de" => vec![
+            ("komma", punct(",")),
+            ("punkt", punct(".")),
+            ("fragezeichen", punct("?")),
+            ("ausrufezeichen", punct("!")),
+            ("doppelpunkt", punct(":")),
+            ("semikolon", punct(";")),
+            ("bindestrich", insert("-")),
+            ("anführungszeichen auf", open("„")),
+            ("anführungszeichen zu", punct("“")),
+            ("klammer auf", open("(")),
+            ("klammer zu", punct(")")),
+            ("neue zeile", SpokenAction::NewLine),
+            ("neuer absatz", SpokenAction::NewParagraph),
+            ("nächstes wort groß", SpokenAction::CapitalizeNext),
+            (
+                "nächstes wort in großbuchstaben",
+                SpokenAction::UppercaseNext,
+            ),
+            ("nächstes wort klein", SpokenAction::LowercaseNext),
+        ],
+        "fr" => vec![
+            ("virgule", punct(",")),
+            ("point final", punct(".")),
+            ("point d'interrogation", punct("?")),
+            ("point d'exclamation", punct("!")),
+            ("deux points", punct(":")),
+            ("point virgule", punct(";")),
+            ("tiret", insert("-")),
+            ("ouvrez les guillemets", open("«")),
+            ("fermez les guillemets", punct("»")),
+            ("ouvrez la parenthèse", open("(")),
+            ("fermez la parenthèse", punct(")")),
+            ("à la ligne", SpokenAction::NewLine),
+            ("nouvelle ligne", SpokenAction::NewLine),
+            ("nouveau paragraphe", SpokenAction::NewParagraph),
+            ("majuscule au mot suivant", SpokenAction::CapitalizeNext),
+            ("tout en majuscules", SpokenAction::UppercaseNext),
+        ],
+        "es" => vec![
+            ("coma", punct(",")),
+            ("punto", punct(".")),
+            ("punto y coma", punct(";")),
+            ("dos puntos", punct(":")),
+            ("signo de interrogación", punct("?")),
+            ("signo de exclamación", punct("!")),
+            ("guion", insert("-")),
+            ("abrir comillas", open("\"")),
+            ("cerrar comillas", punct("\"")),
+            ("abrir paréntesis", open("(")),
+            ("cerrar paréntesis", punct(")")),
+            ("nueva línea", SpokenAction::NewLine),
+            ("nuevo párrafo", SpokenAction::NewParagraph),
+            ("mayúscula siguiente palabra", SpokenAction::CapitalizeNext),
+            (
+                "todo mayúsculas siguiente palabra",
+                SpokenAction::UppercaseNext,
+            ),
+        ],
+        _ => Vec::new(),
+    };
+
+    rules
+        .into_iter()
+        .map(|(phrase, action)| SpokenCommand {
+            phrase: phrase.to_string(),
+            action,
+            language: Some(language.to_string()),
+        })
+        .collect()
+}
+
+/// Lowercases a word and drops the punctuation whisper attached to it, so
+/// "Comma," matches the command "comma".
+fn normalize(word: &str) -> String {
+    word.chars()
+        .filter(|c| c.is_alphanumeric())
+        .flat_map(|c| c.to_lowercase())
+        .collect()
+}
+
+#[derive(Clone, Copy)]
+enum CaseChange {
+    Capitalize,
+    Upper,
+    Lower,
+}
+
+fn apply_case(word: &str, change: CaseChange) -> String {
+    match change {
+        CaseChange::Upper => word.to_uppercase(),
+        CaseChange::Lower => word.to_lowercase(),
+        CaseChange::Capitalize => {
+            let mut out = String::with_capacity(word.len());
+            let mut done = false;
+            for c in word.chars() {
+                if !done && c.is_alphabetic() {
+                    out.extend(c.to_uppercase());
+                    done = true;
+                } else {
+                    out.push(c);
+                }
+            }
+            out
+        }
+    }
+}
+
+/// Appends a word, separated by a space unless the previous output asked the
+/// next word to attach to it.
+fn push_word(out: &mut String, word: &str, attach_next: &mut bool) {
+    if !out.is_empty() && !*attach_next {
+        out.push(' ');
+    }
+    out.push_str(word);
+    *attach_next = false;
+}
+
+/// Turns dictated formatting commands into the characters and case changes
+/// they stand for.
+pub struct SpokenPunctuationProcessor {
+    /// Commands as normalized words, longest first so "new paragraph" wins
+    /// over a hypothetical "new"
+    commands: Vec<(Vec<String>, SpokenAction)>,
+}
+
+impl SpokenPunctuationProcessor {
+    /// `language` is a base language code. User commands for that language
+    /// (or for every language) come before the built-in ones.
+    pub fn new(language: &str, user_commands: &[SpokenCommand]) -> Self {
+        let mut commands: Vec<(Vec<String>, SpokenAction)> = user_commands
+            .iter()
+            .filter(|c| c.language.as_deref().map_or(true, |l| l == language))
+            .cloned()
+            .chain(builtin_commands(language))
+            .map(|c| {
+                let words: Vec<String> = c
+                    .phrase
+                    .split_whitespace()
+                    .map(normalize)
+                    .filter(|w| !w.is_empty())
+                    .collect();
+                (words, c.action)
+            })
+            .filter(|(words, _)| !words.is_empty())
+            .collect();
+
+        // Stable, so user commands keep priority among phrases of equal length
+        commands.sort_by(|a, b| b.0.len().cmp(&a.0.len()));
+
+        Self { commands }
+    }
+
+    fn match_at(&self, words: &[String]) -> Option<(usize, &SpokenAction)> {
+        self.commands
+            .iter()
+            .find(|(phrase, _)| words.len() >= phrase.len() && words[..phrase.len()] == phrase[..])
+            .map(|(phrase, action)| (phrase.len(), action))
+    }
+}
+
+impl TextProcessor for SpokenPunctuationProcessor {
+    fn name(&self) -> &'static str {
+        "spoken_punctuation"
+    }
+
+    fn process(&self, text: &str) -> String {
+        if self.commands.is_empty() {
+            return text.to_string();
+        }
+
+        let words: Vec<&str> = text.split_whitespace().collect();
+        let normalized: Vec<String> = words.iter().map(|w| normalize(w)).collect();
+
+        let mut out = String::with_capacity(text.len());
+        // Set after line breaks and opening marks: no space before the next word
+        let mut attach_next = false;
+        let mut case_next: Option<CaseChange> = None;
+
+        let mut i = 0;
+        while i < words.len() {
+            let (len, action) = match self.match_at(&normalized[i..]) {
+                Some(found) => found,
+                None => {
+                    let word = match case_next.take() {
+                        Some(change) => apply_case(words[i], change),
+                        None => words[i].to_string(),
+                    };
+                    push_word(&mut out, &word, &mut attach_next);
+                    i += 1;
+                    continue;
+                }
+            };
+            i += len;
+
+            match action {
+                SpokenAction::Punctuation { text } => {
+                    let trimmed_len = out.trim_end().len();
+                    out.truncate(trimmed_len);
+                    if text.starts_with(SENTENCE_MARKS) {
+                        let kept_len = out.trim_end_matches(SENTENCE_MARKS).len();
+                        out.truncate(kept_len);
+                    }
+                    out.push_str(text);
+                    attach_next = false;
+                }
+                SpokenAction::Open { text } => {
+                    push_word(&mut out, text, &mut attach_next);
+                    attach_next = true;
+                }
+                SpokenAction::Insert { text } => push_word(&mut out, text, &mut attach_next),
+                SpokenAction::NewLine | SpokenAction::NewParagraph => {
+                    let trimmed_len = out.trim_end_matches(' ').len();
+                    out.truncate(trimmed_len);
+                    out.push('\n');
+                    if *action == SpokenAction::NewParagraph {
+                        out.push('\n');
+                    }
+                    attach_next = true;
+                }
+                SpokenAction::CapitalizeNext => case_next = Some(CaseChange::Capitalize),
+                SpokenAction::UppercaseNext => case_next = Some(CaseChange::Upper),
+                SpokenAction::LowercaseNext => case_next = Some(CaseChange::Lower),
+            }
+        }
+
+        out
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn dictate(language: &str, text: &str) -> String {
+        SpokenPunctuationProcessor::new(language, &[]).process(text)
+    }
+
+    fn command(phrase: &str, action: SpokenAction, language: Option<&str>) -> SpokenCommand {
+        SpokenCommand {
+            phrase: phrase.to_string(),
+            action,
+            language: language.map(str::to_string),
+        }
+    }
+
+    #[test]
+    fn punctuation_attaches_to_the_previous_word() {
+        assert_eq!(
+            dictate("en", "hello comma world exclamation mark"),
+            "hello, world!"
+        );
+        assert_eq!(
+            dictate("en", "one semicolon two dash three"),
+            "one; two - three"
+        );
+        // The mark whisper guessed is replaced by the dictated one
+        assert_eq!(dictate("en", "Wait. Question mark."), "Wait?");
+        assert_eq!(dictate("en", "Hello, Period."), "Hello.");
+    }
+
+    #[test]
+    fn quotes_and_brackets_pair_up() {
+        assert_eq!(
+            dictate("en", "he said open quote hello there close quote and left"),
+            "he said \"hello there\" and left"
+        );
+        assert_eq!(
+            dictate("en", "call me open paren maybe close paren period"),
+            "call me (maybe)."
+        );
+    }
+
+    #[test]
+    fn line_and_paragraph_breaks() {
+        assert_eq!(
+            dictate("en", "first line new line second line"),
+            "first line\nsecond line"
+        );
+        assert_eq!(dictate("en", "one. New paragraph. two"), "one.\n\ntwo");
+        assert_eq!(dictate("en", "done new line"), "done\n");
+    }
+
+    #[test]
+    fn case_commands_change_the_next_word() {
+        assert_eq!(
+            dictate("en", "cap next word paris is nice"),
+            "Paris is nice"
+        );
+        assert_eq!(dictate("en", "all caps next word nasa rocks"), "NASA rocks");
+        assert_eq!(
+            dictate("en", "lowercase next word IPHONE sales"),
+            "iphone sales"
+        );
+        assert_eq!(
+            dictate("en", "open quote cap next word yes close quote"),
+            "\"Yes\""
+        );
+    }
+
+    #[test]
+    fn user_commands_take_priority() {
+        let commands = [
+            command("smiley", insert(":)"), None),
+            command("dash", punct("—"), Some("en")),
+            command("neuer satz", punct("."), Some("de")),
+        ];
+        let processor = SpokenPunctuationProcessor::new("en", &commands);
+        assert_eq!(processor.process("nice smiley"), "nice :)");
+        assert_eq!(processor.process("wait dash what"), "wait— what");
+        // Commands for another language are ignored
+        assert_eq!(processor.process("neuer satz"), "neuer satz");
+    }
+
+    #[test]
+    fn german_rules() {
+        assert_eq!(
+            dictate("de", "hallo komma wie geht's fragezeichen"),
+            "hallo, wie geht's?"
+        );
+        assert_eq!(
+            dictate(
+                "de",
+                "er sagte anführungszeichen auf ja anführungszeichen zu"
+            ),
+            "er sagte „ja“"
+        );
+        assert_eq!(dictate("de", "eins neue zeile zwei"), "eins\nzwei");
+    }
+
+    #[test]
+    fn french_rules() {
+        assert_eq!(
+            dictate("fr", "bonjour virgule ça va point d'interrogation"),
+            "bonjour, ça va?"
+        );
+        assert_eq!(dictate("fr", "fin à la ligne suite"), "fin\nsuite");
+        assert_eq!(
+            dictate("fr", "ouvrez les guillemets oui fermez les guillemets"),
+            "«oui»"
+        );
+    }
+
+    #[test]
+    fn spanish_rules() {
+        assert_eq!(
+            dictate("es", "hola coma qué tal signo de interrogación"),
+            "hola, qué tal?"
+        );
+        // The longer phrase wins over "punto"
+        assert_eq!(dictate("es", "uno punto y coma dos punto"), "uno; dos.");
+        assert_eq!(dictate("es", "fin nuevo párrafo otro"), "fin\n\notro");
+    }
+
+    #[test]
+    fn languages_without_rules_are_left_alone() {
+        assert!(builtin_commands("ja").is_empty());
+        assert_eq!(dictate("ja", "hello comma world"), "hello comma world");
+    }
+}
@@ -51,6 +51,18 @@ pub fn change_tray_icon(app: &AppHandle, icon: TrayIconState) {
     update_tray_menu(app, &icon);
 }
 
+/// Shows how many recordings are still waiting to be transcribed.
+pub fn update_tray_queue_depth(app: &AppHandle, depth: usize) {
+    let tray = app.state::<TrayIcon>();
+    if depth > 0 {
+        let _ = tray.set_tooltip(Some(format!("Handy ({} pending)", depth)));
+        let _ = tray.set_title(Some(depth.to_string()));
+    } else {
+        let _ = tray.set_tooltip(Some("Handy"));
+        let _ = tray.set_title(None::<&str>);
+    }
+}
+
 pub fn update_tray_menu(app: &AppHandle, state: &TrayIconState) {
     // Platform-specific accelerators
     #[cfg(target_os = "macos")]
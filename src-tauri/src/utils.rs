@@ -1,5 +1,6 @@
-use crate::actions::ACTION_MAP;
+use crate::actions::action_for_binding;
 use crate::managers::audio::AudioRecordingManager;
+use crate::managers::transcription::TranscriptionManager;
 use crate::ManagedToggleState;
 use std::sync::Arc;
 use tauri::{AppHandle, Manager};
@@ -31,7 +32,7 @@ pub fn cancel_current_operation(app: &AppHandle) {
             println!("Stopping active action for binding: {}", binding_id);
 
             // Call the action's stop method to ensure proper cleanup
-            if let Some(action) = ACTION_MAP.get(&binding_id) {
+            if let Some(action) = action_for_binding(app, &binding_id) {
                 action.stop(app, &binding_id, "cancelled");
             }
 
@@ -48,6 +49,11 @@ pub fn cancel_current_operation(app: &AppHandle) {
     let audio_manager = app.state::<Arc<AudioRecordingManager>>();
     audio_manager.cancel_recording();
 
+    // Discard any partially streamed transcription and abort a running decode
+    let transcription_manager = app.state::<Arc<TranscriptionManager>>();
+    transcription_manager.cancel_stream();
+    transcription_manager.cancel_transcription();
+
     // Update tray icon and menu to idle state
     change_tray_icon(app, crate::tray::TrayIconState::Idle);
 
@@ -1,7 +1,7 @@
 import React, { useState, useRef, useEffect } from "react";
 import { invoke } from "@tauri-apps/api/core";
 import { listen } from "@tauri-apps/api/event";
-import { ModelInfo } from "../../lib/types";
+import { ModelInfo, TranscriptionModelStatus } from "../../lib/types";
 import ModelStatusButton from "./ModelStatusButton";
 import ModelDropdown from "./ModelDropdown";
 import DownloadProgressDisplay from "./DownloadProgressDisplay";
@@ -192,10 +192,11 @@ const ModelSelector: React.FC<ModelSelectorProps> = ({ onError }) => {
 
       if (current) {
         // Check if model is actually loaded
-        const transcriptionStatus = await invoke<string | null>(
-          "get_transcription_model_status",
-        );
-        if (transcriptionStatus === current) {
+        const transcriptionStatus =
+          await invoke<TranscriptionModelStatus | null>(
+            "get_transcription_model_status",
+          );
+        if (transcriptionStatus?.model_id === current) {
           setModelStatus("ready");
         } else {
           setModelStatus("loading");
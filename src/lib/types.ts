@@ -6,6 +6,102 @@ export const ShortcutBindingSchema = z.object({
   description: z.string(),
   default_binding: z.string(),
   current_binding: z.string(),
+  action: z.string().nullable().optional(),
+  profile_id: z.string().nullable().optional(),
+});
+
+export const DecoderSettingsSchema = z.object({
+  strategy: z.enum(["greedy", "beam_search"]).optional().default("greedy"),
+  beam_size: z.number().optional().default(5),
+  temperature: z.number().optional().default(0),
+  temperature_increment: z.number().optional().default(0.2),
+  threads: z.number().optional().default(0),
+  no_speech_threshold: z.number().optional().default(0.2),
+  use_gpu: z.boolean().optional().default(true),
+  flash_attention: z.boolean().optional().default(false),
+});
+
+export const LlmSettingsSchema = z.object({
+  endpoint: z.string().optional().default("http://localhost:11434/v1"),
+  model: z.string().optional().default(""),
+  api_key: z.string().nullable().optional(),
+  timeout_seconds: z.number().optional().default(10),
+});
+
+export const RefinementPromptSchema = z.object({
+  id: z.string(),
+  name: z.string(),
+  template: z.string(),
+});
+
+export const TranscriptionProfileSchema = z.object({
+  id: z.string(),
+  name: z.string(),
+  selected_model: z.string().nullable().optional(),
+  selected_language: z.string().nullable().optional(),
+  translate_to_english: z.boolean().nullable().optional(),
+  push_to_talk: z.boolean().nullable().optional(),
+  processing_chain: z.string().nullable().optional(),
+  decoder: DecoderSettingsSchema.nullable().optional(),
+  refinement_prompt: z.string().nullable().optional(),
+});
+
+export const ProcessorStepSchema = z
+  .object({ enabled: z.boolean().optional().default(true) })
+  .and(
+    z.discriminatedUnion("type", [
+      z.object({ type: z.literal("custom_words") }),
+      z.object({
+        type: z.literal("replace"),
+        find: z.string(),
+        replace: z.string(),
+        case_sensitive: z.boolean().optional().default(false),
+        whole_word: z.boolean().optional().default(false),
+      }),
+      z.object({
+        type: z.literal("regex"),
+        pattern: z.string(),
+        replace: z.string(),
+      }),
+      z.object({
+        type: z.literal("casing"),
+        mode: z.enum(["lowercase", "uppercase", "sentence"]),
+      }),
+      z.object({ type: z.literal("whitespace") }),
+      z.object({
+        type: z.literal("trailing_punctuation"),
+        mode: z.enum(["remove", "ensure"]),
+      }),
+      z.object({ type: z.literal("spoken_punctuation") }),
+      z.object({
+        type: z.literal("filler_removal"),
+        collapse_repetitions: z.boolean().optional().default(true),
+      }),
+      z.object({ type: z.literal("inverse_normalization") }),
+    ]),
+  );
+
+export const SpokenActionSchema = z.discriminatedUnion("kind", [
+  z.object({ kind: z.literal("punctuation"), text: z.string() }),
+  z.object({ kind: z.literal("open"), text: z.string() }),
+  z.object({ kind: z.literal("insert"), text: z.string() }),
+  z.object({ kind: z.literal("new_line") }),
+  z.object({ kind: z.literal("new_paragraph") }),
+  z.object({ kind: z.literal("capitalize_next") }),
+  z.object({ kind: z.literal("uppercase_next") }),
+  z.object({ kind: z.literal("lowercase_next") }),
+]);
+
+export const SpokenCommandSchema = z.object({
+  phrase: z.string(),
+  action: SpokenActionSchema,
+  language: z.string().nullable().optional(),
+});
+
+export const ProcessingChainSchema = z.object({
+  id: z.string(),
+  name: z.string(),
+  steps: z.array(ProcessorStepSchema),
 });
 
 export const ShortcutBindingsMapSchema = z.record(
@@ -32,10 +128,57 @@ export const SettingsSchema = z.object({
   selected_output_device: z.string().nullable().optional(),
   translate_to_english: z.boolean(),
   selected_language: z.string(),
+  auto_detect_languages: z.array(z.string()).optional().default([]),
+  favorite_languages: z.array(z.string()).optional().default([]),
   overlay_position: OverlayPositionSchema,
   debug_mode: z.boolean(),
   custom_words: z.array(z.string()).optional().default([]),
+  language_custom_words: z
+    .record(z.string(), z.array(z.string()))
+    .optional()
+    .default({}),
+  custom_word_aliases: z
+    .record(z.string(), z.array(z.string()))
+    .optional()
+    .default({}),
   word_correction_threshold: z.number().optional().default(0.18),
+  streaming_transcription: z.boolean().optional().default(false),
+  initial_prompt_template: z.string().optional().default("{context} {words}"),
+  prompt_context_length: z.number().optional().default(0),
+  profiles: z
+    .record(z.string(), TranscriptionProfileSchema)
+    .optional()
+    .default({}),
+  model_unload_timeout_minutes: z.number().optional().default(0),
+  low_memory_unload_threshold_mb: z.number().optional().default(0),
+  max_decode_seconds: z.number().optional().default(300),
+  processing_chains: z
+    .record(z.string(), ProcessingChainSchema)
+    .optional()
+    .default({}),
+  selected_processing_chain: z.string().optional().default("default"),
+  spoken_commands: z.array(SpokenCommandSchema).optional().default([]),
+  literal_mode: z.boolean().optional().default(false),
+  filler_words: z
+    .record(z.string(), z.array(z.string()))
+    .optional()
+    .default({}),
+  hallucination_filter: z.boolean().optional().default(true),
+  hallucination_blocklist: z.array(z.string()).optional().default([]),
+  model_decoder_settings: z
+    .record(z.string(), DecoderSettingsSchema)
+    .optional()
+    .default({}),
+  force_cpu: z.boolean().optional().default(false),
+  llm: LlmSettingsSchema.optional().default({}),
+  refinement_prompts: z
+    .record(z.string(), RefinementPromptSchema)
+    .optional()
+    .default({}),
+  selected_refinement_prompt: z.string().nullable().optional(),
+  api_server_enabled: z.boolean().optional().default(false),
+  api_server_port: z.number().optional().default(5557),
+  api_server_token: z.string().optional().default(""),
 });
 
 export const BindingResponseSchema = z.object({
@@ -49,6 +192,37 @@ export type BindingResponse = z.infer<typeof BindingResponseSchema>;
 export type ShortcutBinding = z.infer<typeof ShortcutBindingSchema>;
 export type ShortcutBindingsMap = z.infer<typeof ShortcutBindingsMapSchema>;
 export type Settings = z.infer<typeof SettingsSchema>;
+export type DecoderSettings = z.infer<typeof DecoderSettingsSchema>;
+export type LlmSettings = z.infer<typeof LlmSettingsSchema>;
+export type RefinementPrompt = z.infer<typeof RefinementPromptSchema>;
+export type TranscriptionProfile = z.infer<typeof TranscriptionProfileSchema>;
+export type ProcessorStep = z.infer<typeof ProcessorStepSchema>;
+export type ProcessingChain = z.infer<typeof ProcessingChainSchema>;
+export type SpokenCommand = z.infer<typeof SpokenCommandSchema>;
+
+export const RemoteModelConfigSchema = z.object({
+  name: z.string(),
+  endpoint: z.string(),
+  model: z.string().optional().default("whisper-1"),
+  api_key: z.string().nullable().optional(),
+  timeout_seconds: z.number().optional().default(30),
+  retries: z.number().optional().default(2),
+  fallback_model: z.string().nullable().optional(),
+});
+
+export type RemoteModelConfig = z.infer<typeof RemoteModelConfigSchema>;
+
+export const BenchmarkResultSchema = z.object({
+  model_id: z.string(),
+  backend: z.enum(["gpu", "cpu", "remote"]),
+  load_ms: z.number(),
+  real_time_factor: z.number(),
+  peak_memory_mb: z.number(),
+  word_error_rate: z.number(),
+  measured_at: z.number(),
+});
+
+export type BenchmarkResult = z.infer<typeof BenchmarkResultSchema>;
 
 export const ModelInfoSchema = z.object({
   id: z.string(),
@@ -58,6 +232,71 @@ export const ModelInfoSchema = z.object({
   is_downloaded: z.boolean(),
   is_downloading: z.boolean(),
   partial_size: z.number(),
+  engine: z.enum(["whisper", "remote"]).optional().default("whisper"),
+  benchmark: BenchmarkResultSchema.nullable().optional(),
 });
 
 export type ModelInfo = z.infer<typeof ModelInfoSchema>;
+
+export const TranscriptionModelStatusSchema = z.object({
+  model_id: z.string(),
+  backend: z.enum(["gpu", "cpu", "remote"]).nullable(),
+});
+
+export type TranscriptionModelStatus = z.infer<
+  typeof TranscriptionModelStatusSchema
+>;
+
+export const TranscriptionTokenSchema = z.object({
+  text: z.string(),
+  probability: z.number(),
+  start_ms: z.number().optional().default(0),
+  end_ms: z.number().optional().default(0),
+});
+
+export const TranscriptionWordSchema = z.object({
+  text: z.string(),
+  start_ms: z.number(),
+  end_ms: z.number(),
+  probability: z.number(),
+});
+
+export const TranscriptionSegmentSchema = z.object({
+  start_ms: z.number(),
+  end_ms: z.number(),
+  text: z.string(),
+  tokens: z.array(TranscriptionTokenSchema),
+  no_speech_probability: z.number().nullable().optional(),
+});
+
+export const FilterReasonSchema = z.enum([
+  "no_speech",
+  "repetitive",
+  "annotation",
+  "repeated",
+  "blocklisted",
+]);
+
+export const FilteredSegmentSchema = z.object({
+  start_ms: z.number(),
+  end_ms: z.number(),
+  text: z.string(),
+  reason: FilterReasonSchema,
+});
+
+// Payload of the "transcription-completed" event
+export const TranscriptionResultSchema = z.object({
+  text: z.string(),
+  segments: z.array(TranscriptionSegmentSchema),
+  language: z.string().nullable(),
+  language_probability: z.number().nullable(),
+  filtered: z.array(FilteredSegmentSchema).optional().default([]),
+  words: z.array(TranscriptionWordSchema).optional().default([]),
+});
+
+export type TranscriptionToken = z.infer<typeof TranscriptionTokenSchema>;
+export type TranscriptionWord = z.infer<typeof TranscriptionWordSchema>;
+export type TranscriptionSegment = z.infer<typeof TranscriptionSegmentSchema>;
+export type FilterReason = z.infer<typeof FilterReasonSchema>;
+export type FilteredSegment = z.infer<typeof FilteredSegmentSchema>;
+export type TranscriptionResult = z.infer<typeof TranscriptionResultSchema>;
@@ -8,28 +8,61 @@ import {
 } from "../components/icons";
 import "./RecordingOverlay.css";
 
-type OverlayState = "recording" | "transcribing";
+type OverlayState = "recording" | "transcribing" | "refining" | "language";
+
+// How long a newly selected language stays visible
+const LANGUAGE_DISPLAY_MS = 1500;
 
 const RecordingOverlay: React.FC = () => {
   const [isVisible, setIsVisible] = useState(false);
   const [state, setState] = useState<OverlayState>("recording");
   const [levels, setLevels] = useState<number[]>(Array(16).fill(0));
+  const [partialText, setPartialText] = useState("");
+  const [isModelLoading, setIsModelLoading] = useState(false);
+  const [queueDepth, setQueueDepth] = useState(0);
+  const [language, setLanguage] = useState<string | null>(null);
   const smoothedLevelsRef = useRef<number[]>(Array(16).fill(0));
+  const isVisibleRef = useRef(false);
+  const languageTimeoutRef = useRef<number | undefined>(undefined);
 
   useEffect(() => {
     const setupEventListeners = async () => {
       // Listen for show-overlay event from Rust
       const unlistenShow = await listen("show-overlay", (event) => {
         const overlayState = event.payload as OverlayState;
+        if (overlayState === "recording") {
+          setPartialText("");
+        }
         setState(overlayState);
         setIsVisible(true);
+        isVisibleRef.current = true;
       });
 
       // Listen for hide-overlay event from Rust
       const unlistenHide = await listen("hide-overlay", () => {
         setIsVisible(false);
+        isVisibleRef.current = false;
       });
 
+      // Listen for the cycle language shortcut. On its own the overlay shows
+      // the language, while recording it appears as a badge
+      const unlistenLanguage = await listen<string>(
+        "language-changed",
+        (event) => {
+          setLanguage(event.payload);
+          if (!isVisibleRef.current) {
+            setState("language");
+            setIsVisible(true);
+            isVisibleRef.current = true;
+          }
+          window.clearTimeout(languageTimeoutRef.current);
+          languageTimeoutRef.current = window.setTimeout(
+            () => setLanguage(null),
+            LANGUAGE_DISPLAY_MS,
+          );
+        },
+      );
+
       // Listen for mic-level updates
       const unlistenLevel = await listen<number[]>("mic-level", (event) => {
         const newLevels = event.payload as number[];
@@ -44,11 +77,47 @@ const RecordingOverlay: React.FC = () => {
         setLevels(smoothed.slice(0, 9));
       });
 
+      // Listen for streamed partial transcripts
+      const unlistenPartial = await listen<{ text: string }>(
+        "transcription-partial",
+        (event) => {
+          setPartialText(event.payload.text);
+        },
+      );
+
+      // Listen for model (re)loads, e.g. after the model was unloaded while idle
+      const unlistenModelState = await listen<{ event_type: string }>(
+        "model-state-changed",
+        (event) => {
+          const eventType = event.payload.event_type;
+          if (eventType === "loading_started") {
+            setIsModelLoading(true);
+          } else if (
+            eventType === "loading_completed" ||
+            eventType === "loading_failed"
+          ) {
+            setIsModelLoading(false);
+          }
+        },
+      );
+
+      // Listen for recordings waiting to be transcribed
+      const unlistenQueue = await listen<{ depth: number }>(
+        "transcription-queue-changed",
+        (event) => {
+          setQueueDepth(event.payload.depth);
+        },
+      );
+
       // Cleanup function
       return () => {
         unlistenShow();
         unlistenHide();
+        unlistenLanguage();
         unlistenLevel();
+        unlistenPartial();
+        unlistenModelState();
+        unlistenQueue();
       };
     };
 
@@ -58,6 +127,8 @@ const RecordingOverlay: React.FC = () => {
   const getIcon = () => {
     if (state === "recording") {
       return <MicrophoneIcon />;
+    } else if (state === "language") {
+      return null;
     } else {
       return <TranscriptionIcon />;
     }
@@ -65,10 +136,21 @@ const RecordingOverlay: React.FC = () => {
 
   return (
     <div className={`recording-overlay ${isVisible ? "fade-in" : ""}`}>
-      <div className="overlay-left">{getIcon()}</div>
+      <div className="overlay-left">
+        {getIcon()}
+        {state === "recording" && queueDepth > 0 && (
+          <div className="queue-badge">{queueDepth}</div>
+        )}
+        {state === "recording" && language && (
+          <div className="language-badge">{language}</div>
+        )}
+      </div>
 
       <div className="overlay-middle">
-        {state === "recording" && (
+        {state === "recording" && partialText && (
+          <div className="partial-text">{partialText}</div>
+        )}
+        {state === "recording" && !partialText && (
           <div className="bars-container">
             {levels.map((v, i) => (
               <div
@@ -83,8 +165,22 @@ const RecordingOverlay: React.FC = () => {
             ))}
           </div>
         )}
+        {state === "language" && (
+          <div className="language-text">
+            {language === "auto" ? "Auto-detect" : `Language: ${language}`}
+          </div>
+        )}
+        {state === "refining" && (
+          <div className="transcribing-text">Refining...</div>
+        )}
         {state === "transcribing" && (
-          <div className="transcribing-text">Transcribing...</div>
+          <div className="transcribing-text">
+            {isModelLoading
+              ? "Loading model..."
+              : queueDepth > 1
+                ? `Transcribing... (${queueDepth - 1} queued)`
+                : "Transcribing..."}
+          </div>
         )}
       </div>
 